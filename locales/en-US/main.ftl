@@ -23,3 +23,159 @@ op-subtract = Subtract
 op-multiply = Multiply
 op-divide = Divide
 op-power = Power
+
+# Function categories
+category-arithmetic = Arithmetic
+category-trigonometry = Trigonometry
+category-hyperbolic = Hyperbolic
+category-complex = Complex numbers
+category-statistics = Statistics
+category-financial = Financial
+category-bitwise = Bitwise
+category-number-theory = Number theory
+category-special = Special functions
+category-calculus = Calculus
+category-logic = Logic
+category-lists = Lists
+category-matrices = Matrices
+category-text = Text
+
+# Function descriptions
+fn-abs = Absolute value (magnitude for complex numbers)
+fn-sqrt = Square root
+fn-nthroot = Real nth root
+fn-log = Base-10 logarithm
+fn-ln = Natural logarithm
+fn-fact = Factorial
+fn-round = Round to a number of decimal digits
+fn-floor = Round down to an integer
+fn-ceil = Round up to an integer
+fn-trunc = Drop the fractional part
+fn-sin = Sine
+fn-cos = Cosine
+fn-tan = Tangent
+fn-asin = Inverse sine
+fn-acos = Inverse cosine
+fn-atan = Inverse tangent
+fn-deg = Convert an angle from radians to degrees
+fn-rad = Convert an angle from degrees to radians
+fn-grad = Convert an angle from radians to gradians
+fn-dms = Build an angle from degrees, minutes and seconds, or split one into D.MMSS
+fn-sinh = Hyperbolic sine
+fn-cosh = Hyperbolic cosine
+fn-tanh = Hyperbolic tangent
+fn-conj = Complex conjugate
+fn-re = Real part
+fn-im = Imaginary part
+fn-mean = Arithmetic mean
+fn-median = Median
+fn-var = Sample variance
+fn-std = Sample standard deviation
+fn-var-p = Population variance
+fn-stdev-p = Population standard deviation
+fn-mode = Most frequent value
+fn-percentile = Value below a fraction of the data, with selectable interpolation
+fn-quartile = Quartile from 0 (minimum) to 4 (maximum)
+fn-skew = Sample skewness
+fn-kurt = Sample excess kurtosis
+fn-geomean = Geometric mean
+fn-harmean = Harmonic mean
+fn-covar = Population covariance of paired data
+fn-covariance-s = Sample covariance of paired data
+fn-correl = Pearson correlation coefficient
+fn-slope = Slope of the least-squares line
+fn-intercept = Intercept of the least-squares line
+fn-rsq = Coefficient of determination of the least-squares line
+fn-forecast = Value of the least-squares line at x
+fn-norm-dist = Normal distribution density or cumulative probability
+fn-norm-inv = Inverse of the normal distribution
+fn-norm-s-dist = Standard normal density or cumulative probability
+fn-norm-s-inv = Inverse of the standard normal distribution
+fn-t-dist = Student's t density or left-tailed probability
+fn-t-dist-2t = Two-tailed Student's t probability
+fn-t-dist-rt = Right-tailed Student's t probability
+fn-t-inv = Left-tailed inverse of Student's t
+fn-t-inv-2t = Two-tailed inverse of Student's t
+fn-chisq-dist = Chi-squared density or left-tailed probability
+fn-chisq-dist-rt = Right-tailed chi-squared probability
+fn-chisq-inv = Left-tailed inverse of the chi-squared distribution
+fn-chisq-inv-rt = Right-tailed inverse of the chi-squared distribution
+fn-f-dist = F distribution density or left-tailed probability
+fn-f-dist-rt = Right-tailed F probability
+fn-f-inv = Left-tailed inverse of the F distribution
+fn-f-inv-rt = Right-tailed inverse of the F distribution
+fn-binom-dist = Binomial probability of k successes in n trials
+fn-binom-inv = Smallest number of successes reaching a cumulative probability
+fn-poisson-dist = Poisson probability of k events
+fn-poisson-inv = Smallest number of Poisson events reaching a cumulative probability
+fn-expon-dist = Exponential density or cumulative probability
+fn-expon-inv = Inverse of the exponential distribution
+fn-z-test = One-tailed p-value of a z-test
+fn-t-test = p-value of Student's t-test
+fn-confidence-norm = Confidence interval half-width with a known standard deviation
+fn-confidence-t = Confidence interval half-width using Student's t
+fn-fv = Future value of an investment
+fn-pv = Present value of an investment
+fn-pmt = Periodic payment of a loan
+fn-nper = Number of payment periods
+fn-rate = Interest rate per period
+fn-npv = Net present value of a series of cash flows
+fn-irr = Internal rate of return
+fn-band = Bitwise AND
+fn-bor = Bitwise OR
+fn-bxor = Bitwise exclusive OR
+fn-bnot = Bitwise NOT
+fn-lsh = Shift bits left
+fn-rsh = Shift bits right
+fn-rol = Rotate bits left
+fn-ror = Rotate bits right
+fn-gcd = Greatest common divisor
+fn-lcm = Least common multiple
+fn-ncr = Combinations of k out of n items
+fn-npr = Arrangements of k out of n items
+fn-binomial = Binomial coefficient, for any n
+fn-mod-pow = Power modulo m
+fn-mod-inv = Inverse modulo m
+fn-isprime = True if n is prime
+fn-nextprime = Smallest prime greater than n
+fn-factor = Prime factors, as a list or a product
+fn-totient = Euler's totient: integers up to n coprime to n
+fn-divisors = Positive divisors
+fn-fib = Fibonacci number
+fn-gamma = Gamma function
+fn-lgamma = Natural logarithm of the gamma function
+fn-beta = Beta function
+fn-erf = Error function
+fn-erfc = Complementary error function
+fn-zeta = Riemann zeta function
+fn-digamma = Digamma function, the derivative of ln Γ
+fn-besselj = Bessel function of the first kind
+fn-bessely = Bessel function of the second kind
+fn-deriv = Numeric derivative of a function, or of an expression in a variable, at a point
+fn-integrate = Numeric definite integral, with infinite bounds allowed
+fn-integrate-err = Estimated absolute error of the matching integrate call
+fn-sum = Sum of values or list elements, or of an expression over an integer range of its variable
+fn-prod = Product of values or list elements, or of an expression over an integer range of its variable
+fn-true = The value true
+fn-false = The value false
+fn-not = Logical NOT
+fn-and = True if every condition is true
+fn-or = True if any condition is true
+fn-xor = True if an odd number of conditions are true
+fn-if = Choose a value depending on a condition
+fn-iferror = A fallback value when the first argument fails or is not finite
+fn-len = Number of elements in a list or characters in a text
+fn-range = Values from start to stop, step apart
+fn-matrix = Matrix from a list of rows
+fn-transpose = Rows and columns swapped
+fn-det = Determinant of a square matrix
+fn-inv = Inverse of a square matrix
+fn-rank = Number of linearly independent rows
+fn-rref = Reduced row echelon form
+fn-solve = Solution x of A·x = b
+fn-identity = Identity matrix of size n
+fn-eigenvalues = Eigenvalues of a square matrix
+fn-text = A number written as text, optionally in a format
+fn-value = The number written in a text
+fn-char = The character with a Unicode code point
+fn-code = The Unicode code point of the first character
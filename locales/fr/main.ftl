@@ -23,3 +23,159 @@ op-subtract = Soustraire
 op-multiply = Multiplier
 op-divide = Diviser
 op-power = Puissance
+
+# Catégories de fonctions
+category-arithmetic = Arithmétique
+category-trigonometry = Trigonométrie
+category-hyperbolic = Hyperboliques
+category-complex = Nombres complexes
+category-statistics = Statistiques
+category-financial = Financières
+category-bitwise = Opérations bit à bit
+category-number-theory = Théorie des nombres
+category-special = Fonctions spéciales
+category-calculus = Analyse
+category-logic = Logique
+category-lists = Listes
+category-matrices = Matrices
+category-text = Texte
+
+# Descriptions des fonctions
+fn-abs = Valeur absolue (module pour les nombres complexes)
+fn-sqrt = Racine carrée
+fn-nthroot = Racine n-ième réelle
+fn-log = Logarithme décimal
+fn-ln = Logarithme népérien
+fn-fact = Factorielle
+fn-round = Arrondit à un nombre de décimales
+fn-floor = Arrondit à l'entier inférieur
+fn-ceil = Arrondit à l'entier supérieur
+fn-trunc = Supprime la partie décimale
+fn-sin = Sinus
+fn-cos = Cosinus
+fn-tan = Tangente
+fn-asin = Arc sinus
+fn-acos = Arc cosinus
+fn-atan = Arc tangente
+fn-deg = Convertit un angle de radians en degrés
+fn-rad = Convertit un angle de degrés en radians
+fn-grad = Convertit un angle de radians en grades
+fn-dms = Construit un angle à partir de degrés, minutes et secondes, ou le décompose en D.MMSS
+fn-sinh = Sinus hyperbolique
+fn-cosh = Cosinus hyperbolique
+fn-tanh = Tangente hyperbolique
+fn-conj = Conjugué complexe
+fn-re = Partie réelle
+fn-im = Partie imaginaire
+fn-mean = Moyenne arithmétique
+fn-median = Médiane
+fn-var = Variance d'échantillon
+fn-std = Écart type d'échantillon
+fn-var-p = Variance de la population
+fn-stdev-p = Écart type de la population
+fn-mode = Valeur la plus fréquente
+fn-percentile = Valeur sous une fraction des données, avec interpolation au choix
+fn-quartile = Quartile de 0 (minimum) à 4 (maximum)
+fn-skew = Coefficient d'asymétrie d'échantillon
+fn-kurt = Kurtosis excédentaire d'échantillon
+fn-geomean = Moyenne géométrique
+fn-harmean = Moyenne harmonique
+fn-covar = Covariance de la population de données appariées
+fn-covariance-s = Covariance d'échantillon de données appariées
+fn-correl = Coefficient de corrélation de Pearson
+fn-slope = Pente de la droite des moindres carrés
+fn-intercept = Ordonnée à l'origine de la droite des moindres carrés
+fn-rsq = Coefficient de détermination de la droite des moindres carrés
+fn-forecast = Valeur de la droite des moindres carrés en x
+fn-norm-dist = Densité ou probabilité cumulée de la loi normale
+fn-norm-inv = Inverse de la loi normale
+fn-norm-s-dist = Densité ou probabilité cumulée de la loi normale centrée réduite
+fn-norm-s-inv = Inverse de la loi normale centrée réduite
+fn-t-dist = Densité ou probabilité à gauche de la loi de Student
+fn-t-dist-2t = Probabilité bilatérale de la loi de Student
+fn-t-dist-rt = Probabilité à droite de la loi de Student
+fn-t-inv = Inverse à gauche de la loi de Student
+fn-t-inv-2t = Inverse bilatérale de la loi de Student
+fn-chisq-dist = Densité ou probabilité à gauche du khi-deux
+fn-chisq-dist-rt = Probabilité à droite du khi-deux
+fn-chisq-inv = Inverse à gauche de la loi du khi-deux
+fn-chisq-inv-rt = Inverse à droite de la loi du khi-deux
+fn-f-dist = Densité ou probabilité à gauche de la loi F
+fn-f-dist-rt = Probabilité à droite de la loi F
+fn-f-inv = Inverse à gauche de la loi F
+fn-f-inv-rt = Inverse à droite de la loi F
+fn-binom-dist = Probabilité binomiale de k succès en n essais
+fn-binom-inv = Plus petit nombre de succès atteignant une probabilité cumulée
+fn-poisson-dist = Probabilité de Poisson de k événements
+fn-poisson-inv = Plus petit nombre d'événements de Poisson atteignant une probabilité cumulée
+fn-expon-dist = Densité ou probabilité cumulée exponentielle
+fn-expon-inv = Inverse de la loi exponentielle
+fn-z-test = Valeur p unilatérale d'un test z
+fn-t-test = Valeur p du test t de Student
+fn-confidence-norm = Demi-largeur de l'intervalle de confiance avec écart type connu
+fn-confidence-t = Demi-largeur de l'intervalle de confiance selon la loi de Student
+fn-fv = Valeur future d'un investissement
+fn-pv = Valeur actuelle d'un investissement
+fn-pmt = Versement périodique d'un emprunt
+fn-nper = Nombre de périodes de versement
+fn-rate = Taux d'intérêt par période
+fn-npv = Valeur actuelle nette d'une série de flux de trésorerie
+fn-irr = Taux de rendement interne
+fn-band = ET bit à bit
+fn-bor = OU bit à bit
+fn-bxor = OU exclusif bit à bit
+fn-bnot = NON bit à bit
+fn-lsh = Décale les bits vers la gauche
+fn-rsh = Décale les bits vers la droite
+fn-rol = Rotation des bits vers la gauche
+fn-ror = Rotation des bits vers la droite
+fn-gcd = Plus grand commun diviseur
+fn-lcm = Plus petit commun multiple
+fn-ncr = Combinaisons de k parmi n éléments
+fn-npr = Arrangements de k parmi n éléments
+fn-binomial = Coefficient binomial, pour tout n
+fn-mod-pow = Puissance modulo m
+fn-mod-inv = Inverse modulo m
+fn-isprime = Vrai si n est premier
+fn-nextprime = Plus petit nombre premier supérieur à n
+fn-factor = Facteurs premiers, en liste ou en produit
+fn-totient = Indicatrice d'Euler : entiers jusqu'à n premiers avec n
+fn-divisors = Diviseurs positifs
+fn-fib = Nombre de Fibonacci
+fn-gamma = Fonction gamma
+fn-lgamma = Logarithme népérien de la fonction gamma
+fn-beta = Fonction bêta
+fn-erf = Fonction d'erreur
+fn-erfc = Fonction d'erreur complémentaire
+fn-zeta = Fonction zêta de Riemann
+fn-digamma = Fonction digamma, la dérivée de ln Γ
+fn-besselj = Fonction de Bessel de première espèce
+fn-bessely = Fonction de Bessel de deuxième espèce
+fn-deriv = Dérivée numérique d'une fonction, ou d'une expression en une variable, en un point
+fn-integrate = Intégrale définie numérique, bornes infinies admises
+fn-integrate-err = Erreur absolue estimée de l'appel integrate correspondant
+fn-sum = Somme de valeurs ou des éléments d'une liste, ou d'une expression sur un intervalle entier de sa variable
+fn-prod = Produit de valeurs ou des éléments d'une liste, ou d'une expression sur un intervalle entier de sa variable
+fn-true = La valeur vrai
+fn-false = La valeur faux
+fn-not = NON logique
+fn-and = Vrai si toutes les conditions sont vraies
+fn-or = Vrai si au moins une condition est vraie
+fn-xor = Vrai si un nombre impair de conditions sont vraies
+fn-if = Choisit une valeur selon une condition
+fn-iferror = Une valeur de repli lorsque le premier argument échoue ou n'est pas fini
+fn-len = Nombre d'éléments d'une liste ou de caractères d'un texte
+fn-range = Valeurs du début à la fin, espacées du pas
+fn-matrix = Matrice à partir d'une liste de lignes
+fn-transpose = Lignes et colonnes échangées
+fn-det = Déterminant d'une matrice carrée
+fn-inv = Inverse d'une matrice carrée
+fn-rank = Nombre de lignes linéairement indépendantes
+fn-rref = Forme échelonnée réduite
+fn-solve = Solution x de A·x = b
+fn-identity = Matrice identité de taille n
+fn-eigenvalues = Valeurs propres d'une matrice carrée
+fn-text = Un nombre écrit en texte, éventuellement dans un format
+fn-value = Le nombre écrit dans un texte
+fn-char = Le caractère d'un point de code Unicode
+fn-code = Le point de code Unicode du premier caractère
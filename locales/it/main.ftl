@@ -23,3 +23,159 @@ op-subtract = Sottrai
 op-multiply = Moltiplica
 op-divide = Dividi
 op-power = Potenza
+
+# Categorie di funzioni
+category-arithmetic = Aritmetica
+category-trigonometry = Trigonometria
+category-hyperbolic = Iperboliche
+category-complex = Numeri complessi
+category-statistics = Statistica
+category-financial = Finanziarie
+category-bitwise = Operazioni bit a bit
+category-number-theory = Teoria dei numeri
+category-special = Funzioni speciali
+category-calculus = Analisi
+category-logic = Logica
+category-lists = Liste
+category-matrices = Matrici
+category-text = Testo
+
+# Descrizioni delle funzioni
+fn-abs = Valore assoluto (modulo per i numeri complessi)
+fn-sqrt = Radice quadrata
+fn-nthroot = Radice n-esima reale
+fn-log = Logaritmo in base 10
+fn-ln = Logaritmo naturale
+fn-fact = Fattoriale
+fn-round = Arrotonda a un numero di cifre decimali
+fn-floor = Arrotonda per difetto a un intero
+fn-ceil = Arrotonda per eccesso a un intero
+fn-trunc = Elimina la parte frazionaria
+fn-sin = Seno
+fn-cos = Coseno
+fn-tan = Tangente
+fn-asin = Arcoseno
+fn-acos = Arcocoseno
+fn-atan = Arcotangente
+fn-deg = Converte un angolo da radianti a gradi
+fn-rad = Converte un angolo da gradi a radianti
+fn-grad = Converte un angolo da radianti a gradienti
+fn-dms = Costruisce un angolo da gradi, primi e secondi, o lo scompone in G.MMSS
+fn-sinh = Seno iperbolico
+fn-cosh = Coseno iperbolico
+fn-tanh = Tangente iperbolica
+fn-conj = Coniugato complesso
+fn-re = Parte reale
+fn-im = Parte immaginaria
+fn-mean = Media aritmetica
+fn-median = Mediana
+fn-var = Varianza campionaria
+fn-std = Deviazione standard campionaria
+fn-var-p = Varianza della popolazione
+fn-stdev-p = Deviazione standard della popolazione
+fn-mode = Valore più frequente
+fn-percentile = Valore sotto una frazione dei dati, con interpolazione selezionabile
+fn-quartile = Quartile da 0 (minimo) a 4 (massimo)
+fn-skew = Asimmetria campionaria
+fn-kurt = Curtosi in eccesso campionaria
+fn-geomean = Media geometrica
+fn-harmean = Media armonica
+fn-covar = Covarianza della popolazione di dati appaiati
+fn-covariance-s = Covarianza campionaria di dati appaiati
+fn-correl = Coefficiente di correlazione di Pearson
+fn-slope = Pendenza della retta dei minimi quadrati
+fn-intercept = Intercetta della retta dei minimi quadrati
+fn-rsq = Coefficiente di determinazione della retta dei minimi quadrati
+fn-forecast = Valore della retta dei minimi quadrati in x
+fn-norm-dist = Densità o probabilità cumulata della distribuzione normale
+fn-norm-inv = Inversa della distribuzione normale
+fn-norm-s-dist = Densità o probabilità cumulata della normale standard
+fn-norm-s-inv = Inversa della distribuzione normale standard
+fn-t-dist = Densità o probabilità a sinistra della t di Student
+fn-t-dist-2t = Probabilità a due code della t di Student
+fn-t-dist-rt = Probabilità a destra della t di Student
+fn-t-inv = Inversa a sinistra della t di Student
+fn-t-inv-2t = Inversa a due code della t di Student
+fn-chisq-dist = Densità o probabilità a sinistra del chi quadrato
+fn-chisq-dist-rt = Probabilità a destra del chi quadrato
+fn-chisq-inv = Inversa a sinistra della distribuzione chi quadrato
+fn-chisq-inv-rt = Inversa a destra della distribuzione chi quadrato
+fn-f-dist = Densità o probabilità a sinistra della distribuzione F
+fn-f-dist-rt = Probabilità a destra della distribuzione F
+fn-f-inv = Inversa a sinistra della distribuzione F
+fn-f-inv-rt = Inversa a destra della distribuzione F
+fn-binom-dist = Probabilità binomiale di k successi in n prove
+fn-binom-inv = Minimo numero di successi che raggiunge una probabilità cumulata
+fn-poisson-dist = Probabilità di Poisson di k eventi
+fn-poisson-inv = Minimo numero di eventi di Poisson che raggiunge una probabilità cumulata
+fn-expon-dist = Densità o probabilità cumulata esponenziale
+fn-expon-inv = Inversa della distribuzione esponenziale
+fn-z-test = Valore p a una coda di un test z
+fn-t-test = Valore p del test t di Student
+fn-confidence-norm = Semiampiezza dell'intervallo di confidenza con deviazione standard nota
+fn-confidence-t = Semiampiezza dell'intervallo di confidenza con la t di Student
+fn-fv = Valore futuro di un investimento
+fn-pv = Valore attuale di un investimento
+fn-pmt = Rata periodica di un prestito
+fn-nper = Numero di periodi di pagamento
+fn-rate = Tasso di interesse per periodo
+fn-npv = Valore attuale netto di una serie di flussi di cassa
+fn-irr = Tasso interno di rendimento
+fn-band = AND bit a bit
+fn-bor = OR bit a bit
+fn-bxor = OR esclusivo bit a bit
+fn-bnot = NOT bit a bit
+fn-lsh = Sposta i bit a sinistra
+fn-rsh = Sposta i bit a destra
+fn-rol = Ruota i bit a sinistra
+fn-ror = Ruota i bit a destra
+fn-gcd = Massimo comune divisore
+fn-lcm = Minimo comune multiplo
+fn-ncr = Combinazioni di k su n elementi
+fn-npr = Disposizioni di k su n elementi
+fn-binomial = Coefficiente binomiale, per qualsiasi n
+fn-mod-pow = Potenza modulo m
+fn-mod-inv = Inverso modulo m
+fn-isprime = Vero se n è primo
+fn-nextprime = Il più piccolo primo maggiore di n
+fn-factor = Fattori primi, come lista o prodotto
+fn-totient = Funzione phi di Eulero: interi fino a n coprimi con n
+fn-divisors = Divisori positivi
+fn-fib = Numero di Fibonacci
+fn-gamma = Funzione gamma
+fn-lgamma = Logaritmo naturale della funzione gamma
+fn-beta = Funzione beta
+fn-erf = Funzione degli errori
+fn-erfc = Funzione degli errori complementare
+fn-zeta = Funzione zeta di Riemann
+fn-digamma = Funzione digamma, la derivata di ln Γ
+fn-besselj = Funzione di Bessel di prima specie
+fn-bessely = Funzione di Bessel di seconda specie
+fn-deriv = Derivata numerica di una funzione, o di un'espressione in una variabile, in un punto
+fn-integrate = Integrale definito numerico, anche con estremi infiniti
+fn-integrate-err = Errore assoluto stimato della chiamata integrate corrispondente
+fn-sum = Somma di valori o degli elementi di una lista, o di un'espressione su un intervallo intero della sua variabile
+fn-prod = Prodotto di valori o degli elementi di una lista, o di un'espressione su un intervallo intero della sua variabile
+fn-true = Il valore vero
+fn-false = Il valore falso
+fn-not = NOT logico
+fn-and = Vero se tutte le condizioni sono vere
+fn-or = Vero se almeno una condizione è vera
+fn-xor = Vero se un numero dispari di condizioni è vero
+fn-if = Sceglie un valore in base a una condizione
+fn-iferror = Un valore alternativo quando il primo argomento fallisce o non è finito
+fn-len = Numero di elementi di una lista o di caratteri di un testo
+fn-range = Valori dall'inizio alla fine, distanziati del passo
+fn-matrix = Matrice da una lista di righe
+fn-transpose = Righe e colonne scambiate
+fn-det = Determinante di una matrice quadrata
+fn-inv = Inversa di una matrice quadrata
+fn-rank = Numero di righe linearmente indipendenti
+fn-rref = Forma a scala ridotta per righe
+fn-solve = Soluzione x di A·x = b
+fn-identity = Matrice identità di ordine n
+fn-eigenvalues = Autovalori di una matrice quadrata
+fn-text = Un numero scritto come testo, eventualmente in un formato
+fn-value = Il numero scritto in un testo
+fn-char = Il carattere di un punto di codice Unicode
+fn-code = Il punto di codice Unicode del primo carattere
@@ -23,3 +23,159 @@ op-subtract = Subtrair
 op-multiply = Multiplicar
 op-divide = Dividir
 op-power = Potência
+
+# Categorias de funções
+category-arithmetic = Aritmética
+category-trigonometry = Trigonometria
+category-hyperbolic = Hiperbólicas
+category-complex = Números complexos
+category-statistics = Estatística
+category-financial = Financeiras
+category-bitwise = Bit a bit
+category-number-theory = Teoria dos números
+category-special = Funções especiais
+category-calculus = Cálculo
+category-logic = Lógica
+category-lists = Listas
+category-matrices = Matrizes
+category-text = Texto
+
+# Descrições das funções
+fn-abs = Valor absoluto (módulo para números complexos)
+fn-sqrt = Raiz quadrada
+fn-nthroot = Raiz n-ésima real
+fn-log = Logaritmo na base 10
+fn-ln = Logaritmo natural
+fn-fact = Fatorial
+fn-round = Arredonda para um número de casas decimais
+fn-floor = Arredonda para baixo até um inteiro
+fn-ceil = Arredonda para cima até um inteiro
+fn-trunc = Descarta a parte fracionária
+fn-sin = Seno
+fn-cos = Cosseno
+fn-tan = Tangente
+fn-asin = Arco seno
+fn-acos = Arco cosseno
+fn-atan = Arco tangente
+fn-deg = Converte um ângulo de radianos para graus
+fn-rad = Converte um ângulo de graus para radianos
+fn-grad = Converte um ângulo de radianos para grados
+fn-dms = Monta um ângulo a partir de graus, minutos e segundos, ou o divide em G.MMSS
+fn-sinh = Seno hiperbólico
+fn-cosh = Cosseno hiperbólico
+fn-tanh = Tangente hiperbólica
+fn-conj = Conjugado complexo
+fn-re = Parte real
+fn-im = Parte imaginária
+fn-mean = Média aritmética
+fn-median = Mediana
+fn-var = Variância amostral
+fn-std = Desvio padrão amostral
+fn-var-p = Variância populacional
+fn-stdev-p = Desvio padrão populacional
+fn-mode = Valor mais frequente
+fn-percentile = Valor abaixo de uma fração dos dados, com interpolação selecionável
+fn-quartile = Quartil de 0 (mínimo) a 4 (máximo)
+fn-skew = Assimetria amostral
+fn-kurt = Curtose em excesso amostral
+fn-geomean = Média geométrica
+fn-harmean = Média harmônica
+fn-covar = Covariância populacional de dados pareados
+fn-covariance-s = Covariância amostral de dados pareados
+fn-correl = Coeficiente de correlação de Pearson
+fn-slope = Inclinação da reta de mínimos quadrados
+fn-intercept = Intercepto da reta de mínimos quadrados
+fn-rsq = Coeficiente de determinação da reta de mínimos quadrados
+fn-forecast = Valor da reta de mínimos quadrados em x
+fn-norm-dist = Densidade ou probabilidade acumulada da distribuição normal
+fn-norm-inv = Inversa da distribuição normal
+fn-norm-s-dist = Densidade ou probabilidade acumulada da normal padrão
+fn-norm-s-inv = Inversa da distribuição normal padrão
+fn-t-dist = Densidade ou probabilidade à esquerda da t de Student
+fn-t-dist-2t = Probabilidade bicaudal da t de Student
+fn-t-dist-rt = Probabilidade à direita da t de Student
+fn-t-inv = Inversa à esquerda da t de Student
+fn-t-inv-2t = Inversa bicaudal da t de Student
+fn-chisq-dist = Densidade ou probabilidade à esquerda da qui-quadrado
+fn-chisq-dist-rt = Probabilidade à direita da qui-quadrado
+fn-chisq-inv = Inversa à esquerda da distribuição qui-quadrado
+fn-chisq-inv-rt = Inversa à direita da distribuição qui-quadrado
+fn-f-dist = Densidade ou probabilidade à esquerda da distribuição F
+fn-f-dist-rt = Probabilidade à direita da distribuição F
+fn-f-inv = Inversa à esquerda da distribuição F
+fn-f-inv-rt = Inversa à direita da distribuição F
+fn-binom-dist = Probabilidade binomial de k sucessos em n tentativas
+fn-binom-inv = Menor número de sucessos que atinge uma probabilidade acumulada
+fn-poisson-dist = Probabilidade de Poisson de k eventos
+fn-poisson-inv = Menor número de eventos de Poisson que atinge uma probabilidade acumulada
+fn-expon-dist = Densidade ou probabilidade acumulada exponencial
+fn-expon-inv = Inversa da distribuição exponencial
+fn-z-test = Valor-p unicaudal de um teste z
+fn-t-test = Valor-p do teste t de Student
+fn-confidence-norm = Meia largura do intervalo de confiança com desvio padrão conhecido
+fn-confidence-t = Meia largura do intervalo de confiança pela t de Student
+fn-fv = Valor futuro de um investimento
+fn-pv = Valor presente de um investimento
+fn-pmt = Pagamento periódico de um empréstimo
+fn-nper = Número de períodos de pagamento
+fn-rate = Taxa de juros por período
+fn-npv = Valor presente líquido de uma série de fluxos de caixa
+fn-irr = Taxa interna de retorno
+fn-band = E bit a bit
+fn-bor = OU bit a bit
+fn-bxor = OU exclusivo bit a bit
+fn-bnot = NÃO bit a bit
+fn-lsh = Desloca bits para a esquerda
+fn-rsh = Desloca bits para a direita
+fn-rol = Rotaciona bits para a esquerda
+fn-ror = Rotaciona bits para a direita
+fn-gcd = Máximo divisor comum
+fn-lcm = Mínimo múltiplo comum
+fn-ncr = Combinações de k entre n itens
+fn-npr = Arranjos de k entre n itens
+fn-binomial = Coeficiente binomial, para qualquer n
+fn-mod-pow = Potência módulo m
+fn-mod-inv = Inverso módulo m
+fn-isprime = Verdadeiro se n for primo
+fn-nextprime = Menor primo maior que n
+fn-factor = Fatores primos, como lista ou produto
+fn-totient = Função totiente de Euler: inteiros até n primos com n
+fn-divisors = Divisores positivos
+fn-fib = Número de Fibonacci
+fn-gamma = Função gama
+fn-lgamma = Logaritmo natural da função gama
+fn-beta = Função beta
+fn-erf = Função erro
+fn-erfc = Função erro complementar
+fn-zeta = Função zeta de Riemann
+fn-digamma = Função digama, a derivada de ln Γ
+fn-besselj = Função de Bessel de primeira espécie
+fn-bessely = Função de Bessel de segunda espécie
+fn-deriv = Derivada numérica de uma função, ou de uma expressão em uma variável, em um ponto
+fn-integrate = Integral definida numérica, aceitando limites infinitos
+fn-integrate-err = Erro absoluto estimado da chamada integrate correspondente
+fn-sum = Soma de valores ou dos elementos de uma lista, ou de uma expressão em um intervalo inteiro da sua variável
+fn-prod = Produto de valores ou dos elementos de uma lista, ou de uma expressão em um intervalo inteiro da sua variável
+fn-true = O valor verdadeiro
+fn-false = O valor falso
+fn-not = NÃO lógico
+fn-and = Verdadeiro se todas as condições forem verdadeiras
+fn-or = Verdadeiro se alguma condição for verdadeira
+fn-xor = Verdadeiro se um número ímpar de condições for verdadeiro
+fn-if = Escolhe um valor de acordo com uma condição
+fn-iferror = Um valor alternativo quando o primeiro argumento falha ou não é finito
+fn-len = Número de elementos de uma lista ou de caracteres de um texto
+fn-range = Valores de início a fim, separados pelo passo
+fn-matrix = Matriz a partir de uma lista de linhas
+fn-transpose = Linhas e colunas trocadas
+fn-det = Determinante de uma matriz quadrada
+fn-inv = Inversa de uma matriz quadrada
+fn-rank = Número de linhas linearmente independentes
+fn-rref = Forma escalonada reduzida por linhas
+fn-solve = Solução x de A·x = b
+fn-identity = Matriz identidade de ordem n
+fn-eigenvalues = Autovalores de uma matriz quadrada
+fn-text = Um número escrito como texto, opcionalmente em um formato
+fn-value = O número escrito em um texto
+fn-char = O caractere de um código Unicode
+fn-code = O código Unicode do primeiro caractere
@@ -0,0 +1,172 @@
+//! Angle units for trigonometry.
+//!
+//! `Context::angle_unit` decides how the trigonometric functions read their
+//! arguments and how the inverse functions report their results. Literals can
+//! name their unit explicitly (`30°`, `30deg`, `0.5rad`, `50grad`), which
+//! converts them into the context's unit so `sin(30°)` works in every mode.
+
+use super::bigfloat::BigFloat;
+use super::types::{Number, from_ratio};
+use num::ToPrimitive;
+use num_rational::BigRational;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AngleUnit {
+    #[default]
+    Radians,
+    Degrees,
+    Gradians,
+}
+
+impl AngleUnit {
+    /// Recognizes the postfix unit names accepted after a number literal.
+    pub fn from_suffix(name: &str) -> Option<Self> {
+        match name {
+            "rad" => Some(AngleUnit::Radians),
+            "deg" | "°" => Some(AngleUnit::Degrees),
+            "grad" => Some(AngleUnit::Gradians),
+            _ => None,
+        }
+    }
+
+    // Size of a full turn, exact for degrees and gradians
+    fn full_turn(self) -> Option<BigRational> {
+        match self {
+            AngleUnit::Radians => None,
+            AngleUnit::Degrees => Some(BigRational::from_integer(360.into())),
+            AngleUnit::Gradians => Some(BigRational::from_integer(400.into())),
+        }
+    }
+
+    /// Converts an angle measured in `self` into `target`. Conversions
+    /// between degrees and gradians are exact; anything involving radians
+    /// goes through π (at the working precision for `BigFloat` values).
+    pub fn convert(self, value: Number, target: AngleUnit) -> Number {
+        if self == target {
+            return value;
+        }
+        let (from, to) = (self.full_turn(), target.full_turn());
+        if let (Some(from), Some(to)) = (&from, &to)
+            && let Some(exact) = exact_value(&value)
+        {
+            return from_ratio(exact * to / from);
+        }
+        // One side is radians: scale by a full turn over 2π
+        let scale = |precision: Option<u32>| -> Number {
+            let turn = |unit: &Option<BigRational>| match (unit, precision) {
+                (Some(r), _) => Number::Rational(r.clone()),
+                (None, Some(p)) => {
+                    Number::BigFloat(BigFloat::pi(p).mul(&BigFloat::from_integer(2.into(), p)))
+                }
+                (None, None) => Number::Float(2.0 * PI),
+            };
+            turn(&to) / turn(&from)
+        };
+        match &value {
+            Number::BigFloat(b) => value.clone() * scale(Some(b.precision())),
+            _ => value * scale(None),
+        }
+    }
+
+    /// The angle as a whole number of 24ths of a turn (multiples of 15°), if
+    /// it is one. Degree and gradian values are checked exactly; radians up to
+    /// float rounding, so `sin(pi/6)` and `sin(30°)` both hit the exact table.
+    /// The rounding allowance is relative, so only an exact zero is 0: tiny
+    /// angles such as `sin(1e-16)` keep their value.
+    pub fn twenty_fourths(self, value: &Number) -> Option<i64> {
+        match self.full_turn() {
+            Some(turn) => {
+                let k = exact_value(value)? * BigRational::from_integer(24.into()) / turn;
+                if k.is_integer() {
+                    k.to_integer().to_i64()
+                } else {
+                    None
+                }
+            }
+            None => match value {
+                Number::BigFloat(b) => {
+                    let p = b.precision();
+                    let step =
+                        BigFloat::pi(p + 5).div(&BigFloat::from_integer(12.into(), p + 5))?;
+                    let k = b.with_precision(p + 5).div(&step)?;
+                    let rounded = k.round_to(0);
+                    let tolerance =
+                        BigFloat::parse(&format!("1e-{}", p.saturating_sub(2).max(1)), p)?
+                            .mul(&rounded.abs());
+                    if k.sub(&rounded).abs() <= tolerance {
+                        Some(rounded.to_f64())
+                            .filter(|r| r.abs() < 1e9)
+                            .map(|r| r as i64)
+                    } else {
+                        None
+                    }
+                }
+                Number::Complex(c) if c.im != 0.0 => None,
+                _ => {
+                    let k = value.to_f64()? / (PI / 12.0);
+                    let rounded = k.round();
+                    let tolerance = 4.0 * f64::EPSILON * rounded.abs();
+                    if rounded.abs() < 1e9 && (k - rounded).abs() <= tolerance {
+                        Some(rounded as i64)
+                    } else {
+                        None
+                    }
+                }
+            },
+        }
+    }
+
+    /// An exact angle of `k` 24ths of a turn in this unit, or `None` for
+    /// radians where it is irrational.
+    pub fn from_twenty_fourths(self, k: i64) -> Option<Number> {
+        let turn = self.full_turn()?;
+        Some(from_ratio(
+            turn * BigRational::from_integer(k.into()) / BigRational::from_integer(24.into()),
+        ))
+    }
+}
+
+// Exact value of a real number (floats by their binary value)
+fn exact_value(n: &Number) -> Option<BigRational> {
+    match n {
+        Number::Integer(i) => Some(BigRational::from_integer(i.clone())),
+        Number::Rational(r) => Some(r.clone()),
+        Number::Float(f) => BigRational::from_float(*f),
+        Number::BigFloat(b) => Some(b.to_rational()),
+        Number::Complex(c) if c.im == 0.0 => BigRational::from_float(c.re),
+        Number::Complex(_)
+        | Number::Boolean(_)
+        | Number::List(_)
+        | Number::Matrix(_)
+        | Number::Text(_)
+        | Number::Quantity(_) => None,
+    }
+}
+
+impl fmt::Display for AngleUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AngleUnit::Radians => "RAD",
+            AngleUnit::Degrees => "DEG",
+            AngleUnit::Gradians => "GRAD",
+        })
+    }
+}
+
+impl FromStr for AngleUnit {
+    type Err = String;
+
+    /// Accepts the display names (`DEG`) as well as the literal suffixes.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "rad" | "radians" => Ok(AngleUnit::Radians),
+            "deg" | "degrees" => Ok(AngleUnit::Degrees),
+            "grad" | "gradians" | "gon" => Ok(AngleUnit::Gradians),
+            _ => Err(format!("Unknown angle unit '{}'", s)),
+        }
+    }
+}
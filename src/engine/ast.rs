@@ -1,9 +1,22 @@
+use super::angle::AngleUnit;
+use super::bigfloat::MAX_PRECISION;
+use super::constants;
+use super::currency::RateTable;
 use super::errors::EngineError;
 use super::functions;
-use super::types::{Number, factorial, pow};
+use super::limits::{CancellationToken, EvalLimits, EvalState, Guard};
+use super::matrix::Matrix;
+use super::native::NativeFunction;
+use super::special_forms::SpecialForm;
+use super::types::{Number, elementwise, factorial, map_elements};
+use super::units;
+use super::word::{self, WordSize};
+use num::{Signed, ToPrimitive};
 use serde::{Deserialize, Serialize};
+use std::cell::Cell;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserFunction {
@@ -15,6 +28,33 @@ pub struct UserFunction {
 pub struct Context {
     pub scopes: Vec<HashMap<String, Arc<Number>>>,
     pub functions: HashMap<String, UserFunction>,
+    #[serde(default)]
+    pub limits: EvalLimits,
+    /// Working precision in significant decimal digits. `None` keeps the
+    /// machine float behavior; with `Some(p)` decimal literals, constants and
+    /// the real transcendental functions produce `Number::BigFloat`.
+    #[serde(default)]
+    precision: Option<u32>,
+    /// Unit in which trigonometric functions take and return angles.
+    #[serde(default)]
+    pub angle_unit: AngleUnit,
+    /// Exchange rates for currency units, supplied by the host and saved
+    /// with the context so that conversions keep working offline.
+    #[serde(default)]
+    pub rates: Option<RateTable>,
+    /// Programmer mode: integer results wrap into this word. `None` keeps
+    /// integers unbounded.
+    #[serde(default)]
+    pub word_size: Option<WordSize>,
+    /// Shared with the host so it can abort evaluation from another thread.
+    /// Stays cancelled until the host calls `reset()` on it.
+    #[serde(skip)]
+    pub cancel: CancellationToken,
+    #[serde(skip)]
+    state: EvalState,
+    /// Host functions (see `engine::native`); closures are not serialized.
+    #[serde(skip)]
+    natives: HashMap<String, NativeFunction>,
 }
 
 impl Default for Context {
@@ -22,6 +62,14 @@ impl Default for Context {
         Self {
             scopes: vec![HashMap::new()],
             functions: HashMap::new(),
+            limits: EvalLimits::default(),
+            precision: None,
+            angle_unit: AngleUnit::default(),
+            rates: None,
+            word_size: None,
+            cancel: CancellationToken::new(),
+            state: EvalState::default(),
+            natives: HashMap::new(),
         }
     }
 }
@@ -31,6 +79,185 @@ impl Context {
         Self::default()
     }
 
+    pub fn precision(&self) -> Option<u32> {
+        self.precision
+    }
+
+    /// Sets the working precision, or returns to machine floats with `None`.
+    pub fn set_precision(&mut self, precision: Option<u32>) -> Result<(), EngineError> {
+        if let Some(p) = precision
+            && !(1..=MAX_PRECISION).contains(&p)
+        {
+            return Err(EngineError::DomainError(format!(
+                "Precision must be between 1 and {} digits",
+                MAX_PRECISION
+            )));
+        }
+        self.precision = precision;
+        Ok(())
+    }
+
+    /// The precision an operation on `args` should use: the context's working
+    /// precision or the widest `BigFloat` among the arguments, if either is set.
+    pub fn precision_for(&self, args: &[Number]) -> Option<u32> {
+        args.iter()
+            .filter_map(|n| match n {
+                Number::BigFloat(b) => Some(b.precision()),
+                _ => None,
+            })
+            .chain(self.precision)
+            .max()
+    }
+
+    /// Registers a host function for this context, replacing any native of
+    /// the same name. Names match case-insensitively, like the builtins.
+    pub fn register_native(&mut self, function: NativeFunction) {
+        self.natives
+            .insert(function.name().to_lowercase(), function);
+    }
+
+    pub fn unregister_native(&mut self, name: &str) -> Option<NativeFunction> {
+        self.natives.remove(&name.to_lowercase())
+    }
+
+    pub fn native(&self, name: &str) -> Option<&NativeFunction> {
+        self.natives.get(&name.to_lowercase())
+    }
+
+    pub fn natives(&self) -> impl Iterator<Item = &NativeFunction> {
+        self.natives.values()
+    }
+
+    // The native a call to `name` resolves to: natives win over builtins, and
+    // over user definitions unless they allow shadowing
+    fn native_for_call(&self, name: &str) -> Option<NativeFunction> {
+        self.native(name)
+            .filter(|n| !n.is_shadowable() || !self.functions.contains_key(name))
+            .cloned()
+    }
+
+    /// Whether a call to `name` reaches a native, user definition or builtin.
+    pub(crate) fn is_callable(&self, name: &str) -> bool {
+        self.native(name).is_some()
+            || self.functions.contains_key(name)
+            || functions::lookup(name).is_some()
+    }
+
+    /// Calls `name` with evaluated arguments: a native, else a user
+    /// definition, else a builtin. Special forms are handled by `Expr::eval`.
+    pub(crate) fn call(
+        &mut self,
+        name: &str,
+        args: Vec<Arc<Number>>,
+    ) -> Result<Arc<Number>, EngineError> {
+        let Some(user_func) = self
+            .functions
+            .get(name)
+            .filter(|_| self.native_for_call(name).is_none())
+            .cloned()
+        else {
+            return self.call_external(name, &args);
+        };
+        if args.len() != user_func.params.len() {
+            return Err(EngineError::ArgumentMismatch(
+                name.to_string(),
+                user_func.params.len(),
+            ));
+        }
+        self.push_scope();
+        for (param, value) in user_func.params.iter().zip(args.iter()) {
+            // Use define_var to initialize params in local scope (shadowing globals)
+            self.define_var(param.clone(), value.clone());
+        }
+        let result = user_func.body.eval(self);
+        self.pop_scope();
+        result
+    }
+
+    // Calls a native or builtin, apart from `call` so that user recursion
+    // does not carry this frame
+    fn call_external(
+        &mut self,
+        name: &str,
+        args: &[Arc<Number>],
+    ) -> Result<Arc<Number>, EngineError> {
+        let raw_args: Vec<Number> = args.iter().map(|a| (**a).clone()).collect();
+        match self.native_for_call(name) {
+            Some(native) => native.call(&raw_args, self).map(Arc::new),
+            None => functions::apply(name, raw_args, self).map(Arc::new),
+        }
+    }
+
+    /// Resets the step counter and starts the clock for `limits.timeout`.
+    /// Called by `engine::evaluate` before each top-level evaluation.
+    pub fn begin_evaluation(&mut self) {
+        self.state = EvalState {
+            depth: 0,
+            steps: 0,
+            deadline: self.limits.timeout.map(|t| Instant::now() + t),
+            overflow: Cell::new(false),
+        };
+    }
+
+    /// Whether the last evaluation had to wrap a computed integer to fit
+    /// `word_size`.
+    pub fn overflowed(&self) -> bool {
+        self.state.overflow.get()
+    }
+
+    /// Read-only view of the limits for code that cannot borrow `&mut self`.
+    pub fn guard(&self) -> Guard<'_> {
+        Guard {
+            limits: &self.limits,
+            cancel: &self.cancel,
+            deadline: self.state.deadline,
+        }
+    }
+
+    // Wraps integers into `word_size`. Numbers as written are bit patterns
+    // and wrap without counting as an overflow.
+    pub(crate) fn fit_word(&self, value: Arc<Number>, as_written: bool) -> Arc<Number> {
+        match self.word_size.and_then(|word| word.fit(&value)) {
+            Some(fitted) => {
+                if !as_written {
+                    self.state.overflow.set(true);
+                }
+                Arc::new(fitted)
+            }
+            None => value,
+        }
+    }
+
+    // Accounts for one expression node entering evaluation
+    fn enter(&mut self) -> Result<(), EngineError> {
+        self.state.steps += 1;
+        self.state.depth += 1;
+        if self.state.depth > self.limits.max_depth {
+            return Err(EngineError::LimitExceeded(format!(
+                "recursion depth (at most {})",
+                self.limits.max_depth
+            )));
+        }
+        if self.state.steps > self.limits.max_steps {
+            return Err(EngineError::LimitExceeded(format!(
+                "evaluation steps (at most {})",
+                self.limits.max_steps
+            )));
+        }
+        if self.cancel.is_cancelled() {
+            return Err(EngineError::Cancelled);
+        }
+        // Reading the clock is comparatively expensive, so only poll it periodically
+        if self.state.steps.is_multiple_of(256) {
+            self.guard().check_interrupt()?;
+        }
+        Ok(())
+    }
+
+    fn leave(&mut self) {
+        self.state.depth = self.state.depth.saturating_sub(1);
+    }
+
     pub fn get_var(&self, name: &str) -> Option<&Arc<Number>> {
         // Search from top (local) to bottom (global)
         for scope in self.scopes.iter().rev() {
@@ -41,23 +268,30 @@ impl Context {
         None
     }
 
-    pub fn set_var(&mut self, name: String, value: Arc<Number>) {
+    pub fn set_var(&mut self, name: String, value: Arc<Number>) -> Result<(), EngineError> {
         // Update-or-define strategy:
         // 1. Search for variable in any scope from local to global (rev)
         // 2. If found, update it in that scope
         // 3. If not found, insert into current (top) scope
+        //    unless the name belongs to a built-in constant
 
         for scope in self.scopes.iter_mut().rev() {
-            if scope.contains_key(&name) {
-                scope.insert(name, value);
-                return;
+            if let Some(slot) = scope.get_mut(&name) {
+                *slot = value;
+                return Ok(());
             }
         }
 
+        // Constants can only be shadowed explicitly through define_var
+        if constants::is_constant(&name) {
+            return Err(EngineError::ConstantAssignment(name));
+        }
+
         // Not found, so define in current scope
         if let Some(scope) = self.scopes.last_mut() {
             scope.insert(name, value);
         }
+        Ok(())
     }
 
     // Force definition in current scope (used for function parameters)
@@ -86,12 +320,29 @@ pub enum BinaryOp {
     Div,
     Mod,
     Pow,
+    /// Text concatenation, `&`
+    Concat,
+    /// Unit conversion, `5 km to mi` (or `in`)
+    Convert,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+    Eq,
+    Ne,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum UnaryOp {
     Neg,
     Factorial,
+    /// An angle written in an explicit unit (`30°`), converted to the
+    /// context's angle unit.
+    Angle(AngleUnit),
+    /// A unit written after a number (`5 km`, `(1/4) /s`) or in a conversion
+    /// target, or a product with the variable of that name if one is defined.
+    /// Elsewhere unit names are ordinary variables.
+    Unit(String),
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -103,10 +354,55 @@ pub enum Expr {
     FunctionCall(String, Vec<Expr>),
     Assignment(String, Box<Expr>),
     FunctionDef(String, Vec<String>, Box<Expr>),
+    /// A list literal, `[a, b, c]`.
+    List(Vec<Expr>),
+    /// A matrix literal, `[a, b; c, d]`, as its rows.
+    Matrix(Vec<Vec<Expr>>),
+    /// `list[index]`, counting from 1. Indexing a matrix gives a row.
+    Index(Box<Expr>, Box<Expr>),
 }
 
 impl Expr {
     pub fn eval(&self, context: &mut Context) -> Result<Arc<Number>, EngineError> {
+        context.enter()?;
+        let result = match self {
+            Expr::BinaryOp(..) => self.eval_chain(context),
+            _ => self.eval_leaf(context),
+        };
+        context.leave();
+        result.map(|value| context.fit_word(value, self.is_written_number()))
+    }
+
+    /// Whether `name` occurs in the expression as a variable, or as a unit
+    /// that may stand for the variable of that name.
+    pub(crate) fn mentions(&self, name: &str) -> bool {
+        match self {
+            Expr::Literal(_) => false,
+            Expr::Variable(var) => var == name,
+            Expr::UnaryOp(UnaryOp::Unit(unit), inner) => unit == name || inner.mentions(name),
+            Expr::UnaryOp(_, inner) => inner.mentions(name),
+            Expr::BinaryOp(_, l, r) | Expr::Index(l, r) => l.mentions(name) || r.mentions(name),
+            Expr::FunctionCall(_, args) | Expr::List(args) => {
+                args.iter().any(|arg| arg.mentions(name))
+            }
+            Expr::Assignment(var, value) => var == name || value.mentions(name),
+            Expr::FunctionDef(_, params, body) => {
+                !params.iter().any(|p| p == name) && body.mentions(name)
+            }
+            Expr::Matrix(rows) => rows.iter().flatten().any(|item| item.mentions(name)),
+        }
+    }
+
+    // A literal, or a negated one, so that `-128` is no overflow in 8 bits
+    fn is_written_number(&self) -> bool {
+        match self {
+            Expr::Literal(_) => true,
+            Expr::UnaryOp(UnaryOp::Neg, inner) => matches!(**inner, Expr::Literal(_)),
+            _ => false,
+        }
+    }
+
+    fn eval_chain(&self, context: &mut Context) -> Result<Arc<Number>, EngineError> {
         // Optimization: Iterative traversal for left-associative BinaryOps to prevent stack overflow
         let mut stack = Vec::new();
         let mut current_expr = self;
@@ -118,61 +414,9 @@ impl Expr {
         }
 
         // Evaluate the leaf (LHS base)
-        let mut result = match current_expr {
-            Expr::Literal(n) => Ok(Arc::new(n.clone())),
-            Expr::Variable(name) => context
-                .get_var(name)
-                .cloned()
-                .ok_or_else(|| EngineError::UndefinedVariable(name.clone())),
-            Expr::Assignment(name, expr) => {
-                let val = expr.eval(context)?;
-                context.set_var(name.clone(), val.clone());
-                Ok(val)
-            }
-            Expr::FunctionDef(name, params, body) => {
-                let func = UserFunction {
-                    params: params.clone(),
-                    body: *body.clone(),
-                };
-                context.functions.insert(name.clone(), func);
-                Ok(Arc::new(Number::Integer(num_bigint::BigInt::from(0))))
-            }
-            Expr::UnaryOp(op, expr) => {
-                let val_arc = expr.eval(context)?;
-                let val = (*val_arc).clone();
-                match op {
-                    UnaryOp::Neg => Ok(Arc::new(-val)),
-                    UnaryOp::Factorial => factorial(val).map(Arc::new),
-                }
-            }
-            Expr::FunctionCall(name, args_exprs) => {
-                let mut args = Vec::with_capacity(args_exprs.len());
-                for arg_expr in args_exprs {
-                    args.push(arg_expr.eval(context)?);
-                }
-
-                if let Some(user_func) = context.functions.get(name).cloned() {
-                    if args.len() != user_func.params.len() {
-                        return Err(EngineError::ArgumentMismatch(
-                            name.clone(),
-                            user_func.params.len(),
-                        ));
-                    }
-                    context.push_scope();
-                    for (param, value) in user_func.params.iter().zip(args.iter()) {
-                        // Use define_var to initialize params in local scope (shadowing globals)
-                        context.define_var(param.clone(), value.clone());
-                    }
-                    let result = user_func.body.eval(context);
-                    context.pop_scope();
-                    result
-                } else {
-                    let raw_args: Vec<Number> = args.iter().map(|a| (**a).clone()).collect();
-                    functions::apply(name, raw_args).map(Arc::new)
-                }
-            }
-            Expr::BinaryOp(_, _, _) => unreachable!("BinaryOp should be handled by the loop"),
-        }?;
+        let mut result = current_expr.eval_leaf(context)?;
+        // The leaf was evaluated in place rather than through `eval`
+        result = context.fit_word(result, current_expr.is_written_number());
 
         // Unwind stack: apply operators from left to right (bottom of stack is first op)
         // Wait. `1+2+3`. Stack: `[(+, 2), (+, 3)]`. (pushed in reverse order?)
@@ -191,21 +435,340 @@ impl Expr {
         // So we apply `(+, 2)` then `(+, 3)`. Correct order for `((1+2)+3)`.
 
         while let Some((op, rhs_expr)) = stack.pop() {
-            let rhs_arc = rhs_expr.eval(context)?;
-            let lhs = (*result).clone();
-            let rhs = (*rhs_arc).clone();
-
-            let res_num = match op {
-                BinaryOp::Add => lhs + rhs,
-                BinaryOp::Sub => lhs - rhs,
-                BinaryOp::Mul => lhs * rhs,
-                BinaryOp::Div => lhs / rhs,
-                BinaryOp::Mod => lhs % rhs,
-                BinaryOp::Pow => pow(lhs, rhs),
-            };
-            result = Arc::new(res_num);
+            let rhs = rhs_expr.eval(context)?;
+            result = combine(op, &result, &rhs, context)?;
         }
 
         Ok(result)
     }
+
+    // Everything but a chain of binary operators. Arms that need much room
+    // call out to their own functions, since this frame sits between the
+    // levels of every user recursion.
+    fn eval_leaf(&self, context: &mut Context) -> Result<Arc<Number>, EngineError> {
+        match self {
+            Expr::Literal(n) => Ok(Arc::new(literal(n, context))),
+            Expr::Variable(name) => context
+                .get_var(name)
+                .cloned()
+                .or_else(|| constants::lookup(name, context.precision).map(Arc::new))
+                .ok_or_else(|| EngineError::UndefinedVariable(name.clone())),
+            Expr::Assignment(name, expr) => assign(name, expr, context),
+            Expr::FunctionDef(name, params, body) => define_function(name, params, body, context),
+            Expr::UnaryOp(op, expr) => {
+                let val = expr.eval(context)?;
+                apply_unary(op, &val, context)
+            }
+            Expr::FunctionCall(name, args_exprs) => eval_call(name, args_exprs, context),
+            Expr::List(items) => eval_list(items, context).map(Arc::new),
+            Expr::Matrix(rows) => eval_matrix(rows, context).map(Arc::new),
+            Expr::Index(list, index) => eval_index(list, index, context),
+            Expr::BinaryOp(_, _, _) => unreachable!("BinaryOp should be handled by the loop"),
+        }
+    }
+}
+
+// Decimal literals are exact in precision mode: `0.1` is one tenth
+fn literal(n: &Number, context: &Context) -> Number {
+    match n {
+        Number::Float(f) => context
+            .precision
+            .and_then(|p| n.to_bigfloat(p))
+            .map_or(Number::Float(*f), Number::BigFloat),
+        Number::BigFloat(b) => match context.precision {
+            Some(p) => Number::BigFloat(b.with_precision(p)),
+            None => Number::Float(b.to_f64()),
+        },
+        n => n.clone(),
+    }
+}
+
+fn assign(name: &str, expr: &Expr, context: &mut Context) -> Result<Arc<Number>, EngineError> {
+    let val = expr.eval(context)?;
+    context.set_var(name.to_string(), val.clone())?;
+    Ok(val)
+}
+
+fn eval_index(
+    list: &Expr,
+    index: &Expr,
+    context: &mut Context,
+) -> Result<Arc<Number>, EngineError> {
+    let list = list.eval(context)?;
+    let index = index.eval(context)?;
+    index_list(&list, &index).map(Arc::new)
+}
+
+fn define_function(
+    name: &str,
+    params: &[String],
+    body: &Expr,
+    context: &mut Context,
+) -> Result<Arc<Number>, EngineError> {
+    if context.native(name).is_some_and(|n| !n.is_shadowable()) {
+        return Err(EngineError::NativeRedefinition(name.to_string()));
+    }
+    let func = UserFunction {
+        params: params.to_vec(),
+        body: body.clone(),
+    };
+    context.functions.insert(name.to_string(), func);
+    Ok(Arc::new(Number::Integer(num_bigint::BigInt::from(0))))
+}
+
+fn apply_unary(op: &UnaryOp, val: &Number, context: &Context) -> Result<Arc<Number>, EngineError> {
+    let val = val.clone();
+    match op {
+        UnaryOp::Neg => negate(val).map(Arc::new),
+        UnaryOp::Factorial => {
+            let guard = context.guard();
+            map_elements(val, &|n| factorial(n, &guard)).map(Arc::new)
+        }
+        UnaryOp::Angle(unit) => Ok(Arc::new(unit.convert(val, context.angle_unit))),
+        UnaryOp::Unit(symbol) => attach_unit(val, symbol, context).map(Arc::new),
+    }
+}
+
+fn eval_call(
+    name: &str,
+    args_exprs: &[Expr],
+    context: &mut Context,
+) -> Result<Arc<Number>, EngineError> {
+    match SpecialForm::lookup(name) {
+        // User definitions take precedence, as for regular built-ins
+        Some(form)
+            if context.native_for_call(name).is_none() && !context.functions.contains_key(name) =>
+        {
+            form.eval(args_exprs, context)
+        }
+        _ => {
+            let mut args = Vec::with_capacity(args_exprs.len());
+            for arg_expr in args_exprs {
+                args.push(arg_expr.eval(context)?);
+            }
+            context.call(name, args)
+        }
+    }
+}
+
+// One step of a chain of binary operators, with the result checked against
+// the limits and wrapped into the word size
+fn combine(
+    op: &BinaryOp,
+    lhs: &Number,
+    rhs: &Number,
+    context: &Context,
+) -> Result<Arc<Number>, EngineError> {
+    let res_num = apply_binary(op, lhs.clone(), rhs.clone(), context)?;
+    context.guard().check_number(&res_num)?;
+    Ok(context.fit_word(Arc::new(res_num), false))
+}
+
+fn eval_list(items: &[Expr], context: &mut Context) -> Result<Number, EngineError> {
+    context.guard().check_elements(items.len())?;
+    let mut values = Vec::with_capacity(items.len());
+    for item in items {
+        values.push((*item.eval(context)?).clone());
+    }
+    Ok(Number::List(values))
+}
+
+fn eval_matrix(rows: &[Vec<Expr>], context: &mut Context) -> Result<Number, EngineError> {
+    context
+        .guard()
+        .check_elements(rows.iter().map(Vec::len).sum())?;
+    let mut values = Vec::with_capacity(rows.len());
+    for row in rows {
+        let mut entries = Vec::with_capacity(row.len());
+        for item in row {
+            let value = (*item.eval(context)?).clone();
+            if let Number::List(_) | Number::Matrix(_) | Number::Text(_) | Number::Quantity(_) =
+                value
+            {
+                return Err(EngineError::TypeMismatch(
+                    "number as a matrix entry".into(),
+                    value.type_name().into(),
+                ));
+            }
+            entries.push(value);
+        }
+        values.push(entries);
+    }
+    Matrix::from_rows(values).map(Number::Matrix)
+}
+
+// Kept out of `Expr::eval` so that the recursive evaluator's stack frames stay
+// small. Arithmetic applies element-wise to lists, and to matrices except for
+// `*` and `^`, which are the matrix product and power. Scalars go through
+// `units`, which checks and converts quantities and leaves numbers alone.
+// In programmer mode integer `/` truncates and `^` is taken within the word.
+pub(crate) fn apply_binary(
+    op: &BinaryOp,
+    lhs: Number,
+    rhs: Number,
+    context: &Context,
+) -> Result<Number, EngineError> {
+    let rates = context.rates.as_ref();
+    match op {
+        BinaryOp::Add => elementwise(lhs, rhs, &|l, r| units::add(l, r, rates)),
+        BinaryOp::Sub => elementwise(lhs, rhs, &|l, r| units::sub(l, r, rates)),
+        BinaryOp::Mul => match (&lhs, &rhs) {
+            (Number::Matrix(_), Number::Matrix(_) | Number::List(_))
+            | (Number::List(_), Number::Matrix(_)) => matrix_product(lhs, rhs, context),
+            _ => elementwise(lhs, rhs, &|l, r| units::mul(l, r, rates)),
+        },
+        BinaryOp::Div if matches!(rhs, Number::Matrix(_)) => Err(EngineError::TypeMismatch(
+            "scalar divisor (use inv for matrices)".into(),
+            "matrix".into(),
+        )),
+        BinaryOp::Div => elementwise(lhs, rhs, &|l, r| match (context.word_size, &l, &r) {
+            (Some(_), Number::Integer(a), Number::Integer(b)) => word::divide(a, b),
+            _ => units::div(l, r, rates),
+        }),
+        BinaryOp::Pow if matches!(lhs, Number::Matrix(_)) => matrix_power(lhs, rhs, context),
+        BinaryOp::Mod => elementwise(lhs, rhs, &|l, r| units::rem(l, r, rates)),
+        BinaryOp::Pow => elementwise(lhs, rhs, &|l, r| match (context.word_size, &l, &r) {
+            (Some(word), Number::Integer(a), Number::Integer(b)) if !b.is_negative() => {
+                let (power, overflowed) = word.pow(a, b);
+                if overflowed {
+                    context.state.overflow.set(true);
+                }
+                Ok(Number::Integer(power))
+            }
+            _ => units::pow(l, r, context),
+        }),
+        BinaryOp::Concat => concat(lhs, rhs),
+        BinaryOp::Convert => map_elements(lhs, &|n| units::convert(n, &rhs, rates)),
+        BinaryOp::Lt => Ok(Number::from(units::compare(&lhs, &rhs, rates)?.is_lt())),
+        BinaryOp::Gt => Ok(Number::from(units::compare(&lhs, &rhs, rates)?.is_gt())),
+        BinaryOp::Le => Ok(Number::from(units::compare(&lhs, &rhs, rates)?.is_le())),
+        BinaryOp::Ge => Ok(Number::from(units::compare(&lhs, &rhs, rates)?.is_ge())),
+        BinaryOp::Eq => Ok(Number::from(units::equal(&lhs, &rhs, rates))),
+        BinaryOp::Ne => Ok(Number::from(!units::equal(&lhs, &rhs, rates))),
+    }
+}
+
+// `5 km` is five times one kilometer. A variable named like the unit takes
+// its place, so `2 m` stays a product for anyone who has defined `m`.
+fn attach_unit(value: Number, symbol: &str, context: &Context) -> Result<Number, EngineError> {
+    let unit = match context.get_var(symbol) {
+        Some(var) => (**var).clone(),
+        None => units::unit_value(symbol)
+            .ok_or_else(|| EngineError::UndefinedVariable(symbol.to_string()))?,
+    };
+    apply_binary(&BinaryOp::Mul, value, unit, context)
+}
+
+fn negate(value: Number) -> Result<Number, EngineError> {
+    map_elements(value, &|n| match n {
+        Number::Text(_) | Number::Boolean(_) => Err(EngineError::TypeMismatch(
+            "number".into(),
+            n.type_name().into(),
+        )),
+        n => Ok(-n),
+    })
+}
+
+// `a & b` joins two values as text, writing numbers as they are displayed
+// with decimals, so `"total: " & 1/4` is "total: 0.25"
+fn concat(lhs: Number, rhs: Number) -> Result<Number, EngineError> {
+    let text = |n: Number| match n {
+        Number::Text(s) => Ok(s),
+        Number::List(_) | Number::Matrix(_) => Err(EngineError::TypeMismatch(
+            "text or number".into(),
+            n.type_name().into(),
+        )),
+        n => Ok(crate::utils::format_number(n, true)),
+    };
+    Ok(Number::Text(text(lhs)? + &text(rhs)?))
+}
+
+// Matrix products; a list on the right is a column vector and on the left a
+// row vector, and either way the result is a list
+fn matrix_product(lhs: Number, rhs: Number, context: &Context) -> Result<Number, EngineError> {
+    let guard = &context.guard();
+    let vector = |items: Vec<Number>, column: bool| {
+        let len = items.len();
+        let (rows, cols) = if column { (len, 1) } else { (1, len) };
+        Matrix::new(rows, cols, items)
+    };
+    match (lhs, rhs) {
+        (Number::Matrix(l), Number::Matrix(r)) => l.mul(&r, guard).map(Number::Matrix),
+        (Number::Matrix(l), Number::List(r)) => l
+            .mul(&vector(r, true)?, guard)
+            .map(|m| Number::List(m.into_data())),
+        (Number::List(l), Number::Matrix(r)) => vector(l, false)?
+            .mul(&r, guard)
+            .map(|m| Number::List(m.into_data())),
+        _ => unreachable!("matrix_product needs a matrix operand"),
+    }
+}
+
+// `A^n` for an integer `n`, multiplying rather than raising each entry
+fn matrix_power(base: Number, exp: Number, context: &Context) -> Result<Number, EngineError> {
+    let Number::Matrix(m) = base else {
+        unreachable!("matrix_power needs a matrix base")
+    };
+    let n = match &exp {
+        Number::Integer(n) => n.clone(),
+        Number::Rational(r) if r.is_integer() => r.to_integer(),
+        other => {
+            return Err(EngineError::TypeMismatch(
+                "integer matrix exponent".into(),
+                other.type_name().into(),
+            ));
+        }
+    };
+    m.pow(&n, &context.guard()).map(Number::Matrix)
+}
+
+// `list[index]` with 1-based indices, as in spreadsheets; `matrix[row]` is
+// that row as a list and `text[i]` the i-th character
+fn index_list(list: &Number, index: &Number) -> Result<Number, EngineError> {
+    let parts;
+    let items = match list {
+        Number::List(items) => items,
+        Number::Matrix(m) => {
+            parts = (0..m.rows())
+                .map(|r| Number::List(m.row(r)))
+                .collect::<Vec<_>>();
+            &parts
+        }
+        Number::Text(s) => {
+            parts = s
+                .chars()
+                .map(|c| Number::Text(c.to_string()))
+                .collect::<Vec<_>>();
+            &parts
+        }
+        other => {
+            return Err(EngineError::TypeMismatch(
+                "list to index".into(),
+                other.type_name().into(),
+            ));
+        }
+    };
+    let position = match index {
+        Number::Integer(i) => i.to_usize(),
+        other => match other.to_f64() {
+            Some(f) if f.fract() == 0.0 && f >= 0.0 => Some(f as usize),
+            _ => {
+                return Err(EngineError::TypeMismatch(
+                    "integer index".into(),
+                    other.type_name().into(),
+                ));
+            }
+        },
+    };
+    position
+        .filter(|&p| p >= 1)
+        .and_then(|p| items.get(p - 1))
+        .cloned()
+        .ok_or_else(|| {
+            EngineError::DomainError(format!(
+                "index {} is out of range for a list of {} elements",
+                crate::utils::format_number(index.clone(), true),
+                items.len()
+            ))
+        })
 }
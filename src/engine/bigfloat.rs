@@ -0,0 +1,887 @@
+//! Arbitrary-precision decimal floating point.
+//!
+//! A `BigFloat` is `mantissa × 10^exponent` with at most `precision`
+//! significant decimal digits. Arithmetic rounds to the larger precision of
+//! its operands; transcendental functions are evaluated in fixed point with a
+//! few guard digits and then rounded, so results are correct to about the last
+//! digit. This is what keeps `sqrt(2)` or `ln(3)` from collapsing to the 53 bits
+//! of an `f64` when `Context::precision` is set.
+
+use super::errors::EngineError;
+use super::limits::Guard;
+use num::{Integer, One, Signed, ToPrimitive, Zero};
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Upper bound for `Context::precision`; series evaluation gets slow beyond it.
+pub const MAX_PRECISION: u32 = 2000;
+
+/// Extra digits carried through intermediate fixed point computations.
+const GUARD_DIGITS: u32 = 12;
+
+/// Most digits a series may carry on top of those. Reducing sin(10^n) takes
+/// n extra digits of pi, so this is also the largest argument it accepts.
+const MAX_EXTRA_DIGITS: i64 = MAX_PRECISION as i64;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BigFloat {
+    mantissa: BigInt,
+    exponent: i64,
+    precision: u32,
+}
+
+fn pow10(n: u32) -> BigInt {
+    num::pow(BigInt::from(10), n as usize)
+}
+
+// Extra working digits asked for by an argument's magnitude, within the cap
+fn extra_digits(extra: i64) -> Result<u32, EngineError> {
+    if extra > MAX_EXTRA_DIGITS {
+        return Err(EngineError::LimitExceeded(format!(
+            "argument magnitude ({} extra digits, at most {} allowed)",
+            extra, MAX_EXTRA_DIGITS
+        )));
+    }
+    Ok(extra.max(0) as u32)
+}
+
+// The constants are computed without a guard, which nothing can interrupt
+fn unguarded(result: Result<BigInt, EngineError>) -> BigInt {
+    result.expect("a series without a guard is never interrupted")
+}
+
+// Number of decimal digits of |n| (1 for zero)
+fn digit_count(n: &BigInt) -> u32 {
+    if n.is_zero() {
+        return 1;
+    }
+    // floor(log10(2^(bits-1))) <= floor(log10|n|) <= that + 1
+    let estimate = ((n.bits() - 1) as f64 * std::f64::consts::LOG10_2) as u32;
+    if n.magnitude() >= pow10(estimate + 1).magnitude() {
+        estimate + 2
+    } else {
+        estimate + 1
+    }
+}
+
+// Division rounding half away from zero
+fn round_div(n: &BigInt, d: &BigInt) -> BigInt {
+    let (q, r) = n.div_rem(d);
+    if (r.abs() * 2) >= d.abs() {
+        if n.is_negative() != d.is_negative() {
+            q - 1
+        } else {
+            q + 1
+        }
+    } else {
+        q
+    }
+}
+
+impl BigFloat {
+    fn new(mantissa: BigInt, exponent: i64, precision: u32) -> Self {
+        BigFloat {
+            mantissa,
+            exponent,
+            precision,
+        }
+        .normalize()
+    }
+
+    /// Rounds to `precision` digits and strips trailing zeros so that every
+    /// value has a single representation.
+    fn normalize(mut self) -> Self {
+        let digits = digit_count(&self.mantissa);
+        if digits > self.precision {
+            let drop = digits - self.precision;
+            self.mantissa = round_div(&self.mantissa, &pow10(drop));
+            self.exponent += drop as i64;
+        }
+        if self.mantissa.is_zero() {
+            self.exponent = 0;
+            return self;
+        }
+        let ten = BigInt::from(10);
+        loop {
+            let (q, r) = self.mantissa.div_rem(&ten);
+            if !r.is_zero() {
+                break;
+            }
+            self.mantissa = q;
+            self.exponent += 1;
+        }
+        self
+    }
+
+    pub fn zero(precision: u32) -> Self {
+        BigFloat::new(BigInt::zero(), 0, precision)
+    }
+
+    pub fn one(precision: u32) -> Self {
+        BigFloat::new(BigInt::one(), 0, precision)
+    }
+
+    pub fn half(precision: u32) -> Self {
+        BigFloat::new(BigInt::from(5), -1, precision)
+    }
+
+    pub fn from_integer(i: BigInt, precision: u32) -> Self {
+        BigFloat::new(i, 0, precision)
+    }
+
+    pub fn from_rational(r: &BigRational, precision: u32) -> Self {
+        let (num, den) = (r.numer(), r.denom());
+        // Scale so the quotient carries precision + guard significant digits
+        let shift =
+            (precision + GUARD_DIGITS) as i64 + digit_count(den) as i64 - digit_count(num) as i64;
+        let q = if shift >= 0 {
+            round_div(&(num * pow10(shift as u32)), den)
+        } else {
+            round_div(num, &(den * pow10((-shift) as u32)))
+        };
+        BigFloat::new(q, -shift, precision)
+    }
+
+    /// Converts through the shortest decimal representation of `f`, so the
+    /// literal `0.1` becomes exactly one tenth rather than its binary neighbor.
+    pub fn from_f64(f: f64, precision: u32) -> Option<Self> {
+        if !f.is_finite() {
+            return None;
+        }
+        BigFloat::parse(&format!("{:e}", f), precision)
+    }
+
+    /// Parses a decimal literal such as `-12.5`, `1e-7` or `3.25E+4`.
+    pub fn parse(text: &str, precision: u32) -> Option<Self> {
+        let text = text.trim();
+        let (number, exp) = match text.find(['e', 'E']) {
+            Some(pos) => (&text[..pos], text[pos + 1..].parse::<i64>().ok()?),
+            None => (text, 0),
+        };
+        let (negative, number) = match number.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, number.strip_prefix('+').unwrap_or(number)),
+        };
+        let (int_part, frac_part) = number.split_once('.').unwrap_or((number, ""));
+        if int_part.is_empty() && frac_part.is_empty() {
+            return None;
+        }
+        let digits = format!("{}{}", int_part, frac_part);
+        if !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let mut mantissa: BigInt = digits.parse().ok()?;
+        if negative {
+            mantissa = -mantissa;
+        }
+        Some(BigFloat::new(
+            mantissa,
+            exp - frac_part.len() as i64,
+            precision,
+        ))
+    }
+
+    pub fn precision(&self) -> u32 {
+        self.precision
+    }
+
+    pub fn with_precision(&self, precision: u32) -> Self {
+        BigFloat::new(self.mantissa.clone(), self.exponent, precision)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.mantissa.is_negative()
+    }
+
+    pub fn abs(&self) -> Self {
+        BigFloat {
+            mantissa: self.mantissa.abs(),
+            ..self.clone()
+        }
+    }
+
+    // Power of ten of the leading digit: 1 for 12.3, -2 for 0.0456
+    fn magnitude(&self) -> i64 {
+        self.exponent + digit_count(&self.mantissa) as i64 - 1
+    }
+
+    // Whether x^2 vanishes next to 1 at this precision, so that sin, tan,
+    // atan, sinh and tanh of x all round to x
+    fn square_is_negligible(&self) -> bool {
+        !self.is_zero() && -2 * self.magnitude() > (self.precision + GUARD_DIGITS) as i64
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.exponent >= 0
+    }
+
+    /// Exact rational value.
+    pub fn to_rational(&self) -> BigRational {
+        if self.exponent >= 0 {
+            BigRational::from_integer(&self.mantissa * pow10(self.exponent as u32))
+        } else {
+            BigRational::new(
+                self.mantissa.clone(),
+                pow10((-self.exponent).min(u32::MAX as i64) as u32),
+            )
+        }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        // Keep 17 significant digits, then let the standard parser round
+        let digits = digit_count(&self.mantissa);
+        let (m, e) = if digits > 17 {
+            let drop = digits - 17;
+            (&self.mantissa / pow10(drop), self.exponent + drop as i64)
+        } else {
+            (self.mantissa.clone(), self.exponent)
+        };
+        format!("{}e{}", m, e).parse().unwrap_or(f64::NAN)
+    }
+
+    /// Rounds toward negative infinity.
+    pub fn floor(&self) -> BigInt {
+        self.to_rational().floor().to_integer()
+    }
+
+    pub fn ceil(&self) -> BigInt {
+        self.to_rational().ceil().to_integer()
+    }
+
+    pub fn trunc(&self) -> BigInt {
+        self.to_rational().trunc().to_integer()
+    }
+
+    /// Rounds half away from zero to `decimals` digits after the point.
+    pub fn round_to(&self, decimals: i64) -> Self {
+        let shift = -decimals - self.exponent;
+        if shift <= 0 {
+            return self.clone();
+        }
+        if shift > self.precision as i64 + 1 {
+            return BigFloat::zero(self.precision);
+        }
+        let mantissa = round_div(&self.mantissa, &pow10(shift as u32));
+        BigFloat::new(mantissa, -decimals, self.precision)
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        let precision = self.precision.max(other.precision);
+        if self.is_zero() {
+            return other.with_precision(precision);
+        }
+        if other.is_zero() {
+            return self.with_precision(precision);
+        }
+        // An operand that is too small to affect the rounded sum is dropped
+        // instead of aligning exponents that are arbitrarily far apart.
+        let gap = self.magnitude() - other.magnitude();
+        let limit = (precision + 2) as i64;
+        if gap > limit {
+            return self.with_precision(precision);
+        }
+        if gap < -limit {
+            return other.with_precision(precision);
+        }
+        let exponent = self.exponent.min(other.exponent);
+        let a = &self.mantissa * pow10((self.exponent - exponent) as u32);
+        let b = &other.mantissa * pow10((other.exponent - exponent) as u32);
+        BigFloat::new(a + b, exponent, precision)
+    }
+
+    pub fn neg(&self) -> Self {
+        BigFloat {
+            mantissa: -&self.mantissa,
+            ..self.clone()
+        }
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        self.add(&other.neg())
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        BigFloat::new(
+            &self.mantissa * &other.mantissa,
+            self.exponent + other.exponent,
+            self.precision.max(other.precision),
+        )
+    }
+
+    /// `None` when dividing by zero.
+    pub fn div(&self, other: &Self) -> Option<Self> {
+        if other.is_zero() {
+            return None;
+        }
+        let precision = self.precision.max(other.precision);
+        let shift = (precision + GUARD_DIGITS) as i64 + digit_count(&other.mantissa) as i64
+            - digit_count(&self.mantissa) as i64;
+        let shift = shift.max(0) as u32;
+        let q = round_div(&(&self.mantissa * pow10(shift)), &other.mantissa);
+        Some(BigFloat::new(
+            q,
+            self.exponent - other.exponent - shift as i64,
+            precision,
+        ))
+    }
+
+    /// Remainder with the sign of the dividend, like `f64`'s `%`.
+    pub fn rem(&self, other: &Self) -> Option<Self> {
+        let q = self.div(other)?.trunc();
+        let precision = self.precision.max(other.precision);
+        Some(self.sub(&other.mul(&BigFloat::from_integer(q, precision))))
+    }
+
+    pub fn powi(&self, exp: &BigInt) -> Result<Self, EngineError> {
+        if exp.is_negative() {
+            return BigFloat::one(self.precision)
+                .div(&self.powi(&-exp)?)
+                .ok_or(EngineError::DivisionByZero);
+        }
+        // Refuse results whose decimal exponent cannot be represented
+        let e = exp.to_i64().unwrap_or(i64::MAX);
+        if !self.is_zero()
+            && (self.magnitude().abs() + 1)
+                .checked_mul(e)
+                .is_none_or(|m| m > 1 << 60)
+        {
+            return Err(EngineError::DomainError(
+                "Result exponent out of range".into(),
+            ));
+        }
+        let mut result = BigFloat::one(self.precision);
+        let mut base = self.clone();
+        let mut e = exp.clone();
+        while !e.is_zero() {
+            if e.is_odd() {
+                result = result.mul(&base);
+            }
+            e >>= 1;
+            if !e.is_zero() {
+                base = base.mul(&base);
+            }
+        }
+        Ok(result)
+    }
+
+    pub fn sqrt(&self) -> Result<Self, EngineError> {
+        if self.is_negative() {
+            return Err(EngineError::DomainError(
+                "Square root of a negative number".into(),
+            ));
+        }
+        if self.is_zero() {
+            return Ok(self.clone());
+        }
+        // Scale to an even exponent with enough digits for the integer root
+        let wanted = 2 * (self.precision + GUARD_DIGITS) as i64;
+        let mut shift = (wanted - digit_count(&self.mantissa) as i64).max(0);
+        if (self.exponent - shift).rem_euclid(2) != 0 {
+            shift += 1;
+        }
+        let root = (&self.mantissa * pow10(shift as u32)).sqrt();
+        Ok(BigFloat::new(
+            root,
+            (self.exponent - shift) / 2,
+            self.precision,
+        ))
+    }
+
+    pub fn pi(precision: u32) -> Self {
+        let f = Fixed::new(precision + GUARD_DIGITS, None);
+        BigFloat::new(unguarded(f.pi()), -(f.wp as i64), precision)
+    }
+
+    pub fn e(precision: u32) -> Self {
+        let f = Fixed::new(precision + GUARD_DIGITS, None);
+        BigFloat::new(unguarded(f.exp_small(&f.one)), -(f.wp as i64), precision)
+    }
+
+    pub fn ln10(precision: u32) -> Self {
+        let f = Fixed::new(precision + GUARD_DIGITS, None);
+        BigFloat::new(unguarded(f.ln10()), -(f.wp as i64), precision)
+    }
+
+    pub fn exp(&self, guard: &Guard) -> Result<Self, EngineError> {
+        let precision = self.precision;
+        if self.is_zero() {
+            return Ok(BigFloat::one(precision));
+        }
+        // exp(x) = 10^k * exp(r) with x = k*ln(10) + r and 0 <= r < ln(10)
+        let int_digits = self.magnitude().max(0) as u32;
+        if int_digits > 15 {
+            return Err(EngineError::DomainError("exp argument out of range".into()));
+        }
+        let f = Fixed::new(precision + GUARD_DIGITS + int_digits, Some(guard));
+        let x = f.scale(self);
+        let ln10 = f.ln10()?;
+        let k = x.div_floor(&ln10);
+        let r = &x - &k * &ln10;
+        let mantissa = f.exp_small(&r)?;
+        let k = k.to_i64().unwrap_or(0);
+        Ok(BigFloat::new(mantissa, k - f.wp as i64, precision))
+    }
+
+    pub fn ln(&self, guard: &Guard) -> Result<Self, EngineError> {
+        if !self.mantissa.is_positive() {
+            return Err(EngineError::DomainError(
+                "Logarithm of a non-positive number".into(),
+            ));
+        }
+        let precision = self.precision;
+        // x = m * 10^k with 1 <= m < 10, so ln(x) = ln(m) + k*ln(10)
+        let k = self.magnitude();
+        // Close to 1 the result is tiny; carry enough digits to keep it relative
+        let near_one = self.sub(&BigFloat::one(precision));
+        let extra = if k == 0 && !near_one.is_zero() {
+            (-near_one.magnitude()).max(0) as u32
+        } else {
+            0
+        };
+        let k_digits = digit_count(&BigInt::from(k));
+        let f = Fixed::new(precision + GUARD_DIGITS + extra + k_digits, Some(guard));
+        let m = f.scale_parts(&self.mantissa, self.exponent - k);
+        let result = f.ln_small(&m)? + f.ln10()? * BigInt::from(k);
+        Ok(BigFloat::new(result, -(f.wp as i64), precision))
+    }
+
+    pub fn pow(&self, exp: &Self, guard: &Guard) -> Result<Self, EngineError> {
+        if exp.is_integer() {
+            return self.powi(&exp.trunc());
+        }
+        if self.is_zero() && !exp.is_negative() {
+            return Ok(BigFloat::zero(self.precision));
+        }
+        let precision = self.precision.max(exp.precision);
+        let working = self.with_precision(precision + GUARD_DIGITS);
+        working
+            .ln(guard)?
+            .mul(exp)
+            .exp(guard)
+            .map(|r| r.with_precision(precision))
+    }
+
+    // Shared driver for sin, cos and tan
+    fn sin_cos(&self, guard: &Guard) -> Result<(Self, Self), EngineError> {
+        let precision = self.precision;
+        if self.square_is_negligible() {
+            return Ok((self.clone(), BigFloat::one(precision)));
+        }
+        // Small arguments need extra digits to keep sin(x) ~ x relative,
+        // large ones need extra digits of pi for the reduction
+        let extra = extra_digits(self.magnitude().abs())?;
+        let f = Fixed::new(precision + GUARD_DIGITS + extra, Some(guard));
+        let x = f.scale(self);
+        let half_pi: BigInt = f.pi()? / 2;
+        // x = q*(pi/2) + r with |r| <= pi/4
+        let q = round_div(&x, &half_pi);
+        let r = &x - &q * &half_pi;
+        let (s, c) = (f.sin_small(&r)?, f.cos_small(&r)?);
+        let (s, c) = match q.mod_floor(&BigInt::from(4)).to_u8() {
+            Some(0) => (s, c),
+            Some(1) => (c, -s),
+            Some(2) => (-s, -c),
+            _ => (-c, s),
+        };
+        let wp = -(f.wp as i64);
+        Ok((
+            BigFloat::new(s, wp, precision),
+            BigFloat::new(c, wp, precision),
+        ))
+    }
+
+    pub fn sin(&self, guard: &Guard) -> Result<Self, EngineError> {
+        Ok(self.sin_cos(guard)?.0)
+    }
+
+    pub fn cos(&self, guard: &Guard) -> Result<Self, EngineError> {
+        Ok(self.sin_cos(guard)?.1)
+    }
+
+    pub fn tan(&self, guard: &Guard) -> Result<Self, EngineError> {
+        let (s, c) = self.sin_cos(guard)?;
+        s.div(&c).ok_or_else(|| {
+            EngineError::DomainError("tan is undefined at odd multiples of π/2".into())
+        })
+    }
+
+    pub fn atan(&self, guard: &Guard) -> Result<Self, EngineError> {
+        let precision = self.precision;
+        if self.square_is_negligible() {
+            return Ok(self.clone());
+        }
+        let extra = extra_digits(-self.magnitude())?;
+        let f = Fixed::new(precision + GUARD_DIGITS + extra, Some(guard));
+        // atan(x) = sign(x)*pi/2 - atan(1/x) for |x| > 1
+        let result = if self.magnitude() >= 0
+            && self.abs().cmp(&BigFloat::one(precision)) == Ordering::Greater
+        {
+            let inv = BigFloat::one(f.wp)
+                .div(self)
+                .unwrap_or_else(|| BigFloat::zero(f.wp));
+            let half_pi: BigInt = f.pi()? / 2;
+            let half_pi = if self.is_negative() {
+                -half_pi
+            } else {
+                half_pi
+            };
+            half_pi - f.atan_small(&f.scale(&inv))?
+        } else {
+            f.atan_small(&f.scale(self))?
+        };
+        Ok(BigFloat::new(result, -(f.wp as i64), precision))
+    }
+
+    /// `None` outside [-1, 1] where the result is complex.
+    pub fn asin(&self, guard: &Guard) -> Result<Option<Self>, EngineError> {
+        let precision = self.precision;
+        let one = BigFloat::one(precision);
+        match self.abs().cmp(&one) {
+            Ordering::Greater => Ok(None),
+            Ordering::Equal => {
+                let half_pi = BigFloat::pi(precision).mul(&BigFloat::half(precision));
+                Ok(Some(if self.is_negative() {
+                    half_pi.neg()
+                } else {
+                    half_pi
+                }))
+            }
+            Ordering::Less => {
+                let working = self.with_precision(precision + GUARD_DIGITS);
+                let ratio = BigFloat::one(working.precision)
+                    .sub(&working.mul(&working))
+                    .sqrt()
+                    .ok()
+                    .and_then(|cos| working.div(&cos));
+                ratio
+                    .map(|r| Ok(r.atan(guard)?.with_precision(precision)))
+                    .transpose()
+            }
+        }
+    }
+
+    pub fn acos(&self, guard: &Guard) -> Result<Option<Self>, EngineError> {
+        let precision = self.precision;
+        let half_pi = BigFloat::pi(precision + GUARD_DIGITS).mul(&BigFloat::half(precision));
+        let asin = self.with_precision(precision + GUARD_DIGITS).asin(guard)?;
+        Ok(asin.map(|asin| half_pi.sub(&asin).with_precision(precision)))
+    }
+
+    // Extra digits needed to compute e^x - e^-x for small x without cancellation
+    fn hyperbolic_working(&self) -> Result<Self, EngineError> {
+        let extra = extra_digits(-self.magnitude())?;
+        Ok(self.with_precision(self.precision + GUARD_DIGITS + extra))
+    }
+
+    pub fn sinh(&self, guard: &Guard) -> Result<Self, EngineError> {
+        if self.square_is_negligible() {
+            return Ok(self.clone());
+        }
+        let x = self.hyperbolic_working()?;
+        let (ep, en) = (x.exp(guard)?, x.neg().exp(guard)?);
+        Ok(ep
+            .sub(&en)
+            .mul(&BigFloat::half(x.precision))
+            .with_precision(self.precision))
+    }
+
+    pub fn cosh(&self, guard: &Guard) -> Result<Self, EngineError> {
+        let x = self.with_precision(self.precision + GUARD_DIGITS);
+        let (ep, en) = (x.exp(guard)?, x.neg().exp(guard)?);
+        Ok(ep
+            .add(&en)
+            .mul(&BigFloat::half(x.precision))
+            .with_precision(self.precision))
+    }
+
+    pub fn tanh(&self, guard: &Guard) -> Result<Self, EngineError> {
+        // Saturates to ±1 long before exp(2x) gets out of range
+        if self.magnitude() > 6 {
+            let one = BigFloat::one(self.precision);
+            return Ok(if self.is_negative() { one.neg() } else { one });
+        }
+        if self.square_is_negligible() {
+            return Ok(self.clone());
+        }
+        let x = self.hyperbolic_working()?;
+        let e2 = x.add(&x).exp(guard)?;
+        let one = BigFloat::one(x.precision);
+        let result = e2
+            .sub(&one)
+            .div(&e2.add(&one))
+            .ok_or(EngineError::DivisionByZero)?;
+        Ok(result.with_precision(self.precision))
+    }
+}
+
+impl PartialEq for BigFloat {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for BigFloat {}
+
+impl PartialOrd for BigFloat {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigFloat {
+    /// Exact comparison of the represented values, regardless of precision.
+    fn cmp(&self, other: &Self) -> Ordering {
+        let sign = |x: &BigFloat| x.mantissa.sign();
+        if sign(self) != sign(other) || self.is_zero() {
+            return sign(self).cmp(&sign(other));
+        }
+        let by_magnitude = self.magnitude().cmp(&other.magnitude());
+        if by_magnitude != Ordering::Equal {
+            return if self.is_negative() {
+                by_magnitude.reverse()
+            } else {
+                by_magnitude
+            };
+        }
+        // Same magnitude, so the exponents are at most a mantissa length apart
+        let exponent = self.exponent.min(other.exponent);
+        let a = &self.mantissa * pow10((self.exponent - exponent) as u32);
+        let b = &other.mantissa * pow10((other.exponent - exponent) as u32);
+        a.cmp(&b)
+    }
+}
+
+impl fmt::Display for BigFloat {
+    /// Plain notation for moderate magnitudes, scientific (`1.5e-12`) otherwise.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        let sign = if self.is_negative() { "-" } else { "" };
+        let digits = self.mantissa.magnitude().to_string();
+        let magnitude = self.magnitude();
+        if (-7..21).contains(&magnitude) {
+            if self.exponent >= 0 {
+                write!(
+                    f,
+                    "{}{}{}",
+                    sign,
+                    digits,
+                    "0".repeat(self.exponent as usize)
+                )
+            } else if magnitude >= 0 {
+                let point = (magnitude + 1) as usize;
+                write!(f, "{}{}.{}", sign, &digits[..point], &digits[point..])
+            } else {
+                let zeros = (-magnitude - 1) as usize;
+                write!(f, "{}0.{}{}", sign, "0".repeat(zeros), digits)
+            }
+        } else {
+            let (head, tail) = digits.split_at(1);
+            if tail.is_empty() {
+                write!(f, "{}{}e{}", sign, head, magnitude)
+            } else {
+                write!(f, "{}{}.{}e{}", sign, head, tail, magnitude)
+            }
+        }
+    }
+}
+
+/// Fixed point arithmetic on integers scaled by `10^wp`, used for the series
+/// behind the transcendental functions.
+struct Fixed<'g> {
+    wp: u32,
+    one: BigInt,
+    /// Polled by the series and reduction loops, which can run long at a
+    /// large working precision
+    guard: Option<&'g Guard<'g>>,
+}
+
+impl<'g> Fixed<'g> {
+    fn new(wp: u32, guard: Option<&'g Guard<'g>>) -> Self {
+        Fixed {
+            wp,
+            one: pow10(wp),
+            guard,
+        }
+    }
+
+    fn check_interrupt(&self) -> Result<(), EngineError> {
+        self.guard.map_or(Ok(()), Guard::check_interrupt)
+    }
+
+    fn scale_parts(&self, mantissa: &BigInt, exponent: i64) -> BigInt {
+        let shift = exponent + self.wp as i64;
+        if shift >= 0 {
+            mantissa * pow10(shift as u32)
+        } else {
+            round_div(mantissa, &pow10((-shift) as u32))
+        }
+    }
+
+    fn scale(&self, x: &BigFloat) -> BigInt {
+        self.scale_parts(&x.mantissa, x.exponent)
+    }
+
+    fn mul(&self, a: &BigInt, b: &BigInt) -> BigInt {
+        (a * b) / &self.one
+    }
+
+    fn div(&self, a: &BigInt, b: &BigInt) -> BigInt {
+        (a * &self.one) / b
+    }
+
+    // atan(1/n) by its Taylor series
+    fn atan_inv(&self, n: u32) -> Result<BigInt, EngineError> {
+        let n2 = BigInt::from(n) * n;
+        let mut term = &self.one / n;
+        let mut sum = term.clone();
+        let mut k: u32 = 1;
+        loop {
+            self.check_interrupt()?;
+            term /= &n2;
+            if term.is_zero() {
+                break;
+            }
+            let t = &term / (2 * k + 1);
+            if k % 2 == 1 {
+                sum -= t;
+            } else {
+                sum += t;
+            }
+            k += 1;
+        }
+        Ok(sum)
+    }
+
+    // Machin's formula: pi = 16 atan(1/5) - 4 atan(1/239)
+    fn pi(&self) -> Result<BigInt, EngineError> {
+        Ok(self.atan_inv(5)? * 16 - self.atan_inv(239)? * 4)
+    }
+
+    // exp(r) for moderate |r| (a few units): halve until tiny, sum the
+    // Taylor series, then square back up
+    fn exp_small(&self, r: &BigInt) -> Result<BigInt, EngineError> {
+        const HALVINGS: u32 = 16;
+        let inner = Fixed::new(self.wp + 6, self.guard);
+        let mut x = inner.scale_parts(r, -(self.wp as i64)) >> HALVINGS;
+        let mut sum = inner.one.clone();
+        let mut term = inner.one.clone();
+        let mut k: u32 = 1;
+        loop {
+            self.check_interrupt()?;
+            term = inner.mul(&term, &x) / k;
+            if term.is_zero() {
+                break;
+            }
+            sum += &term;
+            k += 1;
+        }
+        for _ in 0..HALVINGS {
+            self.check_interrupt()?;
+            sum = inner.mul(&sum, &sum);
+        }
+        x = sum;
+        Ok(round_div(&x, &pow10(6)))
+    }
+
+    // ln(m) for 1 <= m <= 10 by Newton's iteration on exp
+    fn ln_small(&self, m: &BigInt) -> Result<BigInt, EngineError> {
+        // Seed from the leading digits only; m and 10^wp themselves overflow
+        // an f64 past about 308 digits
+        let seed = BigFloat::new(m.clone(), -(self.wp as i64), 17)
+            .to_f64()
+            .ln();
+        let mut y = BigFloat::from_f64(seed, self.wp)
+            .map(|y| self.scale(&y))
+            .unwrap_or_default();
+        // Each step doubles the number of correct digits, so stop once the
+        // correction is down to rounding noise or no longer shrinks
+        let mut last: Option<BigInt> = None;
+        loop {
+            let ey = self.exp_small(&y)?;
+            let step = self.div(&((m - &ey) * 2), &(m + &ey));
+            let size = step.abs();
+            y += step;
+            if size <= BigInt::one() || last.is_some_and(|last| size >= last) {
+                break;
+            }
+            last = Some(size);
+        }
+        Ok(y)
+    }
+
+    fn ln10(&self) -> Result<BigInt, EngineError> {
+        self.ln_small(&(&self.one * 10))
+    }
+
+    fn sin_small(&self, r: &BigInt) -> Result<BigInt, EngineError> {
+        let r2 = self.mul(r, r);
+        let mut term = r.clone();
+        let mut sum = r.clone();
+        let mut k: u32 = 1;
+        loop {
+            self.check_interrupt()?;
+            term = -self.mul(&term, &r2) / ((2 * k) * (2 * k + 1));
+            if term.is_zero() {
+                break;
+            }
+            sum += &term;
+            k += 1;
+        }
+        Ok(sum)
+    }
+
+    fn cos_small(&self, r: &BigInt) -> Result<BigInt, EngineError> {
+        let r2 = self.mul(r, r);
+        let mut term = self.one.clone();
+        let mut sum = self.one.clone();
+        let mut k: u32 = 1;
+        loop {
+            self.check_interrupt()?;
+            term = -self.mul(&term, &r2) / ((2 * k - 1) * (2 * k));
+            if term.is_zero() {
+                break;
+            }
+            sum += &term;
+            k += 1;
+        }
+        Ok(sum)
+    }
+
+    // atan(x) for |x| <= 1: halve the angle until the series converges fast
+    fn atan_small(&self, x: &BigInt) -> Result<BigInt, EngineError> {
+        let mut x = x.clone();
+        let mut doublings = 0;
+        let threshold = &self.one / 100;
+        while x.abs() > threshold {
+            self.check_interrupt()?;
+            // atan(x) = 2 atan(x / (1 + sqrt(1 + x^2)))
+            let root = (&self.one * (&self.one + self.mul(&x, &x))).sqrt();
+            x = self.div(&x, &(&self.one + root));
+            doublings += 1;
+        }
+        let x2 = self.mul(&x, &x);
+        let mut power = x.clone();
+        let mut sum = x.clone();
+        let mut k: u32 = 1;
+        loop {
+            self.check_interrupt()?;
+            power = -self.mul(&power, &x2);
+            let term = &power / (2 * k + 1);
+            if term.is_zero() {
+                break;
+            }
+            sum += term;
+            k += 1;
+        }
+        Ok(sum << doublings)
+    }
+}
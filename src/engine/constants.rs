@@ -0,0 +1,105 @@
+//! Built-in mathematical constants.
+//!
+//! Constants are consulted by `Expr::Variable` after all variable scopes, so a
+//! function parameter named `e` shadows Euler's number inside that function.
+//! They cannot be reassigned with `=` (see `Context::set_var`). Names are
+//! case-sensitive, except that `true` and `false` may be written in any case.
+//! When the context has a working precision the irrational constants are
+//! computed to that many digits.
+
+use super::bigfloat::BigFloat;
+use super::types::Number;
+use num::complex::Complex64;
+use std::f64::consts;
+
+pub struct Constant {
+    pub name: &'static str,
+    pub description: &'static str,
+    value: fn(Option<u32>) -> Number,
+}
+
+impl Constant {
+    /// The constant's value, to `precision` significant digits when given.
+    pub fn value(&self, precision: Option<u32>) -> Number {
+        (self.value)(precision)
+    }
+}
+
+pub const CONSTANTS: &[Constant] = &[
+    Constant {
+        name: "pi",
+        description: "Ratio of a circle's circumference to its diameter",
+        value: |precision| match precision {
+            Some(p) => Number::BigFloat(BigFloat::pi(p)),
+            None => Number::Float(consts::PI),
+        },
+    },
+    Constant {
+        name: "tau",
+        description: "Ratio of a circle's circumference to its radius (2π)",
+        value: |precision| match precision {
+            Some(p) => {
+                Number::BigFloat(BigFloat::pi(p + 1).mul(&BigFloat::from_integer(2.into(), p)))
+            }
+            None => Number::Float(consts::TAU),
+        },
+    },
+    Constant {
+        name: "e",
+        description: "Euler's number, the base of the natural logarithm",
+        value: |precision| match precision {
+            Some(p) => Number::BigFloat(BigFloat::e(p)),
+            None => Number::Float(consts::E),
+        },
+    },
+    Constant {
+        name: "phi",
+        description: "Golden ratio (1 + √5) / 2",
+        value: |precision| match precision {
+            Some(p) => {
+                let five = BigFloat::from_integer(5.into(), p + 2);
+                let root = five.sqrt().unwrap_or(five);
+                let phi = root.add(&BigFloat::one(p)).mul(&BigFloat::half(p));
+                Number::BigFloat(phi.with_precision(p))
+            }
+            None => Number::Float(1.618_033_988_749_895),
+        },
+    },
+    Constant {
+        name: "inf",
+        description: "Positive infinity, for the bounds of integrate",
+        value: |_| Number::Float(f64::INFINITY),
+    },
+    Constant {
+        name: "i",
+        description: "Imaginary unit, √-1",
+        value: |_| Number::Complex(Complex64::new(0.0, 1.0)),
+    },
+    Constant {
+        name: "true",
+        description: "Boolean true",
+        value: |_| Number::Boolean(true),
+    },
+    Constant {
+        name: "false",
+        description: "Boolean false",
+        value: |_| Number::Boolean(false),
+    },
+];
+
+pub fn get_constant(name: &str) -> Option<&'static Constant> {
+    let find = |name: &str| CONSTANTS.iter().find(|c| c.name == name);
+    // The booleans are matched in any case, like TRUE() and the logic functions
+    find(name).or_else(|| match name.to_ascii_lowercase().as_str() {
+        lower @ ("true" | "false") => find(lower),
+        _ => None,
+    })
+}
+
+pub fn is_constant(name: &str) -> bool {
+    get_constant(name).is_some()
+}
+
+pub fn lookup(name: &str, precision: Option<u32>) -> Option<Number> {
+    get_constant(name).map(|c| c.value(precision))
+}
@@ -0,0 +1,152 @@
+//! Exchange rates for currency units.
+//!
+//! ISO 4217 codes (`USD`, `EUR`, `BRL`, ...) are units of their own dimension
+//! (see `engine::units`), so `100 USD in EUR` and `10 USD + 5 EUR` work like
+//! any other conversion. The sizes of the currencies come from a `RateTable`
+//! that the host puts in `Context::rates`, read from JSON or built through
+//! the API; the engine never fetches rates itself. Rates are exact rationals
+//! read from their decimal text, so a rate of 0.92 turns 100 USD into
+//! exactly 92 EUR. Amounts in a single currency need no table at all.
+
+use super::bigfloat::{BigFloat, MAX_PRECISION};
+use super::errors::EngineError;
+use num::{One, Signed};
+use num_rational::BigRational;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The currency codes the parser recognizes as units.
+pub const CURRENCIES: &[&str] = &[
+    "AED", "ARS", "AUD", "BDT", "BGN", "BOB", "BRL", "CAD", "CHF", "CLP", "CNY", "COP", "CZK",
+    "DKK", "EGP", "EUR", "GBP", "HKD", "HUF", "IDR", "ILS", "INR", "ISK", "JPY", "KES", "KRW",
+    "KWD", "MXN", "MYR", "NGN", "NOK", "NZD", "PEN", "PHP", "PKR", "PLN", "PYG", "QAR", "RON",
+    "RUB", "SAR", "SEK", "SGD", "THB", "TRY", "TWD", "UAH", "USD", "UYU", "VND", "ZAR",
+];
+
+/// The static name of a known currency code.
+pub(crate) fn code(name: &str) -> Option<&'static str> {
+    CURRENCIES.iter().copied().find(|c| *c == name)
+}
+
+/// Exchange rates against a base currency, as published at `timestamp`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateTable {
+    base: String,
+    /// Units of each currency per one unit of `base`
+    rates: HashMap<String, BigRational>,
+    /// When the rates were published, in seconds since the Unix epoch
+    timestamp: u64,
+    /// How old the rates may be before conversions refuse them; `None`
+    /// accepts rates of any age
+    #[serde(default)]
+    pub max_age: Option<Duration>,
+}
+
+fn known_code(name: &str) -> Result<&'static str, EngineError> {
+    code(name).ok_or_else(|| EngineError::ExchangeRate(format!("unknown currency {}", name)))
+}
+
+impl RateTable {
+    /// An empty table for `base`, whose own rate is 1.
+    pub fn new(base: &str, timestamp: u64) -> Result<Self, EngineError> {
+        let base = known_code(base)?;
+        Ok(Self {
+            base: base.to_string(),
+            rates: HashMap::new(),
+            timestamp,
+            max_age: None,
+        })
+    }
+
+    /// Reads a table such as
+    /// `{"base": "USD", "timestamp": 1760572800, "rates": {"EUR": 0.92, "BRL": "5.43"}}`.
+    /// Rates may be numbers or strings and are read exactly as written.
+    pub fn from_json(json: &str) -> Result<Self, EngineError> {
+        let invalid =
+            |what: &str| EngineError::ExchangeRate(format!("invalid rate table: {}", what));
+        let value: serde_json::Value =
+            serde_json::from_str(json).map_err(|e| invalid(&e.to_string()))?;
+        let base = value["base"]
+            .as_str()
+            .ok_or_else(|| invalid("missing base"))?;
+        let timestamp = value["timestamp"]
+            .as_u64()
+            .ok_or_else(|| invalid("missing timestamp"))?;
+        let mut table = Self::new(base, timestamp)?;
+        let rates = value["rates"]
+            .as_object()
+            .ok_or_else(|| invalid("missing rates"))?;
+        for (code, rate) in rates {
+            let text = match rate {
+                serde_json::Value::Number(n) => n.to_string(),
+                serde_json::Value::String(s) => s.clone(),
+                _ => return Err(invalid(&format!("rate of {} is not a number", code))),
+            };
+            let rate = BigFloat::parse(&text, MAX_PRECISION)
+                .map(|r| r.to_rational())
+                .ok_or_else(|| invalid(&format!("rate of {} is not a number", code)))?;
+            table.set_rate(code, rate)?;
+        }
+        Ok(table)
+    }
+
+    /// Reads a JSON rate table (see `from_json`) from a file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, EngineError> {
+        let json = std::fs::read_to_string(path.as_ref()).map_err(|e| {
+            EngineError::ExchangeRate(format!("cannot read {}: {}", path.as_ref().display(), e))
+        })?;
+        Self::from_json(&json)
+    }
+
+    /// Sets how many units of `code` one unit of the base currency buys.
+    pub fn set_rate(&mut self, code: &str, rate: BigRational) -> Result<(), EngineError> {
+        let code = known_code(code)?;
+        if !rate.is_positive() {
+            return Err(EngineError::ExchangeRate(format!(
+                "rate of {} must be positive",
+                code
+            )));
+        }
+        self.rates.insert(code.to_string(), rate);
+        Ok(())
+    }
+
+    pub fn base(&self) -> &str {
+        &self.base
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    /// The rate of `code` against the base currency, if the table has one.
+    pub fn rate(&self, code: &str) -> Option<BigRational> {
+        if code == self.base {
+            Some(BigRational::one())
+        } else {
+            self.rates.get(code).cloned()
+        }
+    }
+
+    /// The size of one unit of `code` in the base currency, failing when the
+    /// table has no rate for it or is older than `max_age`.
+    pub(crate) fn value_of(&self, code: &str) -> Result<BigRational, EngineError> {
+        if let Some(max_age) = self.max_age {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs());
+            if now.saturating_sub(self.timestamp) > max_age.as_secs() {
+                return Err(EngineError::ExchangeRate(format!(
+                    "rates from {} are older than {} seconds",
+                    self.timestamp,
+                    max_age.as_secs()
+                )));
+            }
+        }
+        self.rate(code)
+            .map(|rate| rate.recip())
+            .ok_or_else(|| EngineError::ExchangeRate(format!("no rate for {}", code)))
+    }
+}
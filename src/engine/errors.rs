@@ -1,3 +1,5 @@
+use std::fmt;
+use std::ops::Range;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -14,21 +16,148 @@ pub enum EngineError {
     #[error("Function '{0}' requires exactly {1} argument(s)")]
     ArgumentMismatch(String, usize),
 
-    #[error("Function '{0}' is not known")]
-    UnknownFunction(String),
+    /// A builtin called with an argument count outside its declared arity.
+    #[error(
+        "Function '{name}' expects {} argument(s), got {got}",
+        arity_text(*.min, *.max)
+    )]
+    ArgumentCount {
+        name: String,
+        min: usize,
+        max: Option<usize>,
+        got: usize,
+    },
+
+    /// An unknown function name, with the closest known name if there is one.
+    #[error("Function '{0}' is not known{hint}", hint = did_you_mean(.1))]
+    UnknownFunction(String, Option<String>),
+
+    #[error("Cannot assign to constant '{0}'")]
+    ConstantAssignment(String),
+
+    #[error("Dimension mismatch: {0}")]
+    DimensionMismatch(String),
+
+    /// A currency conversion without a usable rate (see `engine::currency`).
+    #[error("Exchange rate unavailable: {0}")]
+    ExchangeRate(String),
+
+    #[error("Cannot redefine native function '{0}'")]
+    NativeRedefinition(String),
 
     #[error("Type mismatch: expected {0}, got {1}")]
     TypeMismatch(String, String),
 
+    #[error("Evaluation cancelled")]
+    Cancelled,
+
+    #[error("Resource limit exceeded: {0}")]
+    LimitExceeded(String),
+
     #[error("Parser error: {0}")]
-    ParserError(String),
+    ParserError(ParseError),
 
     #[error("{0}")]
     Generic(String),
 }
 
+fn arity_text(min: usize, max: Option<usize>) -> String {
+    match max {
+        Some(max) if max == min => min.to_string(),
+        Some(max) => format!("{} to {}", min, max),
+        None => format!("at least {}", min),
+    }
+}
+
+fn did_you_mean(suggestion: &Option<String>) -> String {
+    match suggestion {
+        Some(name) => format!("; did you mean '{}'?", name),
+        None => String::new(),
+    }
+}
+
 impl From<String> for EngineError {
     fn from(s: String) -> Self {
         EngineError::Generic(s)
     }
 }
+
+impl From<ParseError> for EngineError {
+    fn from(e: ParseError) -> Self {
+        EngineError::ParserError(e)
+    }
+}
+
+/// A syntax error with the location of the offending input.
+///
+/// `span` is a byte range into the parsed expression. An empty span at the end
+/// of the input means the expression ended before the parser was done.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub span: Range<usize>,
+    /// Human-readable descriptions of what would have been accepted here.
+    pub expected: Vec<String>,
+    /// The offending source text, or `None` at end of input.
+    pub found: Option<String>,
+}
+
+impl ParseError {
+    pub fn new(span: Range<usize>, expected: Vec<String>, found: Option<String>) -> Self {
+        ParseError {
+            span,
+            expected,
+            found,
+        }
+    }
+
+    /// Renders the source line containing the error with a caret underline
+    /// below the offending range, followed by the error message:
+    ///
+    /// ```text
+    /// 2 * (3 + 4
+    ///           ^
+    /// expected ')', found end of input
+    /// ```
+    pub fn render(&self, source: &str) -> String {
+        let start = self.span.start.min(source.len());
+        let end = self.span.end.clamp(start, source.len());
+
+        // Only the line holding the start of the span is shown.
+        let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[start..]
+            .find('\n')
+            .map_or(source.len(), |i| start + i);
+        let line = &source[line_start..line_end];
+
+        let column = source[line_start..start].chars().count();
+        let width = source[start..end.min(line_end)].chars().count().max(1);
+
+        format!(
+            "{}\n{}{}\n{}",
+            line,
+            " ".repeat(column),
+            "^".repeat(width),
+            self
+        )
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let found = match &self.found {
+            Some(text) => format!("'{}'", text),
+            None => "end of input".to_string(),
+        };
+        match self.expected.as_slice() {
+            [] => write!(f, "unexpected {}", found),
+            [only] => write!(f, "expected {}, found {}", only, found),
+            [init @ .., last] => write!(
+                f,
+                "expected {} or {}, found {}",
+                init.join(", "),
+                last,
+                found
+            ),
+        }
+    }
+}
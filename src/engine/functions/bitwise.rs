@@ -1,93 +1,122 @@
-use crate::engine::types::Number;
+use crate::engine::ast::Context;
 use crate::engine::errors::EngineError;
-use crate::engine::functions::FunctionDef;
-use num::ToPrimitive;
+use crate::engine::functions::{Category, FunctionDef};
+use crate::engine::types::Number;
+use crate::engine::word::WordSize;
+use num::{FromPrimitive, ToPrimitive};
 use num_bigint::BigInt;
 
 fn to_int(n: &Number) -> Result<BigInt, EngineError> {
     match n {
         Number::Integer(i) => Ok(i.clone()),
-        _ => Err(EngineError::TypeMismatch("Bitwise operation".into(), "Integer".into())),
+        // Integral reals such as floor(x) or a demoted complex result are accepted
+        Number::Rational(r) if r.is_integer() => Ok(r.to_integer()),
+        Number::Float(f) if f.fract() == 0.0 => BigInt::from_f64(*f)
+            .ok_or_else(|| EngineError::TypeMismatch("Bitwise operation".into(), "Integer".into())),
+        Number::BigFloat(b) if b.is_integer() => Ok(b.trunc()),
+        _ => Err(EngineError::TypeMismatch(
+            "Bitwise operation".into(),
+            "Integer".into(),
+        )),
     }
 }
 
-fn apply_binary_op<F>(args: &[Number], name: &str, op: F) -> Result<Number, EngineError>
+fn apply_binary_op<F>(args: &[Number], op: F) -> Result<Number, EngineError>
 where
     F: Fn(BigInt, BigInt) -> Result<Number, EngineError>,
 {
-    if args.len() != 2 {
-        return Err(EngineError::ArgumentMismatch(name.into(), 2));
-    }
     let a = to_int(&args[0])?;
     let b = to_int(&args[1])?;
     op(a, b)
 }
 
-pub fn band(args: &[Number]) -> Result<Number, EngineError> {
-    apply_binary_op(args, "band", |a, b| Ok(Number::Integer(a & b)))
+pub fn band(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    apply_binary_op(args, |a, b| Ok(Number::Integer(a & b)))
 }
 
-pub fn bor(args: &[Number]) -> Result<Number, EngineError> {
-    apply_binary_op(args, "bor", |a, b| Ok(Number::Integer(a | b)))
+pub fn bor(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    apply_binary_op(args, |a, b| Ok(Number::Integer(a | b)))
 }
 
-pub fn bxor(args: &[Number]) -> Result<Number, EngineError> {
-    apply_binary_op(args, "bxor", |a, b| Ok(Number::Integer(a ^ b)))
+pub fn bxor(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    apply_binary_op(args, |a, b| Ok(Number::Integer(a ^ b)))
 }
 
-pub fn bnot(args: &[Number]) -> Result<Number, EngineError> {
-    if args.len() != 1 {
-        return Err(EngineError::ArgumentMismatch("bnot".into(), 1));
-    }
-    let a = to_int(&args[0])?;
-    Ok(Number::Integer(!a))
+// In programmer mode results wrap into the word, so `bnot` flips only its
+// bits and `lsh` drops the ones shifted out, neither counting as an overflow
+fn in_word(i: BigInt, ctx: &Context) -> Number {
+    Number::Integer(match ctx.word_size {
+        Some(word) => word.wrap(&i),
+        None => i,
+    })
 }
 
-pub fn lsh(args: &[Number]) -> Result<Number, EngineError> {
-    apply_binary_op(args, "lsh", |a, b| {
-         if let Some(shift) = b.to_usize() {
-             Ok(Number::Integer(a << shift))
-         } else {
-             Err(EngineError::Generic("Shift count too large or negative".into()))
-         }
-    })
+pub fn bnot(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let a = to_int(&args[0])?;
+    Ok(in_word(!a, ctx))
 }
 
-pub fn rsh(args: &[Number]) -> Result<Number, EngineError> {
-    apply_binary_op(args, "rsh", |a, b| {
-         if let Some(shift) = b.to_usize() {
-             Ok(Number::Integer(a >> shift))
-         } else {
-             Err(EngineError::Generic("Shift count too large or negative".into()))
-         }
+pub fn lsh(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    apply_binary_op(args, |a, b| {
+        if let Some(shift) = b.to_usize() {
+            // Anything at or past the word's width shifts everything out
+            let shift = ctx
+                .word_size
+                .map_or(shift, |w| shift.min(w.bits() as usize));
+            // Checked before shifting, as the result may not fit in memory
+            if ctx.word_size.is_none() && a.bits() > 0 {
+                ctx.guard()
+                    .check_bits(a.bits().saturating_add(shift as u64))?;
+            }
+            Ok(in_word(a << shift, ctx))
+        } else {
+            Err(EngineError::Generic(
+                "Shift count too large or negative".into(),
+            ))
+        }
     })
 }
 
-pub fn rol(args: &[Number]) -> Result<Number, EngineError> {
-    apply_binary_op(args, "rol", |a, b| {
-        if let (Some(val), Some(rot)) = (a.to_i64(), b.to_u32()) {
-            Ok(Number::Integer(BigInt::from(val.rotate_left(rot))))
+pub fn rsh(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    apply_binary_op(args, |a, b| {
+        if let Some(shift) = b.to_usize() {
+            Ok(Number::Integer(a >> shift))
         } else {
-            Err(EngineError::Generic("Rotation arguments too large".into()))
+            Err(EngineError::Generic(
+                "Shift count too large or negative".into(),
+            ))
         }
     })
 }
 
-pub fn ror(args: &[Number]) -> Result<Number, EngineError> {
-    apply_binary_op(args, "ror", |a, b| {
-        if let (Some(val), Some(rot)) = (a.to_i64(), b.to_u32()) {
-            Ok(Number::Integer(BigInt::from(val.rotate_right(rot))))
-        } else {
-            Err(EngineError::Generic("Rotation arguments too large".into()))
+// Rotations need a width: the context's word, or 64-bit signed without one
+fn rotate(args: &[Number], ctx: &Context, right: bool) -> Result<Number, EngineError> {
+    let word = ctx.word_size.unwrap_or(WordSize::DEFAULT);
+    apply_binary_op(args, |a, b| {
+        if !word.contains(&a) {
+            return Err(EngineError::DomainError(format!(
+                "{} does not fit in {}",
+                a, word
+            )));
         }
+        let count = if right { -b } else { b };
+        Ok(Number::Integer(word.rotate_left(&a, &count)))
     })
 }
 
-inventory::submit! { FunctionDef { name: "band", func: band } }
-inventory::submit! { FunctionDef { name: "bor", func: bor } }
-inventory::submit! { FunctionDef { name: "bxor", func: bxor } }
-inventory::submit! { FunctionDef { name: "bnot", func: bnot } }
-inventory::submit! { FunctionDef { name: "lsh", func: lsh } }
-inventory::submit! { FunctionDef { name: "rsh", func: rsh } }
-inventory::submit! { FunctionDef { name: "rol", func: rol } }
-inventory::submit! { FunctionDef { name: "ror", func: ror } }
+pub fn rol(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    rotate(args, ctx, false)
+}
+
+pub fn ror(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    rotate(args, ctx, true)
+}
+
+inventory::submit! { FunctionDef::new("band", band, Category::Bitwise, "fn-band").params(&["a", "b"]) }
+inventory::submit! { FunctionDef::new("bor", bor, Category::Bitwise, "fn-bor").params(&["a", "b"]) }
+inventory::submit! { FunctionDef::new("bxor", bxor, Category::Bitwise, "fn-bxor").params(&["a", "b"]) }
+inventory::submit! { FunctionDef::new("bnot", bnot, Category::Bitwise, "fn-bnot").params(&["a"]) }
+inventory::submit! { FunctionDef::new("lsh", lsh, Category::Bitwise, "fn-lsh").params(&["a", "bits"]) }
+inventory::submit! { FunctionDef::new("rsh", rsh, Category::Bitwise, "fn-rsh").params(&["a", "bits"]) }
+inventory::submit! { FunctionDef::new("rol", rol, Category::Bitwise, "fn-rol").params(&["a", "bits"]) }
+inventory::submit! { FunctionDef::new("ror", ror, Category::Bitwise, "fn-ror").params(&["a", "bits"]) }
@@ -0,0 +1,302 @@
+//! Numeric calculus: derivatives, integrals and the sum and product operators.
+//!
+//! `deriv`, `integrate`, `sum` and `prod` take a function name or an
+//! expression followed by the variable it is in, so they are evaluated as
+//! special forms (see `engine::special_forms`), which bind the variable and
+//! call the kernels here. Any other call of `sum` or `prod` is a plain
+//! aggregate, registered in `statistics`. Derivatives use Ridders'
+//! extrapolation of central differences and integrals adaptive Gauss–Kronrod
+//! quadrature, both in `f64`, also when a working precision is set. An
+//! integral whose error estimate stays large is reported as a domain error
+//! rather than returned; `integrate.err` gives the estimate for one that
+//! converges.
+
+use crate::engine::ast::Context;
+use crate::engine::errors::EngineError;
+use crate::engine::functions::{Category, FunctionDef};
+use crate::engine::types::Number;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+// Step reduction between Ridders' tableau columns and the tableau size
+const RIDDERS_SHRINK: f64 = 1.4;
+const RIDDERS_TABLE: usize = 10;
+
+/// f'(x) by Ridders' method: central differences with shrinking steps,
+/// extrapolated to step zero. Stops early once the extrapolation starts to
+/// lose accuracy to rounding.
+pub(crate) fn derivative(
+    f: &mut impl FnMut(f64) -> Result<f64, EngineError>,
+    x: f64,
+) -> Result<f64, EngineError> {
+    let shrink2 = RIDDERS_SHRINK * RIDDERS_SHRINK;
+    let mut h = 0.1 * x.abs().max(1.0);
+    let mut central =
+        |h: f64| -> Result<f64, EngineError> { Ok((f(x + h)? - f(x - h)?) / (2.0 * h)) };
+    let mut table = [[0.0; RIDDERS_TABLE]; RIDDERS_TABLE];
+    table[0][0] = central(h)?;
+    let mut best = table[0][0];
+    let mut error = f64::INFINITY;
+    for i in 1..RIDDERS_TABLE {
+        h /= RIDDERS_SHRINK;
+        table[0][i] = central(h)?;
+        let mut factor = shrink2;
+        for j in 1..=i {
+            table[j][i] = (table[j - 1][i] * factor - table[j - 1][i - 1]) / (factor - 1.0);
+            factor *= shrink2;
+            let change = (table[j][i] - table[j - 1][i])
+                .abs()
+                .max((table[j][i] - table[j - 1][i - 1]).abs());
+            if change <= error {
+                error = change;
+                best = table[j][i];
+            }
+        }
+        if (table[i][i] - table[i - 1][i - 1]).abs() >= 2.0 * error {
+            break;
+        }
+    }
+    Ok(best)
+}
+
+// Nodes of the 15-point Kronrod rule on [-1, 1], largest first, with 0 last.
+// The odd-indexed nodes and 0 are those of the 7-point Gauss rule.
+const KRONROD_NODES: [f64; 8] = [
+    0.991_455_371_120_812_6,
+    0.949_107_912_342_758_5,
+    0.864_864_423_359_769_1,
+    0.741_531_185_599_394_5,
+    0.586_087_235_467_691_1,
+    0.405_845_151_377_397_2,
+    0.207_784_955_007_898_48,
+    0.0,
+];
+const KRONROD_WEIGHTS: [f64; 8] = [
+    0.022_935_322_010_529_224,
+    0.063_092_092_629_978_56,
+    0.104_790_010_322_250_19,
+    0.140_653_259_715_525_92,
+    0.169_004_726_639_267_9,
+    0.190_350_578_064_785_42,
+    0.204_432_940_075_298_89,
+    0.209_482_141_084_727_82,
+];
+const GAUSS_WEIGHTS: [f64; 4] = [
+    0.129_484_966_168_869_7,
+    0.279_705_391_489_276_64,
+    0.381_830_050_505_118_9,
+    0.417_959_183_673_469_4,
+];
+
+// Subintervals an integral may be split into before giving up
+const MAX_SEGMENTS: usize = 20_000;
+
+/// The value of an integral and an estimate of its absolute error.
+pub(crate) struct Estimate {
+    pub value: f64,
+    pub error: f64,
+}
+
+// An estimate that overflowed: the integral grows without bound
+fn diverges() -> EngineError {
+    EngineError::DomainError("integrate diverges: the estimate grows without bound".to_string())
+}
+
+// The Kronrod estimate over one subinterval, with an error estimate and
+// ∫ |f| to judge rounding by
+struct Segment {
+    a: f64,
+    b: f64,
+    value: f64,
+    error: f64,
+    magnitude: f64,
+}
+
+// Segments are ordered by their error, so the heap yields the worst first
+impl PartialEq for Segment {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Segment {}
+
+impl PartialOrd for Segment {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Segment {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.error.total_cmp(&other.error)
+    }
+}
+
+// The difference between the Kronrod and embedded Gauss estimates is scaled
+// as in QUADPACK's qk15, since used as it is it overstates the error of the
+// Kronrod estimate by orders of magnitude
+fn kronrod(
+    f: &mut impl FnMut(f64) -> Result<f64, EngineError>,
+    a: f64,
+    b: f64,
+) -> Result<Segment, EngineError> {
+    let center = 0.5 * (a + b);
+    let half = 0.5 * (b - a);
+    let mut values = [(0.0, 0.0); 7];
+    for (value, node) in values.iter_mut().zip(KRONROD_NODES) {
+        *value = (f(center - half * node)?, f(center + half * node)?);
+    }
+    let fc = f(center)?;
+    let mut kronrod = fc * KRONROD_WEIGHTS[7];
+    let mut gauss = fc * GAUSS_WEIGHTS[3];
+    let mut magnitude = fc.abs() * KRONROD_WEIGHTS[7];
+    for (i, (left, right)) in values.iter().enumerate() {
+        kronrod += KRONROD_WEIGHTS[i] * (left + right);
+        magnitude += KRONROD_WEIGHTS[i] * (left.abs() + right.abs());
+        if i % 2 == 1 {
+            gauss += GAUSS_WEIGHTS[i / 2] * (left + right);
+        }
+    }
+    // ∫ |f - mean| over the subinterval, in the same units
+    let mean = 0.5 * kronrod;
+    let mut spread = KRONROD_WEIGHTS[7] * (fc - mean).abs();
+    for (i, (left, right)) in values.iter().enumerate() {
+        spread += KRONROD_WEIGHTS[i] * ((left - mean).abs() + (right - mean).abs());
+    }
+    let mut error = ((kronrod - gauss) * half).abs();
+    let spread = spread * half.abs();
+    if spread != 0.0 && error != 0.0 {
+        error = spread * (200.0 * error / spread).powf(1.5).min(1.0);
+    }
+    Ok(Segment {
+        a,
+        b,
+        value: kronrod * half,
+        error,
+        magnitude: magnitude * half.abs(),
+    })
+}
+
+// Bisects the subinterval with the largest error estimate until the total
+// estimate is within a relative 1e-12, or within rounding of ∫ |f|. The
+// totals are kept as running sums and recomputed before they are trusted.
+fn adaptive(
+    f: &mut impl FnMut(f64) -> Result<f64, EngineError>,
+    a: f64,
+    b: f64,
+) -> Result<Estimate, EngineError> {
+    let first = kronrod(f, a, b)?;
+    let (mut value, mut error, mut magnitude) = (first.value, first.error, first.magnitude);
+    let mut segments = BinaryHeap::from([first]);
+    let totals = |segments: &BinaryHeap<Segment>| {
+        (
+            segments.iter().map(|s| s.value).sum::<f64>(),
+            segments.iter().map(|s| s.error).sum::<f64>(),
+            segments.iter().map(|s| s.magnitude).sum::<f64>(),
+        )
+    };
+    loop {
+        let converged = |value: f64, error: f64, magnitude: f64| {
+            error <= (1e-12 * value.abs()).max(50.0 * f64::EPSILON * magnitude)
+        };
+        if converged(value, error, magnitude) || !(value.is_finite() && error.is_finite()) {
+            (value, error, magnitude) = totals(&segments);
+            if !(value.is_finite() && error.is_finite()) {
+                return Err(diverges());
+            }
+            if converged(value, error, magnitude) {
+                return Ok(Estimate { value, error });
+            }
+        }
+        let worst = segments
+            .pop()
+            .expect("an integral has at least one segment");
+        let mid = 0.5 * (worst.a + worst.b);
+        if segments.len() >= MAX_SEGMENTS || mid <= worst.a || mid >= worst.b {
+            segments.push(worst);
+            let (_, error, _) = totals(&segments);
+            if !error.is_finite() {
+                return Err(diverges());
+            }
+            return Err(EngineError::DomainError(format!(
+                "integrate did not converge; the estimated error is {:e}",
+                error
+            )));
+        }
+        let (left, right) = (kronrod(f, worst.a, mid)?, kronrod(f, mid, worst.b)?);
+        value += left.value + right.value - worst.value;
+        error += left.error + right.error - worst.error;
+        magnitude += left.magnitude + right.magnitude - worst.magnitude;
+        segments.push(left);
+        segments.push(right);
+    }
+}
+
+/// ∫ f from `a` to `b`, with an estimate of its absolute error. Infinite
+/// bounds are mapped onto a finite interval by x = a + (1 - t)/t, which the
+/// quadrature never evaluates at t = 0.
+pub(crate) fn integral(
+    f: &mut impl FnMut(f64) -> Result<f64, EngineError>,
+    a: f64,
+    b: f64,
+) -> Result<Estimate, EngineError> {
+    if a.is_nan() || b.is_nan() {
+        return Err(EngineError::DomainError(
+            "integrate needs real bounds".to_string(),
+        ));
+    }
+    if a == b {
+        return Ok(Estimate {
+            value: 0.0,
+            error: 0.0,
+        });
+    }
+    if a > b {
+        return integral(f, b, a).map(|estimate| Estimate {
+            value: -estimate.value,
+            ..estimate
+        });
+    }
+    match (a.is_finite(), b.is_finite()) {
+        (true, true) => adaptive(f, a, b),
+        (true, false) => adaptive(&mut |t: f64| Ok(f(a + (1.0 - t) / t)? / (t * t)), 0.0, 1.0),
+        (false, true) => adaptive(&mut |t: f64| Ok(f(b - (1.0 - t) / t)? / (t * t)), 0.0, 1.0),
+        (false, false) => {
+            let left = integral(f, f64::NEG_INFINITY, 0.0)?;
+            let right = integral(f, 0.0, f64::INFINITY)?;
+            Ok(Estimate {
+                value: left.value + right.value,
+                error: left.error + right.error,
+            })
+        }
+    }
+}
+
+// The forms are evaluated by `engine::special_forms` before their arguments
+// are; the builtins below are only reached when one is called by name with
+// evaluated arguments, as in `deriv(integrate, 1)`, where there is nothing to bind.
+fn unevaluated(name: &str) -> EngineError {
+    EngineError::DomainError(format!(
+        "{} needs a function name or an expression and its variable",
+        name
+    ))
+}
+
+pub fn deriv(_args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    Err(unevaluated("deriv"))
+}
+
+pub fn integrate(_args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    Err(unevaluated("integrate"))
+}
+
+pub fn integrate_err(_args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    Err(unevaluated("integrate.err"))
+}
+
+// The parameters are those of the function name form; `binds_variable` makes
+// room for the variable that follows an inline expression
+inventory::submit! { FunctionDef::new("deriv", deriv, Category::Calculus, "fn-deriv").params(&["f", "x"]).binds_variable() }
+inventory::submit! { FunctionDef::new("integrate", integrate, Category::Calculus, "fn-integrate").params(&["f", "a", "b"]).binds_variable() }
+inventory::submit! { FunctionDef::new("integrate.err", integrate_err, Category::Calculus, "fn-integrate-err").params(&["f", "a", "b"]).binds_variable() }
@@ -1,55 +1,41 @@
+use crate::engine::ast::Context;
 use crate::engine::errors::EngineError;
-use crate::engine::functions::FunctionDef;
+use crate::engine::functions::{Category, FunctionDef};
 use crate::engine::types::Number;
-use num::Signed;
 
-pub fn conj(args: &[Number]) -> Result<Number, EngineError> {
-    if args.len() != 1 {
-        return Err(EngineError::ArgumentMismatch("conj".into(), 1));
-    }
+pub fn conj(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
     match &args[0] {
         Number::Complex(c) => Ok(Number::Complex(c.conj())),
         n => Ok(n.clone()), // Real numbers are their own conjugate
     }
 }
 
-pub fn re(args: &[Number]) -> Result<Number, EngineError> {
-    if args.len() != 1 {
-        return Err(EngineError::ArgumentMismatch("re".into(), 1));
-    }
+pub fn re(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
     match &args[0] {
         Number::Complex(c) => Ok(Number::Float(c.re)), // Complex parts are floats
         n => Ok(n.clone()),                            // Real part of real is self
     }
 }
 
-pub fn im(args: &[Number]) -> Result<Number, EngineError> {
-    if args.len() != 1 {
-        return Err(EngineError::ArgumentMismatch("im".into(), 1));
-    }
+pub fn im(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
     match &args[0] {
         Number::Complex(c) => Ok(Number::Float(c.im)),
-        Number::Integer(_) | Number::Rational(_) => {
+        Number::Integer(_) | Number::Rational(_) | Number::BigFloat(_) => {
             Ok(Number::Integer(num_bigint::BigInt::from(0)))
         }
         Number::Float(_) => Ok(Number::Float(0.0)),
+        // `functions::apply` maps lists element by element before this point
+        Number::List(_)
+        | Number::Matrix(_)
+        | Number::Text(_)
+        | Number::Boolean(_)
+        | Number::Quantity(_) => Err(EngineError::TypeMismatch(
+            "number".into(),
+            args[0].type_name().into(),
+        )),
     }
 }
 
-pub fn abs(args: &[Number]) -> Result<Number, EngineError> {
-    if args.len() != 1 {
-        return Err(EngineError::ArgumentMismatch("abs".into(), 1));
-    }
-    match &args[0] {
-        Number::Integer(i) => Ok(Number::Integer(i.abs())),
-        Number::Rational(r) => Ok(Number::Rational(r.abs())),
-        Number::Float(f) => Ok(Number::Float(f.abs())),
-        Number::Complex(c) => Ok(Number::Float(c.norm())),
-    }
-}
-
-inventory::submit! { FunctionDef { name: "conj", func: conj } }
-inventory::submit! { FunctionDef { name: "re", func: re } }
-inventory::submit! { FunctionDef { name: "im", func: im } }
-inventory::submit! { FunctionDef { name: "lm", func: im } } // Alias
-inventory::submit! { FunctionDef { name: "abs", func: abs } }
+inventory::submit! { FunctionDef::new("conj", conj, Category::Complex, "fn-conj").params(&["z"]) }
+inventory::submit! { FunctionDef::new("re", re, Category::Complex, "fn-re").params(&["z"]) }
+inventory::submit! { FunctionDef::new("im", im, Category::Complex, "fn-im").params(&["z"]).aliases(&["lm"]) }
@@ -1,71 +1,122 @@
+use crate::engine::ast::Context;
+use crate::engine::bigfloat::BigFloat;
 use crate::engine::errors::EngineError;
-use crate::engine::functions::FunctionDef;
-use crate::engine::types::Number;
-use num::Signed;
-use num::complex::Complex64;
+use crate::engine::functions::{Category, FunctionDef, precise_arg};
+use crate::engine::types::{Number, exact_root};
+use num::{Signed, ToPrimitive};
 
-fn one_arg(args: &[Number], name: &str) -> Result<Complex64, EngineError> {
-    if args.len() != 1 {
-        return Err(EngineError::ArgumentMismatch(name.into(), 1));
+// Logarithms and roots of negative numbers are complex and stay in f64
+pub fn log(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let z = args[0].to_complex();
+    if let Some(x) = precise_arg(args, ctx).filter(|x| !x.is_negative() && !x.is_zero()) {
+        let ln10 = BigFloat::ln10(x.precision());
+        return Ok(Number::BigFloat(
+            x.ln(&ctx.guard())?
+                .div(&ln10)
+                .ok_or(EngineError::DivisionByZero)?,
+        ));
     }
-    Ok(args[0].to_complex())
-}
-
-pub fn log(args: &[Number]) -> Result<Number, EngineError> {
-    Ok(Number::Complex(one_arg(args, "log")?.log(10.0)))
+    if z.im == 0.0 && z.re >= 0.0 {
+        return Ok(Number::Float(z.re.log10()));
+    }
+    Ok(Number::Complex(z.log(10.0)))
 }
 
-pub fn ln(args: &[Number]) -> Result<Number, EngineError> {
-    Ok(Number::Complex(one_arg(args, "ln")?.ln()))
+pub fn ln(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let z = args[0].to_complex();
+    if let Some(x) = precise_arg(args, ctx).filter(|x| !x.is_negative() && !x.is_zero()) {
+        return Ok(Number::BigFloat(x.ln(&ctx.guard())?));
+    }
+    if z.im == 0.0 && z.re >= 0.0 {
+        return Ok(Number::Float(z.re.ln()));
+    }
+    Ok(Number::Complex(z.ln()))
 }
 
-pub fn sqrt(args: &[Number]) -> Result<Number, EngineError> {
-    Ok(Number::Complex(one_arg(args, "sqrt")?.sqrt()))
+pub fn sqrt(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let z = args[0].to_complex();
+    if let Some(root) = exact_root(&args[0], 2) {
+        return Ok(root);
+    }
+    if let Some(x) = precise_arg(args, ctx).filter(|x| !x.is_negative()) {
+        return Ok(Number::BigFloat(x.sqrt()?));
+    }
+    if z.im == 0.0 && z.re >= 0.0 {
+        return Ok(Number::Float(z.re.sqrt()));
+    }
+    Ok(Number::Complex(z.sqrt()))
 }
 
-// Helper for optional second argument (default 0)
-fn get_two_args(args: &[Number]) -> Result<(Number, i32), EngineError> {
-    if args.is_empty() || args.len() > 2 {
-        return Err(EngineError::ArgumentMismatch("function".into(), 1)); // Simplified error
+/// `nthroot(x, n)`: the real `n`th root, so odd roots of negative numbers are
+/// negative (`nthroot(-8, 3) = -2`) where `x^(1/3)` would be complex.
+pub fn nthroot(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let n = match &args[1] {
+        Number::Integer(n) if n.is_positive() => n
+            .to_u32()
+            .ok_or_else(|| EngineError::DomainError("nthroot degree is too large".into()))?,
+        _ => {
+            return Err(EngineError::DomainError(
+                "nthroot degree must be a positive integer".into(),
+            ));
+        }
+    };
+    if let Some(root) = exact_root(&args[0], n) {
+        return Ok(root);
     }
-    let val = args[0].clone();
-    let digits = if args.len() == 2 {
-        match args[1].to_f64() {
-            Some(f) => f as i32,
-            None => 0,
+    let z = args[0].to_complex();
+    if z.im != 0.0 || (z.re < 0.0 && n.is_multiple_of(2)) {
+        // No real root; the principal complex root
+        return Ok(Number::Complex(z.powf(1.0 / n as f64)));
+    }
+    // Root of the magnitude, with the sign put back for odd roots
+    let negative = z.re < 0.0;
+    let root = match precise_arg(args, ctx) {
+        Some(x) => {
+            let inverse = BigFloat::one(x.precision())
+                .div(&BigFloat::from_integer(n.into(), x.precision()))
+                .ok_or(EngineError::DivisionByZero)?;
+            Number::BigFloat(x.abs().pow(&inverse, &ctx.guard())?)
         }
-    } else {
-        0
+        None => Number::Float(z.re.abs().powf(1.0 / n as f64)),
     };
-    Ok((val, digits))
+    Ok(if negative { -root } else { root })
 }
 
-pub fn abs(args: &[Number]) -> Result<Number, EngineError> {
-    if args.len() != 1 {
-        return Err(EngineError::ArgumentMismatch("abs".into(), 1));
-    }
+// Optional digits argument of round (default 0)
+fn digits_arg(args: &[Number]) -> i32 {
+    args.get(1).and_then(|d| d.to_f64()).map_or(0, |f| f as i32)
+}
+
+pub fn abs(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
     // Generic abs logic
     match &args[0] {
         Number::Integer(i) => Ok(Number::Integer(i.abs())),
         Number::Rational(r) => Ok(Number::Rational(r.abs())),
         Number::Float(f) => Ok(Number::Float(f.abs())),
+        Number::BigFloat(b) => Ok(Number::BigFloat(b.abs())),
         Number::Complex(c) => Ok(Number::Float(c.norm())), // Magnitude for complex
+        // `functions::apply` maps lists element by element before this point
+        Number::List(_)
+        | Number::Matrix(_)
+        | Number::Text(_)
+        | Number::Boolean(_)
+        | Number::Quantity(_) => Err(EngineError::TypeMismatch(
+            "number".into(),
+            args[0].type_name().into(),
+        )),
     }
 }
 
-pub fn fact(args: &[Number]) -> Result<Number, EngineError> {
-    if args.len() != 1 {
-        return Err(EngineError::ArgumentMismatch("fact".into(), 1));
-    }
-    // Using the function defined in types.rs (re-exported or accessible?)
-    // Actually types.rs defined it as a standalone function `pub fn factorial`.
-    // We need to import it or access it. It seems types.rs is in the same module tree.
-    // Let's assume crate::engine::types::factorial exists.
-    crate::engine::types::factorial(args[0].clone())
+pub fn fact(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    // Shares the implementation (and resource limits) of the postfix `!` operator
+    crate::engine::types::factorial(args[0].clone(), &ctx.guard())
 }
 
-pub fn round(args: &[Number]) -> Result<Number, EngineError> {
-    let (val, digits) = get_two_args(args)?;
+pub fn round(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let (val, digits) = (&args[0], digits_arg(args));
+    if let Number::BigFloat(b) = val {
+        return Ok(Number::BigFloat(b.round_to(digits as i64)));
+    }
     let f = val
         .to_f64()
         .ok_or(EngineError::Generic("Cannot convert to float".into()))?;
@@ -73,43 +124,48 @@ pub fn round(args: &[Number]) -> Result<Number, EngineError> {
     Ok(Number::Float((f * multiplier).round() / multiplier))
 }
 
-pub fn floor(args: &[Number]) -> Result<Number, EngineError> {
-    let (val, _digits) = get_two_args(args)?; // OpenFormula FLOOR has significance, we simplify to math floor for now or implement significance later if requested exact ODFF.
+pub fn floor(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    // OpenFormula FLOOR has a significance argument; this is the plain math floor
+    let val = &args[0];
     // Standard Math FLOOR(x)
+    if let Number::BigFloat(b) = val {
+        return Ok(Number::Integer(b.floor()));
+    }
     let f = val
         .to_f64()
         .ok_or(EngineError::Generic("Cannot convert to float".into()))?;
     Ok(Number::Float(f.floor()))
 }
 
-pub fn ceiling(args: &[Number]) -> Result<Number, EngineError> {
-    let (val, _digits) = get_two_args(args)?;
+pub fn ceiling(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let val = &args[0];
+    if let Number::BigFloat(b) = val {
+        return Ok(Number::Integer(b.ceil()));
+    }
     let f = val
         .to_f64()
         .ok_or(EngineError::Generic("Cannot convert to float".into()))?;
     Ok(Number::Float(f.ceil()))
 }
 
-pub fn trunc(args: &[Number]) -> Result<Number, EngineError> {
-    let (val, _digits) = get_two_args(args)?;
+pub fn trunc(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let val = &args[0];
+    if let Number::BigFloat(b) = val {
+        return Ok(Number::Integer(b.trunc()));
+    }
     let f = val
         .to_f64()
         .ok_or(EngineError::Generic("Cannot convert to float".into()))?;
     Ok(Number::Float(f.trunc()))
 }
 
-inventory::submit! { FunctionDef { name: "log", func: log } }
-inventory::submit! { FunctionDef { name: "ln", func: ln } }
-inventory::submit! { FunctionDef { name: "sqrt", func: sqrt } }
-inventory::submit! { FunctionDef { name: "abs", func: abs } }
-inventory::submit! { FunctionDef { name: "ABS", func: abs } }
-inventory::submit! { FunctionDef { name: "fact", func: fact } }
-inventory::submit! { FunctionDef { name: "FACT", func: fact } }
-inventory::submit! { FunctionDef { name: "round", func: round } }
-inventory::submit! { FunctionDef { name: "ROUND", func: round } }
-inventory::submit! { FunctionDef { name: "floor", func: floor } }
-inventory::submit! { FunctionDef { name: "FLOOR", func: floor } }
-inventory::submit! { FunctionDef { name: "ceil", func: ceiling } }
-inventory::submit! { FunctionDef { name: "CEILING", func: ceiling } }
-inventory::submit! { FunctionDef { name: "trunc", func: trunc } }
-inventory::submit! { FunctionDef { name: "TRUNC", func: trunc } }
+inventory::submit! { FunctionDef::new("log", log, Category::Arithmetic, "fn-log").params(&["x"]) }
+inventory::submit! { FunctionDef::new("ln", ln, Category::Arithmetic, "fn-ln").params(&["x"]) }
+inventory::submit! { FunctionDef::new("sqrt", sqrt, Category::Arithmetic, "fn-sqrt").params(&["x"]) }
+inventory::submit! { FunctionDef::new("nthroot", nthroot, Category::Arithmetic, "fn-nthroot").params(&["x", "n"]) }
+inventory::submit! { FunctionDef::new("abs", abs, Category::Arithmetic, "fn-abs").params(&["x"]) }
+inventory::submit! { FunctionDef::new("fact", fact, Category::Arithmetic, "fn-fact").params(&["n"]) }
+inventory::submit! { FunctionDef::new("round", round, Category::Arithmetic, "fn-round").params(&["x", "digits"]).optional(1) }
+inventory::submit! { FunctionDef::new("floor", floor, Category::Arithmetic, "fn-floor").params(&["x"]) }
+inventory::submit! { FunctionDef::new("ceil", ceiling, Category::Arithmetic, "fn-ceil").params(&["x"]).aliases(&["ceiling"]) }
+inventory::submit! { FunctionDef::new("trunc", trunc, Category::Arithmetic, "fn-trunc").params(&["x"]) }
@@ -0,0 +1,782 @@
+//! Probability distributions and hypothesis tests under their spreadsheet
+//! names: `norm.dist`, `t.inv`, `chisq.dist.rt`, `z.test`, ...
+//!
+//! The `.dist` functions give the density (or probability mass) and, when
+//! their `cumulative` argument is true, the distribution function; `.rt`
+//! variants give the right tail and `.2t` both tails. The `.inv` functions
+//! invert the distribution function, for the continuous distributions by
+//! Newton steps inside a bisection bracket. Results are `f64`, except that
+//! the binomial distribution is exact for an exact probability and up to
+//! 1000 trials. A float probability with a short binary value, such as 0.5
+//! or 0.125, counts as exact.
+
+use crate::engine::ast::Context;
+use crate::engine::errors::EngineError;
+use crate::engine::functions::logic::is_truthy;
+use crate::engine::functions::number_theory::choose;
+use crate::engine::functions::special::{beta_inc, erfc_real, gamma_inc, lgamma_real};
+use crate::engine::functions::{Category, FunctionDef};
+use crate::engine::limits::Guard;
+use crate::engine::types::{Number, from_ratio};
+use num::{One, ToPrimitive, Zero};
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use std::f64::consts::{LN_2, PI, SQRT_2, TAU};
+
+// Above this many trials the binomial distribution is computed in floats
+const EXACT_TRIALS: u64 = 1000;
+
+// Largest denominator of a float probability taken as exact, such as 0.5 or 0.125
+const EXACT_DENOMINATOR: u32 = 1 << 16;
+
+// Counts beyond 2^53 are not exact in the f64 the distributions work in
+const MAX_COUNT: f64 = 9_007_199_254_740_992.0;
+
+fn real(arg: &Number, name: &str) -> Result<f64, EngineError> {
+    // Only the `cumulative` flags take booleans
+    if let Number::Boolean(_) = arg {
+        return Err(EngineError::TypeMismatch(
+            "number".into(),
+            arg.type_name().into(),
+        ));
+    }
+    arg.to_f64()
+        .filter(|x| x.is_finite())
+        .ok_or_else(|| EngineError::DomainError(format!("{} needs finite real arguments", name)))
+}
+
+// Fails with a domain error saying what `name` needs unless `ok`
+fn require(ok: bool, name: &str, what: &str) -> Result<(), EngineError> {
+    if ok {
+        Ok(())
+    } else {
+        Err(EngineError::DomainError(format!("{} needs {}", name, what)))
+    }
+}
+
+fn positive(arg: &Number, name: &str, what: &str) -> Result<f64, EngineError> {
+    let value = real(arg, name)?;
+    require(value > 0.0, name, what)?;
+    Ok(value)
+}
+
+// A probability strictly between 0 and 1, as the continuous inverses take
+fn probability(arg: &Number, name: &str) -> Result<f64, EngineError> {
+    let p = real(arg, name)?;
+    require(p > 0.0 && p < 1.0, name, "a probability between 0 and 1")?;
+    Ok(p)
+}
+
+// A non-negative integer such as a number of trials
+fn count(arg: &Number, name: &str, what: &str) -> Result<u64, EngineError> {
+    let value = real(arg, name)?;
+    require(
+        value >= 0.0 && value.fract() == 0.0 && value <= MAX_COUNT,
+        name,
+        what,
+    )?;
+    Ok(value as u64)
+}
+
+// The tails of the standard normal distribution at z
+fn normal_tails(z: f64) -> (f64, f64) {
+    (0.5 * erfc_real(-z / SQRT_2), 0.5 * erfc_real(z / SQRT_2))
+}
+
+fn normal_pdf(z: f64) -> f64 {
+    (-z * z / 2.0).exp() / TAU.sqrt()
+}
+
+// Student's t with `df` degrees of freedom, through
+// P(|T| > t) = I_(df / (df + t²))(df/2, 1/2)
+fn t_tails(t: f64, df: f64) -> (f64, f64) {
+    let square = t * t;
+    let (x, y) = (df / (df + square), square / (df + square));
+    let tail = 0.5 * beta_inc(df / 2.0, 0.5, x, y).0;
+    if t > 0.0 {
+        (1.0 - tail, tail)
+    } else {
+        (tail, 1.0 - tail)
+    }
+}
+
+fn t_pdf(t: f64, df: f64) -> f64 {
+    let ln_norm = lgamma_real((df + 1.0) / 2.0) - lgamma_real(df / 2.0) - 0.5 * (df * PI).ln();
+    (ln_norm - (df + 1.0) / 2.0 * (t * t / df).ln_1p()).exp()
+}
+
+fn chisq_tails(x: f64, df: f64) -> (f64, f64) {
+    gamma_inc(df / 2.0, x / 2.0)
+}
+
+// A density on [0, ∞) behaving like x^(k - 1) at 0, whose value there is
+// infinite, `at_one` or 0 as k is below, at or above 1
+fn density_at_zero(k: f64, at_one: f64) -> f64 {
+    if k < 1.0 {
+        f64::INFINITY
+    } else if k == 1.0 {
+        at_one
+    } else {
+        0.0
+    }
+}
+
+fn chisq_pdf(x: f64, df: f64) -> f64 {
+    let k = df / 2.0;
+    if x == 0.0 {
+        return density_at_zero(k, 0.5);
+    }
+    ((k - 1.0) * x.ln() - x / 2.0 - k * LN_2 - lgamma_real(k)).exp()
+}
+
+// Fisher's F with (d1, d2) degrees of freedom, through
+// P(F <= x) = I_(d1 x / (d1 x + d2))(d1/2, d2/2)
+fn f_tails(x: f64, d1: f64, d2: f64) -> (f64, f64) {
+    let u = d1 * x;
+    beta_inc(d1 / 2.0, d2 / 2.0, u / (u + d2), d2 / (u + d2))
+}
+
+fn f_pdf(x: f64, d1: f64, d2: f64) -> f64 {
+    let (a, b) = (d1 / 2.0, d2 / 2.0);
+    if x == 0.0 {
+        return density_at_zero(a, 1.0);
+    }
+    let u = d1 * x;
+    let ln_beta = lgamma_real(a) + lgamma_real(b) - lgamma_real(a + b);
+    (a * u.ln() + b * d2.ln() - (a + b) * (u + d2).ln() - x.ln() - ln_beta).exp()
+}
+
+// The x >= 0 at which a distribution has lower tail p and upper tail q
+// (p + q = 1), given its tails and density. The smaller tail is matched so
+// that far quantiles stay accurate. Newton steps are taken while they stay
+// inside a shrinking bracket, and bisection steps otherwise.
+fn quantile(p: f64, q: f64, tails: impl Fn(f64) -> (f64, f64), pdf: impl Fn(f64) -> f64) -> f64 {
+    // Increasing in x and zero at the quantile
+    let error = |x: f64| {
+        let (lower, upper) = tails(x);
+        if p <= q { lower - p } else { q - upper }
+    };
+    // Bracket the quantile within a factor of 2
+    let (mut lo, mut hi) = (1.0, 1.0);
+    if error(1.0) > 0.0 {
+        while error(lo) > 0.0 {
+            hi = lo;
+            lo /= 2.0;
+            if lo == 0.0 {
+                return 0.0;
+            }
+        }
+    } else {
+        while error(hi) <= 0.0 {
+            lo = hi;
+            hi *= 2.0;
+            if hi.is_infinite() {
+                return f64::INFINITY;
+            }
+        }
+    }
+    let mut x = 0.5 * (lo + hi);
+    for _ in 0..1000 {
+        let e = error(x);
+        if e == 0.0 {
+            return x;
+        }
+        if e > 0.0 {
+            hi = x;
+        } else {
+            lo = x;
+        }
+        let newton = x - e / pdf(x);
+        let next = if newton > lo && newton < hi {
+            newton
+        } else {
+            0.5 * (lo + hi)
+        };
+        if (next - x).abs() <= 2.0 * f64::EPSILON * x {
+            return next;
+        }
+        x = next;
+    }
+    x
+}
+
+// Quantiles of the distributions symmetric about 0, from their x >= 0 half
+fn symmetric_quantile(
+    p: f64,
+    q: f64,
+    tails: impl Fn(f64) -> (f64, f64),
+    pdf: impl Fn(f64) -> f64,
+) -> f64 {
+    if p == q {
+        0.0
+    } else if p < q {
+        -quantile(q, p, tails, pdf)
+    } else {
+        quantile(p, q, tails, pdf)
+    }
+}
+
+fn normal_quantile(p: f64, q: f64) -> f64 {
+    symmetric_quantile(p, q, normal_tails, normal_pdf)
+}
+
+fn t_quantile(p: f64, q: f64, df: f64) -> f64 {
+    symmetric_quantile(p, q, |t| t_tails(t, df), |t| t_pdf(t, df))
+}
+
+// The smallest k >= 0 with cdf(k) >= p, for an increasing cdf that reaches
+// p by `limit`
+fn discrete_quantile(p: f64, limit: u64, cdf: impl Fn(u64) -> f64) -> u64 {
+    if cdf(0) >= p {
+        return 0;
+    }
+    // cdf(lo) < p throughout
+    let (mut lo, mut hi) = (0, 1.min(limit));
+    while hi < limit && cdf(hi) < p {
+        lo = hi;
+        hi = (hi * 2).min(limit);
+    }
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        if cdf(mid) >= p {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    hi
+}
+
+/// `norm.dist(x, mean, sd, cumulative)`: the normal density, or the
+/// distribution function when `cumulative` is true.
+pub fn norm_dist(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let name = "norm.dist";
+    let x = real(&args[0], name)?;
+    let mean = real(&args[1], name)?;
+    let sd = positive(&args[2], name, "a positive standard deviation")?;
+    let z = (x - mean) / sd;
+    Ok(Number::Float(if is_truthy(&args[3]) {
+        normal_tails(z).0
+    } else {
+        normal_pdf(z) / sd
+    }))
+}
+
+/// `norm.inv(p, mean, sd)`: the value below which the normal distribution
+/// has probability `p`.
+pub fn norm_inv(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let name = "norm.inv";
+    let p = probability(&args[0], name)?;
+    let mean = real(&args[1], name)?;
+    let sd = positive(&args[2], name, "a positive standard deviation")?;
+    Ok(Number::Float(mean + sd * normal_quantile(p, 1.0 - p)))
+}
+
+/// `norm.s.dist(z, cumulative)`: `norm.dist` with mean 0 and deviation 1.
+pub fn norm_s_dist(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let z = real(&args[0], "norm.s.dist")?;
+    Ok(Number::Float(if is_truthy(&args[1]) {
+        normal_tails(z).0
+    } else {
+        normal_pdf(z)
+    }))
+}
+
+/// `norm.s.inv(p)`: the standard normal quantile.
+pub fn norm_s_inv(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let p = probability(&args[0], "norm.s.inv")?;
+    Ok(Number::Float(normal_quantile(p, 1.0 - p)))
+}
+
+fn degrees(arg: &Number, name: &str) -> Result<f64, EngineError> {
+    positive(arg, name, "positive degrees of freedom")
+}
+
+/// `t.dist(x, df, cumulative)`: Student's t density or left tail.
+pub fn t_dist(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let name = "t.dist";
+    let x = real(&args[0], name)?;
+    let df = degrees(&args[1], name)?;
+    Ok(Number::Float(if is_truthy(&args[2]) {
+        t_tails(x, df).0
+    } else {
+        t_pdf(x, df)
+    }))
+}
+
+/// `t.dist.2t(x, df)`: the probability that |T| exceeds |x|.
+pub fn t_dist_2t(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let name = "t.dist.2t";
+    let x = real(&args[0], name)?;
+    let df = degrees(&args[1], name)?;
+    Ok(Number::Float(2.0 * t_tails(x.abs(), df).1))
+}
+
+/// `t.dist.rt(x, df)`: the right tail of Student's t.
+pub fn t_dist_rt(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let name = "t.dist.rt";
+    let x = real(&args[0], name)?;
+    let df = degrees(&args[1], name)?;
+    Ok(Number::Float(t_tails(x, df).1))
+}
+
+/// `t.inv(p, df)`: the left-tailed inverse of Student's t.
+pub fn t_inv(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let name = "t.inv";
+    let p = probability(&args[0], name)?;
+    let df = degrees(&args[1], name)?;
+    Ok(Number::Float(t_quantile(p, 1.0 - p, df)))
+}
+
+/// `t.inv.2t(p, df)`: the t >= 0 with probability `p` that |T| exceeds it.
+pub fn t_inv_2t(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let name = "t.inv.2t";
+    let p = real(&args[0], name)?;
+    require(
+        p > 0.0 && p <= 1.0,
+        name,
+        "a probability above 0 and up to 1",
+    )?;
+    let df = degrees(&args[1], name)?;
+    Ok(Number::Float(t_quantile(1.0 - p / 2.0, p / 2.0, df)))
+}
+
+// The x >= 0 and degrees of freedom of the chi-squared functions
+fn chisq_args(args: &[Number], name: &str) -> Result<(f64, f64), EngineError> {
+    let x = real(&args[0], name)?;
+    require(x >= 0.0, name, "a non-negative value")?;
+    Ok((x, degrees(&args[1], name)?))
+}
+
+/// `chisq.dist(x, df, cumulative)`: the chi-squared density or left tail.
+pub fn chisq_dist(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let (x, df) = chisq_args(args, "chisq.dist")?;
+    Ok(Number::Float(if is_truthy(&args[2]) {
+        chisq_tails(x, df).0
+    } else {
+        chisq_pdf(x, df)
+    }))
+}
+
+/// `chisq.dist.rt(x, df)`: the right tail of the chi-squared distribution.
+pub fn chisq_dist_rt(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let (x, df) = chisq_args(args, "chisq.dist.rt")?;
+    Ok(Number::Float(chisq_tails(x, df).1))
+}
+
+/// `chisq.inv(p, df)`: the left-tailed chi-squared inverse.
+pub fn chisq_inv(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let name = "chisq.inv";
+    let p = probability(&args[0], name)?;
+    let df = degrees(&args[1], name)?;
+    Ok(Number::Float(quantile(
+        p,
+        1.0 - p,
+        |x| chisq_tails(x, df),
+        |x| chisq_pdf(x, df),
+    )))
+}
+
+/// `chisq.inv.rt(p, df)`: the value the chi-squared distribution exceeds
+/// with probability `p`.
+pub fn chisq_inv_rt(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let name = "chisq.inv.rt";
+    let p = probability(&args[0], name)?;
+    let df = degrees(&args[1], name)?;
+    Ok(Number::Float(quantile(
+        1.0 - p,
+        p,
+        |x| chisq_tails(x, df),
+        |x| chisq_pdf(x, df),
+    )))
+}
+
+// The x >= 0 and the two degrees of freedom of the F functions
+fn f_args(args: &[Number], name: &str) -> Result<(f64, f64, f64), EngineError> {
+    let x = real(&args[0], name)?;
+    require(x >= 0.0, name, "a non-negative value")?;
+    Ok((x, degrees(&args[1], name)?, degrees(&args[2], name)?))
+}
+
+/// `f.dist(x, df1, df2, cumulative)`: the F density or left tail.
+pub fn f_dist(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let (x, d1, d2) = f_args(args, "f.dist")?;
+    Ok(Number::Float(if is_truthy(&args[3]) {
+        f_tails(x, d1, d2).0
+    } else {
+        f_pdf(x, d1, d2)
+    }))
+}
+
+/// `f.dist.rt(x, df1, df2)`: the right tail of the F distribution.
+pub fn f_dist_rt(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let (x, d1, d2) = f_args(args, "f.dist.rt")?;
+    Ok(Number::Float(f_tails(x, d1, d2).1))
+}
+
+// The probability and the two degrees of freedom of the F inverses
+fn f_inv_args(args: &[Number], name: &str) -> Result<(f64, f64, f64), EngineError> {
+    Ok((
+        probability(&args[0], name)?,
+        degrees(&args[1], name)?,
+        degrees(&args[2], name)?,
+    ))
+}
+
+/// `f.inv(p, df1, df2)`: the left-tailed F inverse.
+pub fn f_inv(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let (p, d1, d2) = f_inv_args(args, "f.inv")?;
+    Ok(Number::Float(quantile(
+        p,
+        1.0 - p,
+        |x| f_tails(x, d1, d2),
+        |x| f_pdf(x, d1, d2),
+    )))
+}
+
+/// `f.inv.rt(p, df1, df2)`: the value the F distribution exceeds with
+/// probability `p`.
+pub fn f_inv_rt(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let (p, d1, d2) = f_inv_args(args, "f.inv.rt")?;
+    Ok(Number::Float(quantile(
+        1.0 - p,
+        p,
+        |x| f_tails(x, d1, d2),
+        |x| f_pdf(x, d1, d2),
+    )))
+}
+
+// An exact probability, for the exact binomial distribution
+fn exact(arg: &Number) -> Option<BigRational> {
+    match arg {
+        Number::Integer(i) => Some(BigRational::from_integer(i.clone())),
+        Number::Rational(r) => Some(r.clone()),
+        Number::Float(f) => BigRational::from_float(*f)
+            .filter(|r| r.denom().to_u32().is_some_and(|d| d <= EXACT_DENOMINATOR)),
+        _ => None,
+    }
+}
+
+// P(X = k), or P(X <= k) when cumulative, for X binomial with an exact p
+fn binomial_exact(
+    k: u64,
+    n: u64,
+    p: &BigRational,
+    cumulative: bool,
+    guard: &Guard,
+) -> Result<BigRational, EngineError> {
+    // With p = a/b each term is C(n, j) a^j (b - a)^(n - j) / b^n
+    let (a, b) = (p.numer(), p.denom());
+    let c = b - a;
+    let b_power = num::pow(b.clone(), n as usize);
+    if !cumulative {
+        let term = choose(&BigInt::from(n), &BigInt::from(k), guard)?
+            * num::pow(a.clone(), k as usize)
+            * num::pow(c, (n - k) as usize);
+        return Ok(BigRational::new(term, b_power));
+    }
+    let mut c_powers = vec![BigInt::one()];
+    for i in 0..n as usize {
+        let next = &c_powers[i] * &c;
+        c_powers.push(next);
+    }
+    let (mut coefficient, mut a_power, mut total) = (BigInt::one(), BigInt::one(), BigInt::zero());
+    for j in 0..=k {
+        total += &coefficient * &a_power * &c_powers[(n - j) as usize];
+        coefficient = coefficient * (n - j) / (j + 1);
+        a_power *= a;
+    }
+    Ok(BigRational::new(total, b_power))
+}
+
+// ln C(n, k), from the exact coefficient while it fits an f64
+fn ln_choose(k: u64, n: u64, guard: &Guard) -> Result<f64, EngineError> {
+    if n <= EXACT_TRIALS {
+        let coefficient = choose(&BigInt::from(n), &BigInt::from(k), guard)?;
+        return Ok(coefficient.to_f64().unwrap_or(f64::INFINITY).ln());
+    }
+    let (k, n) = (k as f64, n as f64);
+    Ok(lgamma_real(n + 1.0) - lgamma_real(k + 1.0) - lgamma_real(n - k + 1.0))
+}
+
+fn binomial_pmf(k: u64, n: u64, p: f64, ln_choose: f64) -> f64 {
+    if p == 0.0 || p == 1.0 {
+        let certain = if p == 0.0 { 0 } else { n };
+        return if k == certain { 1.0 } else { 0.0 };
+    }
+    let (k, n) = (k as f64, n as f64);
+    (ln_choose + k * p.ln() + (n - k) * (-p).ln_1p()).exp()
+}
+
+// P(X <= k) = I_(1 - p)(n - k, k + 1)
+fn binomial_cdf(k: u64, n: u64, p: f64) -> f64 {
+    if k >= n {
+        return 1.0;
+    }
+    beta_inc((n - k) as f64, (k + 1) as f64, 1.0 - p, p).0
+}
+
+// A success probability, which may be 0 or 1 for the discrete distributions
+fn chance(arg: &Number, name: &str) -> Result<f64, EngineError> {
+    let p = real(arg, name)?;
+    require((0.0..=1.0).contains(&p), name, "a probability from 0 to 1")?;
+    Ok(p)
+}
+
+/// `binom.dist(k, n, p, cumulative)`: the probability of exactly `k`
+/// successes in `n` trials, or of at most `k` when `cumulative` is true.
+pub fn binom_dist(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let name = "binom.dist";
+    let k = count(&args[0], name, "a whole number of successes")?;
+    let n = count(&args[1], name, "a whole number of trials")?;
+    require(k <= n, name, "no more successes than trials")?;
+    let p = chance(&args[2], name)?;
+    let cumulative = is_truthy(&args[3]);
+    let guard = ctx.guard();
+    if n <= EXACT_TRIALS
+        && let Some(p) = exact(&args[2])
+    {
+        return Ok(from_ratio(binomial_exact(k, n, &p, cumulative, &guard)?));
+    }
+    Ok(Number::Float(if cumulative {
+        binomial_cdf(k, n, p)
+    } else {
+        binomial_pmf(k, n, p, ln_choose(k, n, &guard)?)
+    }))
+}
+
+/// `binom.inv(n, p, alpha)`: the smallest number of successes whose
+/// cumulative probability reaches `alpha`.
+pub fn binom_inv(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let name = "binom.inv";
+    let n = count(&args[0], name, "a whole number of trials")?;
+    let p = chance(&args[1], name)?;
+    let alpha = chance(&args[2], name)?;
+    let k = discrete_quantile(alpha, n, |k| binomial_cdf(k, n, p));
+    Ok(Number::Integer(BigInt::from(k)))
+}
+
+fn poisson_pmf(k: u64, mean: f64) -> f64 {
+    if mean == 0.0 {
+        return if k == 0 { 1.0 } else { 0.0 };
+    }
+    let k = k as f64;
+    (k * mean.ln() - mean - lgamma_real(k + 1.0)).exp()
+}
+
+// P(X <= k) = Q(k + 1, mean)
+fn poisson_cdf(k: u64, mean: f64) -> f64 {
+    gamma_inc(k as f64 + 1.0, mean).1
+}
+
+fn poisson_mean(arg: &Number, name: &str) -> Result<f64, EngineError> {
+    let mean = real(arg, name)?;
+    require(mean >= 0.0, name, "a non-negative mean")?;
+    Ok(mean)
+}
+
+/// `poisson.dist(k, mean, cumulative)`: the probability of exactly `k`
+/// events, or of at most `k` when `cumulative` is true.
+pub fn poisson_dist(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let name = "poisson.dist";
+    let k = count(&args[0], name, "a whole number of events")?;
+    let mean = poisson_mean(&args[1], name)?;
+    Ok(Number::Float(if is_truthy(&args[2]) {
+        poisson_cdf(k, mean)
+    } else {
+        poisson_pmf(k, mean)
+    }))
+}
+
+/// `poisson.inv(p, mean)`: the smallest number of events whose cumulative
+/// probability reaches `p`.
+pub fn poisson_inv(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let name = "poisson.inv";
+    let p = real(&args[0], name)?;
+    require(
+        (0.0..1.0).contains(&p),
+        name,
+        "a probability from 0 up to 1",
+    )?;
+    let mean = poisson_mean(&args[1], name)?;
+    let k = discrete_quantile(p, MAX_COUNT as u64, |k| poisson_cdf(k, mean));
+    Ok(Number::Integer(BigInt::from(k)))
+}
+
+/// `expon.dist(x, rate, cumulative)`: the exponential density or
+/// distribution function.
+pub fn expon_dist(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let name = "expon.dist";
+    let x = real(&args[0], name)?;
+    require(x >= 0.0, name, "a non-negative value")?;
+    let rate = positive(&args[1], name, "a positive rate")?;
+    Ok(Number::Float(if is_truthy(&args[2]) {
+        -(-rate * x).exp_m1()
+    } else {
+        rate * (-rate * x).exp()
+    }))
+}
+
+/// `expon.inv(p, rate)`: the exponential quantile, -ln(1 - p) / rate.
+pub fn expon_inv(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let name = "expon.inv";
+    let p = probability(&args[0], name)?;
+    let rate = positive(&args[1], name, "a positive rate")?;
+    Ok(Number::Float(-(-p).ln_1p() / rate))
+}
+
+// The values of a sample given as a list or matrix, nested lists flattened
+fn sample(arg: &Number, name: &str) -> Result<Vec<f64>, EngineError> {
+    fn collect(arg: &Number, values: &mut Vec<f64>) -> Result<(), EngineError> {
+        match arg {
+            Number::List(items) => items.iter().try_for_each(|n| collect(n, values)),
+            Number::Matrix(m) => m.data().iter().try_for_each(|n| collect(n, values)),
+            other => {
+                let value = other.to_f64().filter(|v| v.is_finite()).ok_or_else(|| {
+                    EngineError::TypeMismatch("real number".into(), other.type_name().into())
+                })?;
+                values.push(value);
+                Ok(())
+            }
+        }
+    }
+    let mut values = Vec::new();
+    collect(arg, &mut values)?;
+    require(values.len() >= 2, name, "samples of at least two values")?;
+    Ok(values)
+}
+
+// The mean and the sample variance
+fn mean_and_variance(values: &[f64]) -> (f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let squares: f64 = values.iter().map(|v| (v - mean) * (v - mean)).sum();
+    (mean, squares / (n - 1.0))
+}
+
+/// `z.test(values, x, [sigma])`: the one-tailed p-value of a z-test that the
+/// sample mean exceeds `x`. Without `sigma` the sample standard deviation is
+/// used.
+pub fn z_test(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let name = "z.test";
+    let values = sample(&args[0], name)?;
+    let x = real(&args[1], name)?;
+    let (mean, variance) = mean_and_variance(&values);
+    let sigma = match args.get(2) {
+        Some(sigma) => positive(sigma, name, "a positive standard deviation")?,
+        None => variance.sqrt(),
+    };
+    if sigma == 0.0 {
+        return Err(EngineError::DivisionByZero);
+    }
+    let z = (mean - x) / (sigma / (values.len() as f64).sqrt());
+    Ok(Number::Float(normal_tails(z).1))
+}
+
+/// `t.test(values1, values2, tails, type)`: the p-value of Student's t-test
+/// with 1 or 2 tails. Type 1 pairs the samples, type 2 assumes they have
+/// equal variances and type 3 (Welch's test) does not.
+pub fn t_test(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let name = "t.test";
+    let first = sample(&args[0], name)?;
+    let second = sample(&args[1], name)?;
+    let tails = count(&args[2], name, "1 or 2 tails")?;
+    require(tails == 1 || tails == 2, name, "1 or 2 tails")?;
+    let kind = count(&args[3], name, "a test type of 1, 2 or 3")?;
+    let (n1, n2) = (first.len() as f64, second.len() as f64);
+    let (t, df) = match kind {
+        1 => {
+            if first.len() != second.len() {
+                return Err(EngineError::DimensionMismatch(format!(
+                    "paired samples of {} and {} values passed to '{}'",
+                    first.len(),
+                    second.len(),
+                    name
+                )));
+            }
+            let differences: Vec<f64> = first.iter().zip(&second).map(|(a, b)| a - b).collect();
+            let (mean, variance) = mean_and_variance(&differences);
+            (mean / (variance / n1).sqrt(), n1 - 1.0)
+        }
+        2 | 3 => {
+            let (m1, v1) = mean_and_variance(&first);
+            let (m2, v2) = mean_and_variance(&second);
+            if kind == 2 {
+                let df = n1 + n2 - 2.0;
+                let pooled = ((n1 - 1.0) * v1 + (n2 - 1.0) * v2) / df;
+                ((m1 - m2) / (pooled * (1.0 / n1 + 1.0 / n2)).sqrt(), df)
+            } else {
+                // Welch–Satterthwaite degrees of freedom
+                let (s1, s2) = (v1 / n1, v2 / n2);
+                let df = (s1 + s2).powi(2) / (s1 * s1 / (n1 - 1.0) + s2 * s2 / (n2 - 1.0));
+                ((m1 - m2) / (s1 + s2).sqrt(), df)
+            }
+        }
+        _ => {
+            return Err(EngineError::DomainError(format!(
+                "{} needs a test type of 1, 2 or 3",
+                name
+            )));
+        }
+    };
+    if !t.is_finite() {
+        return Err(EngineError::DivisionByZero);
+    }
+    Ok(Number::Float(tails as f64 * t_tails(t.abs(), df).1))
+}
+
+// The significance level, standard deviation and sample size of the
+// confidence functions
+fn confidence_args(args: &[Number], name: &str) -> Result<(f64, f64, f64), EngineError> {
+    Ok((
+        probability(&args[0], name)?,
+        positive(&args[1], name, "a positive standard deviation")?,
+        positive(&args[2], name, "a positive sample size")?,
+    ))
+}
+
+/// `confidence.norm(alpha, sd, n)`: half the width of the 1 - alpha
+/// confidence interval for a mean, with a known standard deviation.
+pub fn confidence_norm(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let (alpha, sd, n) = confidence_args(args, "confidence.norm")?;
+    let z = normal_quantile(1.0 - alpha / 2.0, alpha / 2.0);
+    Ok(Number::Float(z * sd / n.sqrt()))
+}
+
+/// `confidence.t(alpha, sd, n)`: half the width of the 1 - alpha confidence
+/// interval for a mean, with the standard deviation of the sample.
+pub fn confidence_t(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let name = "confidence.t";
+    let (alpha, sd, n) = confidence_args(args, name)?;
+    require(n > 1.0, name, "a sample size above 1")?;
+    let t = t_quantile(1.0 - alpha / 2.0, alpha / 2.0, n - 1.0);
+    Ok(Number::Float(t * sd / n.sqrt()))
+}
+
+inventory::submit! { FunctionDef::new("norm.dist", norm_dist, Category::Statistics, "fn-norm-dist").params(&["x", "mean", "sd", "cumulative"]).takes_booleans() }
+inventory::submit! { FunctionDef::new("norm.inv", norm_inv, Category::Statistics, "fn-norm-inv").params(&["p", "mean", "sd"]) }
+inventory::submit! { FunctionDef::new("norm.s.dist", norm_s_dist, Category::Statistics, "fn-norm-s-dist").params(&["z", "cumulative"]).takes_booleans() }
+inventory::submit! { FunctionDef::new("norm.s.inv", norm_s_inv, Category::Statistics, "fn-norm-s-inv").params(&["p"]) }
+inventory::submit! { FunctionDef::new("t.dist", t_dist, Category::Statistics, "fn-t-dist").params(&["x", "df", "cumulative"]).takes_booleans() }
+inventory::submit! { FunctionDef::new("t.dist.2t", t_dist_2t, Category::Statistics, "fn-t-dist-2t").params(&["x", "df"]) }
+inventory::submit! { FunctionDef::new("t.dist.rt", t_dist_rt, Category::Statistics, "fn-t-dist-rt").params(&["x", "df"]) }
+inventory::submit! { FunctionDef::new("t.inv", t_inv, Category::Statistics, "fn-t-inv").params(&["p", "df"]) }
+inventory::submit! { FunctionDef::new("t.inv.2t", t_inv_2t, Category::Statistics, "fn-t-inv-2t").params(&["p", "df"]) }
+inventory::submit! { FunctionDef::new("chisq.dist", chisq_dist, Category::Statistics, "fn-chisq-dist").params(&["x", "df", "cumulative"]).takes_booleans() }
+inventory::submit! { FunctionDef::new("chisq.dist.rt", chisq_dist_rt, Category::Statistics, "fn-chisq-dist-rt").params(&["x", "df"]) }
+inventory::submit! { FunctionDef::new("chisq.inv", chisq_inv, Category::Statistics, "fn-chisq-inv").params(&["p", "df"]) }
+inventory::submit! { FunctionDef::new("chisq.inv.rt", chisq_inv_rt, Category::Statistics, "fn-chisq-inv-rt").params(&["p", "df"]) }
+inventory::submit! { FunctionDef::new("f.dist", f_dist, Category::Statistics, "fn-f-dist").params(&["x", "df1", "df2", "cumulative"]).takes_booleans() }
+inventory::submit! { FunctionDef::new("f.dist.rt", f_dist_rt, Category::Statistics, "fn-f-dist-rt").params(&["x", "df1", "df2"]) }
+inventory::submit! { FunctionDef::new("f.inv", f_inv, Category::Statistics, "fn-f-inv").params(&["p", "df1", "df2"]) }
+inventory::submit! { FunctionDef::new("f.inv.rt", f_inv_rt, Category::Statistics, "fn-f-inv-rt").params(&["p", "df1", "df2"]) }
+inventory::submit! { FunctionDef::new("binom.dist", binom_dist, Category::Statistics, "fn-binom-dist").params(&["k", "n", "p", "cumulative"]).takes_booleans() }
+inventory::submit! { FunctionDef::new("binom.inv", binom_inv, Category::Statistics, "fn-binom-inv").params(&["n", "p", "alpha"]) }
+inventory::submit! { FunctionDef::new("poisson.dist", poisson_dist, Category::Statistics, "fn-poisson-dist").params(&["k", "mean", "cumulative"]).takes_booleans() }
+inventory::submit! { FunctionDef::new("poisson.inv", poisson_inv, Category::Statistics, "fn-poisson-inv").params(&["p", "mean"]) }
+inventory::submit! { FunctionDef::new("expon.dist", expon_dist, Category::Statistics, "fn-expon-dist").params(&["x", "rate", "cumulative"]).takes_booleans() }
+inventory::submit! { FunctionDef::new("expon.inv", expon_inv, Category::Statistics, "fn-expon-inv").params(&["p", "rate"]) }
+inventory::submit! { FunctionDef::new("z.test", z_test, Category::Statistics, "fn-z-test").params(&["values", "x", "sigma"]).optional(1).aliases(&["ztest"]).takes_lists() }
+inventory::submit! { FunctionDef::new("t.test", t_test, Category::Statistics, "fn-t-test").params(&["values1", "values2", "tails", "type"]).aliases(&["ttest"]).takes_lists() }
+inventory::submit! { FunctionDef::new("confidence.norm", confidence_norm, Category::Statistics, "fn-confidence-norm").params(&["alpha", "sd", "n"]) }
+inventory::submit! { FunctionDef::new("confidence.t", confidence_t, Category::Statistics, "fn-confidence-t").params(&["alpha", "sd", "n"]) }
@@ -1,9 +1,9 @@
-use crate::engine::types::Number;
+use crate::engine::ast::Context;
 use crate::engine::errors::EngineError;
-use crate::engine::functions::FunctionDef;
-use num::complex::Complex64;
+use crate::engine::functions::{Category, FunctionDef};
+use crate::engine::types::Number;
 use num::Zero;
-
+use num::complex::Complex64;
 
 // Helper to convert args to Complex64
 fn to_complex_args(args: &[Number]) -> Vec<Complex64> {
@@ -11,16 +11,21 @@ fn to_complex_args(args: &[Number]) -> Vec<Complex64> {
 }
 
 // Future Value
-pub fn fv(args: &[Number]) -> Result<Number, EngineError> {
+pub fn fv(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
     let args = to_complex_args(args);
-    if args.len() < 3 || args.len() > 5 {
-        return Err(EngineError::ArgumentMismatch("fv".into(), 3));
-    }
     let rate = args[0];
     let nper = args[1];
     let pv = args[2];
-    let pmt = if args.len() >= 4 { args[3] } else { Complex64::zero() };
-    let type_val = if args.len() >= 5 { args[4].re as i32 } else { 0 };
+    let pmt = if args.len() >= 4 {
+        args[3]
+    } else {
+        Complex64::zero()
+    };
+    let type_val = if args.len() >= 5 {
+        args[4].re as i32
+    } else {
+        0
+    };
 
     let result = if rate.norm() < 1e-9 {
         -(pv + pmt * nper)
@@ -34,16 +39,21 @@ pub fn fv(args: &[Number]) -> Result<Number, EngineError> {
 }
 
 // Present Value
-pub fn pv(args: &[Number]) -> Result<Number, EngineError> {
+pub fn pv(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
     let args = to_complex_args(args);
-    if args.len() < 3 || args.len() > 5 {
-         return Err(EngineError::ArgumentMismatch("pv".into(), 3));
-    }
     let rate = args[0];
     let nper = args[1];
     let fv = args[2];
-    let pmt = if args.len() >= 4 { args[3] } else { Complex64::zero() };
-    let type_val = if args.len() >= 5 { args[4].re as i32 } else { 0 };
+    let pmt = if args.len() >= 4 {
+        args[3]
+    } else {
+        Complex64::zero()
+    };
+    let type_val = if args.len() >= 5 {
+        args[4].re as i32
+    } else {
+        0
+    };
 
     let result = if rate.norm() < 1e-9 {
         -(fv + pmt * nper)
@@ -57,16 +67,21 @@ pub fn pv(args: &[Number]) -> Result<Number, EngineError> {
 }
 
 // Payment
-pub fn pmt(args: &[Number]) -> Result<Number, EngineError> {
+pub fn pmt(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
     let args = to_complex_args(args);
-    if args.len() < 3 || args.len() > 5 {
-        return Err(EngineError::ArgumentMismatch("pmt".into(), 3));
-    }
     let rate = args[0];
     let nper = args[1];
     let pv = args[2];
-    let fv = if args.len() >= 4 { args[3] } else { Complex64::zero() };
-    let type_val = if args.len() >= 5 { args[4].re as i32 } else { 0 };
+    let fv = if args.len() >= 4 {
+        args[3]
+    } else {
+        Complex64::zero()
+    };
+    let type_val = if args.len() >= 5 {
+        args[4].re as i32
+    } else {
+        0
+    };
 
     let result = if rate.norm() < 1e-9 {
         -(fv + pv) / nper
@@ -81,16 +96,21 @@ pub fn pmt(args: &[Number]) -> Result<Number, EngineError> {
 }
 
 // Number of Periods
-pub fn nper(args: &[Number]) -> Result<Number, EngineError> {
+pub fn nper(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
     let args = to_complex_args(args);
-    if args.len() < 3 || args.len() > 5 {
-        return Err(EngineError::ArgumentMismatch("nper".into(), 3));
-    }
     let rate = args[0];
     let pmt = args[1];
     let pv = args[2];
-    let fv = if args.len() >= 4 { args[3] } else { Complex64::zero() };
-    let type_val = if args.len() >= 5 { args[4].re as i32 } else { 0 };
+    let fv = if args.len() >= 4 {
+        args[3]
+    } else {
+        Complex64::zero()
+    };
+    let type_val = if args.len() >= 5 {
+        args[4].re as i32
+    } else {
+        0
+    };
 
     if rate.norm() < 1e-9 {
         Ok(Number::Complex(-(fv + pv) / pmt))
@@ -104,26 +124,23 @@ pub fn nper(args: &[Number]) -> Result<Number, EngineError> {
     }
 }
 
-pub fn npv(args: &[Number]) -> Result<Number, EngineError> {
+pub fn npv(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
     let args = to_complex_args(args);
-    if args.len() < 2 {
-        return Err(EngineError::ArgumentMismatch("npv".into(), 2)); 
-    }
     let rate = args[0];
     let values = &args[1..];
     let mut sum = Complex64::zero();
     let one = Complex64::new(1.0, 0.0);
-    
+
     // Precision Critical: Use independent powc per term to prevent cumulative error drift.
     // Complexity: O(N log t)
     for (i, &val) in values.iter().enumerate() {
-        let t = (i+1) as f64;
+        let t = (i + 1) as f64;
         sum += val / (one + rate).powf(t);
     }
     Ok(Number::Complex(sum))
 }
 
-pub fn irr(args: &[Number]) -> Result<Number, EngineError> {
+pub fn irr(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
     // Precision Critical: Use Complex64 to handle all root paths and independent powc calls.
     let args = to_complex_args(args);
     let values: Vec<f64> = args.iter().map(|c| c.re).collect();
@@ -133,7 +150,7 @@ pub fn irr(args: &[Number]) -> Result<Number, EngineError> {
         let mut deriv = 0.0;
         for (i, &val) in values.iter().enumerate() {
             let t = i as f64;
-             let factor = (1.0_f64 + guess).powf(t);
+            let factor = (1.0_f64 + guess).powf(t);
             npv += val / factor;
             if t > 0.0 {
                 let d_factor = (1.0_f64 + guess).powf(t + 1.0_f64);
@@ -141,49 +158,56 @@ pub fn irr(args: &[Number]) -> Result<Number, EngineError> {
             }
         }
         if npv.abs() < 1e-7 {
-             return Ok(Number::Complex(Complex64::new(guess, 0.0)));
+            return Ok(Number::Complex(Complex64::new(guess, 0.0)));
+        }
+        if deriv.abs() < 1e-10 {
+            break;
         }
-        if deriv.abs() < 1e-10 { break; }
         guess -= npv / deriv;
     }
     Ok(Number::Complex(Complex64::new(guess, 0.0)))
 }
 
-pub fn rate(args: &[Number]) -> Result<Number, EngineError> {
+pub fn rate(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
     let args = to_complex_args(args);
-    if args.len() < 3 {
-        return Err(EngineError::ArgumentMismatch("rate".into(), 3));
-    }
     let nper = args[0].re;
     let pmt = args[1].re;
     let pv = args[2].re;
     let fv = if args.len() >= 4 { args[3].re } else { 0.0 };
-    let type_val = if args.len() >= 5 { args[4].re as i32 } else { 0 };
+    let type_val = if args.len() >= 5 {
+        args[4].re as i32
+    } else {
+        0
+    };
     let mut guess = if args.len() >= 6 { args[5].re } else { 0.1 };
 
     for _ in 0..100 {
         if guess.abs() < 1e-9 {
-             let y = pv + pmt * nper + fv;
-             if y.abs() < 1e-7 { return Ok(Number::Complex(Complex64::zero())); }
-             guess = 0.0001;
-             continue;
+            let y = pv + pmt * nper + fv;
+            if y.abs() < 1e-7 {
+                return Ok(Number::Complex(Complex64::zero()));
+            }
+            guess = 0.0001;
+            continue;
         }
         let r = guess;
         let factor = (1.0 + r).powf(nper);
         let term_pmt = (pmt * (1.0 + r * (type_val as f64))) * ((factor - 1.0) / r);
         let y = pv * factor + term_pmt + fv;
-        
+
         let delta = 1e-5;
         let r_d = r + delta;
         let factor_d = (1.0 + r_d).powf(nper);
         let term_pmt_d = (pmt * (1.0 + r_d * (type_val as f64))) * ((factor_d - 1.0) / r_d);
         let y_d = pv * factor_d + term_pmt_d + fv;
         let deriv = (y_d - y) / delta;
-        
-        if deriv.abs() < 1e-10 { break; }
+
+        if deriv.abs() < 1e-10 {
+            break;
+        }
         let new_r = r - y / deriv;
         if (new_r - r).abs() < 1e-7 {
-             return Ok(Number::Complex(Complex64::new(new_r, 0.0)));
+            return Ok(Number::Complex(Complex64::new(new_r, 0.0)));
         }
         guess = new_r;
     }
@@ -191,10 +215,10 @@ pub fn rate(args: &[Number]) -> Result<Number, EngineError> {
 }
 
 // Register Functions
-inventory::submit! { FunctionDef { name: "fv", func: fv } }
-inventory::submit! { FunctionDef { name: "pv", func: pv } }
-inventory::submit! { FunctionDef { name: "pmt", func: pmt } }
-inventory::submit! { FunctionDef { name: "nper", func: nper } }
-inventory::submit! { FunctionDef { name: "rate", func: rate } }
-inventory::submit! { FunctionDef { name: "npv", func: npv } }
-inventory::submit! { FunctionDef { name: "irr", func: irr } }
+inventory::submit! { FunctionDef::new("fv", fv, Category::Financial, "fn-fv").params(&["rate", "nper", "pv", "pmt", "type"]).optional(2) }
+inventory::submit! { FunctionDef::new("pv", pv, Category::Financial, "fn-pv").params(&["rate", "nper", "fv", "pmt", "type"]).optional(2) }
+inventory::submit! { FunctionDef::new("pmt", pmt, Category::Financial, "fn-pmt").params(&["rate", "nper", "pv", "fv", "type"]).optional(2) }
+inventory::submit! { FunctionDef::new("nper", nper, Category::Financial, "fn-nper").params(&["rate", "pmt", "pv", "fv", "type"]).optional(2) }
+inventory::submit! { FunctionDef::new("rate", rate, Category::Financial, "fn-rate").params(&["nper", "pmt", "pv", "fv", "type", "guess"]).optional(3) }
+inventory::submit! { FunctionDef::new("npv", npv, Category::Financial, "fn-npv").params(&["rate", "values"]).variadic(2).splat() }
+inventory::submit! { FunctionDef::new("irr", irr, Category::Financial, "fn-irr").params(&["values"]).variadic(1).splat() }
@@ -1,27 +1,33 @@
-use crate::engine::types::Number;
+use crate::engine::ast::Context;
 use crate::engine::errors::EngineError;
-use crate::engine::functions::FunctionDef;
+use crate::engine::functions::{Category, FunctionDef, precise_arg, real_or_complex};
+use crate::engine::types::Number;
 use num::complex::Complex64;
 
-fn one_arg(args: &[Number], name: &str) -> Result<Complex64, EngineError> {
-    if args.len() != 1 {
-        return Err(EngineError::ArgumentMismatch(name.into(), 1));
+pub fn sinh(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let z = args[0].to_complex();
+    match precise_arg(args, ctx) {
+        Some(x) => Ok(Number::BigFloat(x.sinh(&ctx.guard())?)),
+        None => Ok(real_or_complex(z, f64::sinh, Complex64::sinh)),
     }
-    Ok(args[0].to_complex())
-}
-
-pub fn sinh(args: &[Number]) -> Result<Number, EngineError> {
-    Ok(Number::Complex(one_arg(args, "sinh")?.sinh()))
 }
 
-pub fn cosh(args: &[Number]) -> Result<Number, EngineError> {
-    Ok(Number::Complex(one_arg(args, "cosh")?.cosh()))
+pub fn cosh(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let z = args[0].to_complex();
+    match precise_arg(args, ctx) {
+        Some(x) => Ok(Number::BigFloat(x.cosh(&ctx.guard())?)),
+        None => Ok(real_or_complex(z, f64::cosh, Complex64::cosh)),
+    }
 }
 
-pub fn tanh(args: &[Number]) -> Result<Number, EngineError> {
-    Ok(Number::Complex(one_arg(args, "tanh")?.tanh()))
+pub fn tanh(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let z = args[0].to_complex();
+    match precise_arg(args, ctx) {
+        Some(x) => Ok(Number::BigFloat(x.tanh(&ctx.guard())?)),
+        None => Ok(real_or_complex(z, f64::tanh, Complex64::tanh)),
+    }
 }
 
-inventory::submit! { FunctionDef { name: "sinh", func: sinh } }
-inventory::submit! { FunctionDef { name: "cosh", func: cosh } }
-inventory::submit! { FunctionDef { name: "tanh", func: tanh } }
+inventory::submit! { FunctionDef::new("sinh", sinh, Category::Hyperbolic, "fn-sinh").params(&["x"]) }
+inventory::submit! { FunctionDef::new("cosh", cosh, Category::Hyperbolic, "fn-cosh").params(&["x"]) }
+inventory::submit! { FunctionDef::new("tanh", tanh, Category::Hyperbolic, "fn-tanh").params(&["x"]) }
@@ -0,0 +1,107 @@
+use crate::engine::ast::Context;
+use crate::engine::errors::EngineError;
+use crate::engine::functions::{Category, FunctionDef};
+use crate::engine::matrix::Matrix;
+use crate::engine::types::Number;
+use num::ToPrimitive;
+use num_bigint::BigInt;
+
+// A matrix argument; a list of equal-length lists is read as its rows
+fn as_matrix(arg: &Number) -> Result<Matrix, EngineError> {
+    match arg {
+        Number::Matrix(m) => Ok(m.clone()),
+        Number::List(rows) if rows.iter().all(|r| matches!(r, Number::List(_))) => {
+            let rows = rows
+                .iter()
+                .map(|r| match r {
+                    Number::List(items) => items.clone(),
+                    _ => unreachable!(),
+                })
+                .collect::<Vec<Vec<Number>>>();
+            if let Some(entry) = rows.iter().flatten().find(|n| {
+                matches!(
+                    n,
+                    Number::List(_) | Number::Matrix(_) | Number::Text(_) | Number::Quantity(_)
+                )
+            }) {
+                return Err(EngineError::TypeMismatch(
+                    "number as a matrix entry".into(),
+                    entry.type_name().into(),
+                ));
+            }
+            Matrix::from_rows(rows)
+        }
+        other => Err(EngineError::TypeMismatch(
+            "matrix".into(),
+            other.type_name().into(),
+        )),
+    }
+}
+
+/// `matrix(rows)` builds a matrix from a list of rows, `matrix([[1, 2], [3, 4]])`.
+pub fn matrix(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    as_matrix(&args[0]).map(Number::Matrix)
+}
+
+pub fn transpose(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    Ok(Number::Matrix(as_matrix(&args[0])?.transpose()))
+}
+
+pub fn det(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    as_matrix(&args[0])?.determinant(&ctx.guard())
+}
+
+pub fn inv(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    as_matrix(&args[0])?
+        .inverse(&ctx.guard())
+        .map(Number::Matrix)
+}
+
+pub fn rank(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let rank = as_matrix(&args[0])?.rank(&ctx.guard())?;
+    Ok(Number::Integer(BigInt::from(rank)))
+}
+
+pub fn rref(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    as_matrix(&args[0])?.rref(&ctx.guard()).map(Number::Matrix)
+}
+
+/// `solve(A, b)` solves `A x = b` for a square, non-singular `A`. A list `b`
+/// gives the solution as a list; a matrix `b` solves for each of its columns.
+pub fn solve(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let a = as_matrix(&args[0])?;
+    let guard = ctx.guard();
+    match &args[1] {
+        Number::List(items) if !items.iter().any(|i| matches!(i, Number::List(_))) => {
+            let b = Matrix::new(items.len(), 1, items.clone())?;
+            Ok(Number::List(a.solve(&b, &guard)?.into_data()))
+        }
+        other => a.solve(&as_matrix(other)?, &guard).map(Number::Matrix),
+    }
+}
+
+pub fn identity(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let n = match &args[0] {
+        Number::Integer(i) => i.to_usize().filter(|&n| n > 0),
+        _ => None,
+    }
+    .ok_or_else(|| EngineError::DomainError("identity size must be a positive integer".into()))?;
+    ctx.guard().check_elements(n.saturating_mul(n))?;
+    Ok(Number::Matrix(Matrix::identity(n)))
+}
+
+pub fn eigenvalues(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    as_matrix(&args[0])?
+        .eigenvalues(&ctx.guard())
+        .map(Number::List)
+}
+
+inventory::submit! { FunctionDef::new("matrix", matrix, Category::Matrices, "fn-matrix").params(&["rows"]).takes_lists() }
+inventory::submit! { FunctionDef::new("transpose", transpose, Category::Matrices, "fn-transpose").params(&["matrix"]).takes_lists() }
+inventory::submit! { FunctionDef::new("det", det, Category::Matrices, "fn-det").params(&["matrix"]).aliases(&["determinant"]).takes_lists() }
+inventory::submit! { FunctionDef::new("inv", inv, Category::Matrices, "fn-inv").params(&["matrix"]).aliases(&["inverse"]).takes_lists() }
+inventory::submit! { FunctionDef::new("rank", rank, Category::Matrices, "fn-rank").params(&["matrix"]).takes_lists() }
+inventory::submit! { FunctionDef::new("rref", rref, Category::Matrices, "fn-rref").params(&["matrix"]).takes_lists() }
+inventory::submit! { FunctionDef::new("solve", solve, Category::Matrices, "fn-solve").params(&["matrix", "b"]).takes_lists() }
+inventory::submit! { FunctionDef::new("identity", identity, Category::Matrices, "fn-identity").params(&["n"]).aliases(&["eye"]).takes_lists() }
+inventory::submit! { FunctionDef::new("eigenvalues", eigenvalues, Category::Matrices, "fn-eigenvalues").params(&["matrix"]).aliases(&["eig"]).takes_lists() }
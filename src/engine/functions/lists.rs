@@ -0,0 +1,71 @@
+use crate::engine::ast::Context;
+use crate::engine::errors::EngineError;
+use crate::engine::functions::{Category, FunctionDef};
+use crate::engine::types::Number;
+use num::{ToPrimitive, Zero};
+use num_bigint::BigInt;
+use std::cmp::Ordering;
+
+/// `len(x)`: the number of elements in a list, or of characters in a text.
+pub fn len(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    match &args[0] {
+        Number::List(items) => Ok(Number::Integer(BigInt::from(items.len()))),
+        Number::Text(s) => Ok(Number::Integer(BigInt::from(s.chars().count()))),
+        other => Err(EngineError::TypeMismatch(
+            "list or text".into(),
+            other.type_name().into(),
+        )),
+    }
+}
+
+/// `range(start, stop, [step])`: the values from `start` to `stop` inclusive,
+/// `step` apart (1 by default). Elements are computed as `start + k * step`
+/// so exact inputs give exact elements and float steps do not drift.
+pub fn range(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    for arg in args {
+        if arg.to_f64().is_none_or(|f| !f.is_finite()) {
+            return Err(EngineError::TypeMismatch(
+                "finite real number".into(),
+                arg.type_name().into(),
+            ));
+        }
+    }
+    let start = args[0].clone();
+    let stop = args[1].clone();
+    let step = args
+        .get(2)
+        .cloned()
+        .unwrap_or_else(|| Number::Integer(BigInt::from(1)));
+    let zero = Number::Integer(BigInt::zero());
+    let direction = step.partial_cmp(&zero);
+    if direction == Some(Ordering::Equal) {
+        return Err(EngineError::DomainError("range step cannot be zero".into()));
+    }
+
+    // Number of steps that fit between start and stop; exact for exact inputs
+    let steps = (stop - start.clone()) / step.clone();
+    let count = match &steps {
+        Number::Integer(i) => i.to_f64().unwrap_or(f64::INFINITY),
+        Number::Rational(r) => r.floor().to_integer().to_f64().unwrap_or(f64::INFINITY),
+        // Allow for rounding in float steps such as range(0, 1, 0.1)
+        other => (other.to_f64().unwrap_or(f64::NAN) + 1e-9).floor(),
+    };
+    if count.is_nan() || count < 0.0 {
+        return Ok(Number::List(Vec::new()));
+    }
+    let count = count + 1.0;
+    let guard = ctx.guard();
+    guard.check_elements(count.min(usize::MAX as f64) as usize)?;
+
+    let mut items = Vec::with_capacity(count as usize);
+    for k in 0..count as usize {
+        if k % 4096 == 4095 {
+            guard.check_interrupt()?;
+        }
+        items.push(start.clone() + step.clone() * Number::Integer(BigInt::from(k)));
+    }
+    Ok(Number::List(items))
+}
+
+inventory::submit! { FunctionDef::new("len", len, Category::Lists, "fn-len").params(&["list"]).takes_lists() }
+inventory::submit! { FunctionDef::new("range", range, Category::Lists, "fn-range").params(&["start", "stop", "step"]).optional(1).takes_lists() }
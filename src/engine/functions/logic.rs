@@ -1,42 +1,53 @@
+use crate::engine::ast::Context;
 use crate::engine::errors::EngineError;
-use crate::engine::functions::FunctionDef;
+use crate::engine::functions::{Category, FunctionDef};
 use crate::engine::types::Number;
 use num::Zero;
 
-fn is_truthy(n: &Number) -> bool {
+pub(crate) fn is_truthy(n: &Number) -> bool {
     // Zero is false, anything else is true
     match n {
         Number::Integer(i) => !i.is_zero(),
         Number::Rational(r) => !r.is_zero(),
         Number::Float(f) => *f != 0.0,
+        Number::BigFloat(b) => !b.is_zero(),
         Number::Complex(c) => !c.is_zero(),
+        Number::Boolean(b) => *b,
+        // A list is true when all of its elements are, like AND over a range
+        Number::List(items) => items.iter().all(is_truthy),
+        Number::Matrix(m) => m.data().iter().all(is_truthy),
+        // Text is true unless empty
+        Number::Text(s) => !s.is_empty(),
+        Number::Quantity(q) => is_truthy(q.value()),
     }
 }
 
-fn from_bool(b: bool) -> Number {
-    if b {
-        Number::Integer(1.into())
-    } else {
-        Number::Integer(0.into())
+/// Like `is_truthy`, but a list or matrix is true when any of its elements is.
+pub(crate) fn any_truthy(n: &Number) -> bool {
+    match n {
+        Number::List(items) => items.iter().any(any_truthy),
+        Number::Matrix(m) => m.data().iter().any(any_truthy),
+        other => is_truthy(other),
     }
 }
 
-pub fn true_val(_args: &[Number]) -> Result<Number, EngineError> {
+fn from_bool(b: bool) -> Number {
+    Number::from(b)
+}
+
+pub fn true_val(_args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
     Ok(from_bool(true))
 }
 
-pub fn false_val(_args: &[Number]) -> Result<Number, EngineError> {
+pub fn false_val(_args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
     Ok(from_bool(false))
 }
 
-pub fn not(args: &[Number]) -> Result<Number, EngineError> {
-    if args.len() != 1 {
-        return Err(EngineError::ArgumentMismatch("not".into(), 1));
-    }
+pub fn not(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
     Ok(from_bool(!is_truthy(&args[0])))
 }
 
-pub fn and(args: &[Number]) -> Result<Number, EngineError> {
+pub fn and(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
     for arg in args {
         if !is_truthy(arg) {
             return Ok(from_bool(false));
@@ -45,7 +56,7 @@ pub fn and(args: &[Number]) -> Result<Number, EngineError> {
     Ok(from_bool(true))
 }
 
-pub fn or(args: &[Number]) -> Result<Number, EngineError> {
+pub fn or(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
     for arg in args {
         if is_truthy(arg) {
             return Ok(from_bool(true));
@@ -54,7 +65,7 @@ pub fn or(args: &[Number]) -> Result<Number, EngineError> {
     Ok(from_bool(false))
 }
 
-pub fn xor(args: &[Number]) -> Result<Number, EngineError> {
+pub fn xor(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
     let mut true_count = 0;
     for arg in args {
         if is_truthy(arg) {
@@ -65,12 +76,12 @@ pub fn xor(args: &[Number]) -> Result<Number, EngineError> {
     Ok(from_bool(true_count % 2 != 0))
 }
 
-pub fn if_func(args: &[Number]) -> Result<Number, EngineError> {
-    if args.len() != 3 {
-        return Err(EngineError::ArgumentMismatch("if".into(), 3));
-    }
+// IF, AND, OR and IFERROR are evaluated lazily as special forms by `Expr::eval`
+// (see `engine::special_forms`). The eager versions below only serve direct
+// `functions::apply` callers, where every argument has already been evaluated.
+
+pub fn if_func(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
     // args[0] is condition, args[1] is then, args[2] is else
-    // Note: Both branches are already evaluated by the caller in this architecture
     if is_truthy(&args[0]) {
         Ok(args[1].clone())
     } else {
@@ -78,21 +89,16 @@ pub fn if_func(args: &[Number]) -> Result<Number, EngineError> {
     }
 }
 
-inventory::submit! { FunctionDef { name: "TRUE", func: true_val } }
-inventory::submit! { FunctionDef { name: "FALSE", func: false_val } }
-inventory::submit! { FunctionDef { name: "NOT", func: not } }
-inventory::submit! { FunctionDef { name: "AND", func: and } }
-inventory::submit! { FunctionDef { name: "OR", func: or } }
-inventory::submit! { FunctionDef { name: "XOR", func: xor } }
-
-// Support lowercase alias as well for common usage
-inventory::submit! { FunctionDef { name: "true", func: true_val } }
-inventory::submit! { FunctionDef { name: "false", func: false_val } }
-inventory::submit! { FunctionDef { name: "not", func: not } }
-inventory::submit! { FunctionDef { name: "and", func: and } }
-inventory::submit! { FunctionDef { name: "or", func: or } }
-inventory::submit! { FunctionDef { name: "xor", func: xor } }
+pub fn iferror(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    // An already evaluated value cannot be an error
+    Ok(args[0].clone())
+}
 
-// IF function
-inventory::submit! { FunctionDef { name: "IF", func: if_func } }
-inventory::submit! { FunctionDef { name: "if", func: if_func } }
+inventory::submit! { FunctionDef::new("true", true_val, Category::Logic, "fn-true") }
+inventory::submit! { FunctionDef::new("false", false_val, Category::Logic, "fn-false") }
+inventory::submit! { FunctionDef::new("not", not, Category::Logic, "fn-not").params(&["x"]).takes_booleans() }
+inventory::submit! { FunctionDef::new("and", and, Category::Logic, "fn-and").params(&["conditions"]).variadic(1).splat().takes_booleans() }
+inventory::submit! { FunctionDef::new("or", or, Category::Logic, "fn-or").params(&["conditions"]).variadic(1).splat().takes_booleans() }
+inventory::submit! { FunctionDef::new("xor", xor, Category::Logic, "fn-xor").params(&["conditions"]).variadic(1).splat().takes_booleans() }
+inventory::submit! { FunctionDef::new("if", if_func, Category::Logic, "fn-if").params(&["condition", "then", "else"]).takes_booleans() }
+inventory::submit! { FunctionDef::new("iferror", iferror, Category::Logic, "fn-iferror").params(&["value", "fallback"]).takes_booleans() }
@@ -1,43 +1,520 @@
 pub mod bitwise;
+pub mod calculus;
 pub mod complex_ops;
 pub mod core_funcs;
+pub mod distributions;
 pub mod financial;
 pub mod hyperbolic;
+pub mod linear_algebra;
+pub mod lists;
 pub mod logic;
+pub mod number_theory;
+pub mod special;
 pub mod statistics;
+pub mod text;
 pub mod trigonometry;
 
+use crate::engine::ast::Context;
+use crate::engine::bigfloat::BigFloat;
 use crate::engine::errors::EngineError;
+use crate::engine::matrix::Matrix;
 use crate::engine::types::Number;
+use crate::i18n;
+use num::complex::Complex64;
 
 use std::collections::HashMap;
 use std::sync::OnceLock;
 
+/// Builtins receive their evaluated arguments and read-only access to the
+/// evaluation context (settings and resource limits).
+pub type BuiltinFn = fn(&[Number], &Context) -> Result<Number, EngineError>;
+
+/// Groups builtins for the function browser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Category {
+    Arithmetic,
+    Trigonometry,
+    Hyperbolic,
+    Complex,
+    Statistics,
+    Financial,
+    Bitwise,
+    NumberTheory,
+    Special,
+    Calculus,
+    Logic,
+    Lists,
+    Matrices,
+    Text,
+}
+
+impl Category {
+    /// Localization key of the category's display name.
+    pub fn key(self) -> &'static str {
+        match self {
+            Category::Arithmetic => "category-arithmetic",
+            Category::Trigonometry => "category-trigonometry",
+            Category::Hyperbolic => "category-hyperbolic",
+            Category::Complex => "category-complex",
+            Category::Statistics => "category-statistics",
+            Category::Financial => "category-financial",
+            Category::Bitwise => "category-bitwise",
+            Category::NumberTheory => "category-number-theory",
+            Category::Special => "category-special",
+            Category::Calculus => "category-calculus",
+            Category::Logic => "category-logic",
+            Category::Lists => "category-lists",
+            Category::Matrices => "category-matrices",
+            Category::Text => "category-text",
+        }
+    }
+
+    /// The display name in the current locale.
+    pub fn display_name(self) -> String {
+        i18n::t(self.key())
+    }
+}
+
+/// How a builtin receives list arguments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListArgs {
+    /// Scalar function: called once per element, with scalar arguments
+    /// repeated, so `sqrt([4, 9])` is `[2, 3]`.
+    Map,
+    /// Aggregate: list arguments are flattened into the argument list, so
+    /// `mean(xs)` and `mean(1, 2, 3)` agree.
+    Splat,
+    /// The function takes lists as they are.
+    Whole,
+}
+
+/// A registered builtin and the metadata the UI shows for it.
+///
+/// Registrations use the `const` builder so they can go straight into
+/// `inventory::submit!`, e.g. `FunctionDef::new(..).params(&["x", "digits"])
+/// .optional(1)` for `round(x, [digits])`. Each function is registered once
+/// under its canonical name; other spellings go in `aliases`.
 pub struct FunctionDef {
     pub name: &'static str,
-    pub func: fn(&[Number]) -> Result<Number, EngineError>,
+    /// Alternative names. Names and aliases are matched case-insensitively.
+    pub aliases: &'static [&'static str],
+    pub func: BuiltinFn,
+    pub category: Category,
+    /// Parameter names, in order. For variadic functions the last one repeats.
+    pub params: &'static [&'static str],
+    pub min_args: usize,
+    /// `None` when the function takes any number of arguments.
+    pub max_args: Option<usize>,
+    /// Localization key of the one-line help text.
+    pub description: &'static str,
+    pub lists: ListArgs,
+    /// Whether text arguments are passed in. Otherwise they are rejected with
+    /// `TypeMismatch`, unless the function takes lists whole.
+    pub text: bool,
+    /// Whether boolean arguments are passed in, rejected like text otherwise.
+    pub booleans: bool,
+}
+
+impl FunctionDef {
+    /// A function taking no arguments; add them with `params`.
+    pub const fn new(
+        name: &'static str,
+        func: BuiltinFn,
+        category: Category,
+        description: &'static str,
+    ) -> Self {
+        FunctionDef {
+            name,
+            aliases: &[],
+            func,
+            category,
+            params: &[],
+            min_args: 0,
+            max_args: Some(0),
+            description,
+            lists: ListArgs::Map,
+            text: false,
+            booleans: false,
+        }
+    }
+
+    pub const fn aliases(mut self, aliases: &'static [&'static str]) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    /// Sets the parameter names; every one of them is required.
+    pub const fn params(mut self, params: &'static [&'static str]) -> Self {
+        self.params = params;
+        self.min_args = params.len();
+        self.max_args = Some(params.len());
+        self
+    }
+
+    /// Makes the last `count` parameters optional.
+    pub const fn optional(mut self, count: usize) -> Self {
+        self.min_args = self.params.len() - count;
+        self
+    }
+
+    /// Lets the last parameter repeat, requiring at least `min` arguments.
+    pub const fn variadic(mut self, min: usize) -> Self {
+        self.min_args = min;
+        self.max_args = None;
+        self
+    }
+
+    /// Allows one argument more than `params` names, for the calculus forms
+    /// whose first argument may be an expression followed by its variable.
+    pub const fn binds_variable(mut self) -> Self {
+        if let Some(max) = self.max_args {
+            self.max_args = Some(max + 1);
+        }
+        self
+    }
+
+    /// Flattens list arguments (see `ListArgs::Splat`).
+    pub const fn splat(mut self) -> Self {
+        self.lists = ListArgs::Splat;
+        self
+    }
+
+    /// Passes list arguments through unchanged (see `ListArgs::Whole`).
+    pub const fn takes_lists(mut self) -> Self {
+        self.lists = ListArgs::Whole;
+        self
+    }
+
+    /// Passes text arguments through (see `text`).
+    pub const fn takes_text(mut self) -> Self {
+        self.text = true;
+        self
+    }
+
+    /// Passes boolean arguments through (see `booleans`).
+    pub const fn takes_booleans(mut self) -> Self {
+        self.booleans = true;
+        self
+    }
+
+    pub fn accepts(&self, count: usize) -> bool {
+        accepts(self.min_args, self.max_args, count)
+    }
+
+    /// Fails with `ArgumentCount` unless the function accepts `count` arguments.
+    pub fn check_arity(&self, count: usize) -> Result<(), EngineError> {
+        check_arity(self.name, self.min_args, self.max_args, count)
+    }
+
+    /// The call signature shown in tooltips: `round(x, [digits])`,
+    /// `mean(values...)`.
+    pub fn signature(&self) -> String {
+        signature(self.name, self.params, self.min_args, self.max_args)
+    }
+
+    /// The help text in the current locale.
+    pub fn help(&self) -> String {
+        i18n::t(self.description)
+    }
 }
 
 inventory::collect!(FunctionDef);
 
-static FUNCTION_REGISTRY: OnceLock<
-    HashMap<&'static str, fn(&[Number]) -> Result<Number, EngineError>>,
-> = OnceLock::new();
+// Arity and signature rules shared by builtins and host-registered natives
+
+pub(crate) fn accepts(min: usize, max: Option<usize>, count: usize) -> bool {
+    count >= min && max.is_none_or(|max| count <= max)
+}
+
+pub(crate) fn check_arity(
+    name: &str,
+    min: usize,
+    max: Option<usize>,
+    count: usize,
+) -> Result<(), EngineError> {
+    if accepts(min, max, count) {
+        Ok(())
+    } else {
+        Err(EngineError::ArgumentCount {
+            name: name.to_string(),
+            min,
+            max,
+            got: count,
+        })
+    }
+}
+
+pub(crate) fn signature<S: AsRef<str>>(
+    name: &str,
+    params: &[S],
+    min: usize,
+    max: Option<usize>,
+) -> String {
+    let params: Vec<String> = params
+        .iter()
+        .enumerate()
+        .map(|(i, param)| {
+            let param = param.as_ref();
+            if max.is_none() && i + 1 == params.len() {
+                format!("{}...", param)
+            } else if i >= min {
+                format!("[{}]", param)
+            } else {
+                param.to_string()
+            }
+        })
+        .collect();
+    format!("{}({})", name, params.join(", "))
+}
 
-fn get_registry() -> &'static HashMap<&'static str, fn(&[Number]) -> Result<Number, EngineError>> {
+struct Registry {
+    /// Lowercased names and aliases
+    by_name: HashMap<String, &'static FunctionDef>,
+    functions: Vec<&'static FunctionDef>,
+}
+
+static FUNCTION_REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+/// Builds the registry, refusing to start if two registrations claim the
+/// same name: with a plain map the last one iterated would silently win.
+fn get_registry() -> &'static Registry {
     FUNCTION_REGISTRY.get_or_init(|| {
-        let mut m = HashMap::new();
+        let mut by_name: HashMap<String, &'static FunctionDef> = HashMap::new();
+        let mut conflicts = Vec::new();
+        let mut functions = Vec::new();
         for func_def in inventory::iter::<FunctionDef> {
-            m.insert(func_def.name, func_def.func);
+            for name in std::iter::once(&func_def.name).chain(func_def.aliases) {
+                let key = name.to_lowercase();
+                if let Some(existing) = by_name.get(&key) {
+                    conflicts.push(format!(
+                        "'{}' is claimed by both '{}' and '{}'",
+                        key, existing.name, func_def.name
+                    ));
+                } else {
+                    by_name.insert(key, func_def);
+                }
+            }
+            functions.push(func_def);
         }
-        m
+        assert!(
+            conflicts.is_empty(),
+            "conflicting function registrations: {}",
+            conflicts.join("; ")
+        );
+        functions.sort_by_key(|f| (f.category, f.name));
+        Registry { by_name, functions }
     })
 }
 
-pub fn apply(name: &str, args: Vec<Number>) -> Result<Number, EngineError> {
-    let registry = get_registry();
-    match registry.get(name) {
-        Some(func) => func(&args),
-        None => Err(EngineError::UnknownFunction(name.to_string())),
+/// The builtin registered under `name` or one of its aliases, ignoring case.
+pub fn lookup(name: &str) -> Option<&'static FunctionDef> {
+    get_registry().by_name.get(&name.to_lowercase()).copied()
+}
+
+/// Every registered builtin, once each, sorted by category and then by name.
+pub fn list_functions() -> Vec<&'static FunctionDef> {
+    get_registry().functions.clone()
+}
+
+// Edit distance counting an adjacent transposition as one edit, so "mena"
+// is one step from "mean"
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    d[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// The closest known function name to a misspelled `name`: a builtin (by
+/// its canonical name) or one of the context's user or native functions.
+pub fn suggest(name: &str, context: &Context) -> Option<String> {
+    let lowered = name.to_lowercase();
+    // Allow one edit for short names and a third of the length for longer ones
+    let limit = (lowered.chars().count() / 3).max(1);
+    let builtins = get_registry()
+        .by_name
+        .iter()
+        .map(|(key, def)| (key.as_str(), def.name));
+    let user = context
+        .functions
+        .keys()
+        .chain(context.natives().map(|n| n.name()))
+        .map(|k| (k.as_str(), k.as_str()));
+    builtins
+        .chain(user)
+        .map(|(key, canonical)| (edit_distance(&lowered, &key.to_lowercase()), canonical))
+        .filter(|(distance, _)| *distance <= limit)
+        .min()
+        .map(|(_, canonical)| canonical.to_string())
+}
+
+/// The first argument as a `BigFloat` when the call should be evaluated at a
+/// working precision (see `Context::precision_for`) and the argument is real.
+pub(crate) fn precise_arg(args: &[Number], ctx: &Context) -> Option<BigFloat> {
+    let precision = ctx.precision_for(args)?;
+    args.first()?.to_bigfloat(precision)
+}
+
+/// Evaluates `real` for real arguments and `complex` otherwise, for functions
+/// that are real everywhere on the real line.
+pub(crate) fn real_or_complex(
+    z: Complex64,
+    real: fn(f64) -> f64,
+    complex: fn(Complex64) -> Complex64,
+) -> Number {
+    if z.im == 0.0 {
+        Number::Float(real(z.re))
+    } else {
+        Number::Complex(complex(z))
+    }
+}
+
+/// Real arguments should give real results: a complex result whose imaginary
+/// part is exactly zero becomes a `Float` unless an argument was complex.
+fn demote_complex(result: Number, args: &[Number]) -> Number {
+    match result {
+        Number::Complex(c)
+            if c.im == 0.0 && !args.iter().any(|a| matches!(a, Number::Complex(_))) =>
+        {
+            Number::Float(c.re)
+        }
+        other => other,
+    }
+}
+
+pub fn apply(name: &str, args: Vec<Number>, context: &Context) -> Result<Number, EngineError> {
+    let def = lookup(name)
+        .ok_or_else(|| EngineError::UnknownFunction(name.to_string(), suggest(name, context)))?;
+    let args = match def.lists {
+        ListArgs::Splat => flatten(args),
+        _ => args,
+    };
+    def.check_arity(args.len())?;
+    // Results are checked like those of operators
+    let result = call(def, args, context)?;
+    context.guard().check_number(&result)?;
+    Ok(result)
+}
+
+fn flatten(args: Vec<Number>) -> Vec<Number> {
+    let mut flat = Vec::with_capacity(args.len());
+    for arg in args {
+        match arg {
+            Number::List(items) => flat.extend(flatten(items)),
+            Number::Matrix(m) => flat.extend(m.into_data()),
+            other => flat.push(other),
+        }
+    }
+    flat
+}
+
+// Calls the builtin, once per element when a scalar function gets lists or
+// matrices
+fn call(def: &FunctionDef, args: Vec<Number>, context: &Context) -> Result<Number, EngineError> {
+    if def.lists == ListArgs::Map
+        && let Some(shape) = args.iter().find_map(|a| match a {
+            Number::Matrix(m) => Some((m.rows(), m.cols())),
+            _ => None,
+        })
+    {
+        return call_on_entries(def, args, shape, context);
+    }
+    if def.lists == ListArgs::Map {
+        let mut lengths = args.iter().filter_map(|a| match a {
+            Number::List(items) => Some(items.len()),
+            _ => None,
+        });
+        if let Some(len) = lengths.next() {
+            if let Some(other) = lengths.find(|&l| l != len) {
+                return Err(EngineError::DimensionMismatch(format!(
+                    "lists of length {} and {} passed to '{}'",
+                    len, other, def.name
+                )));
+            }
+            let mut results = Vec::with_capacity(len);
+            for i in 0..len {
+                let row = args
+                    .iter()
+                    .map(|a| match a {
+                        Number::List(items) => items[i].clone(),
+                        other => other.clone(),
+                    })
+                    .collect();
+                results.push(call(def, row, context)?);
+            }
+            return Ok(Number::List(results));
+        }
+    }
+    // Builtins work on plain numbers; quantities only reach the ones that take
+    // whole values, which check their arguments themselves
+    if def.lists != ListArgs::Whole
+        && let Some(arg) = args.iter().find(|a| match a {
+            Number::Text(_) => !def.text,
+            Number::Boolean(_) => !def.booleans,
+            Number::Quantity(_) => true,
+            _ => false,
+        })
+    {
+        return Err(EngineError::TypeMismatch(
+            "number".into(),
+            arg.type_name().into(),
+        ));
+    }
+    (def.func)(&args, context).map(|result| demote_complex(result, &args))
+}
+
+fn call_on_entries(
+    def: &FunctionDef,
+    args: Vec<Number>,
+    (rows, cols): (usize, usize),
+    context: &Context,
+) -> Result<Number, EngineError> {
+    for arg in &args {
+        match arg {
+            Number::Matrix(m) if (m.rows(), m.cols()) != (rows, cols) => {
+                return Err(EngineError::DimensionMismatch(format!(
+                    "matrices of size {}×{} and {}×{} passed to '{}'",
+                    rows,
+                    cols,
+                    m.rows(),
+                    m.cols(),
+                    def.name
+                )));
+            }
+            Number::List(_) => {
+                return Err(EngineError::TypeMismatch(
+                    "matrix or scalar".into(),
+                    "list".into(),
+                ));
+            }
+            _ => {}
+        }
+    }
+    let mut results = Vec::with_capacity(rows * cols);
+    for k in 0..rows * cols {
+        let entry = args
+            .iter()
+            .map(|a| match a {
+                Number::Matrix(m) => m.data()[k].clone(),
+                other => other.clone(),
+            })
+            .collect();
+        results.push(call(def, entry, context)?);
     }
+    Matrix::new(rows, cols, results).map(Number::Matrix)
 }
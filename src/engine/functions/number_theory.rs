@@ -0,0 +1,516 @@
+//! Integer functions: divisibility, modular arithmetic, primes and counting.
+//!
+//! Everything here is exact on `BigInt`. Primality uses Miller–Rabin, which
+//! is deterministic below 3.3·10^24 and a strong probable-prime test above
+//! that; factoring removes small primes by trial division and splits what
+//! remains with Pollard's rho (Brent's variant). Work that grows with the
+//! input polls the context's guard like `fact` does.
+
+use crate::engine::ast::Context;
+use crate::engine::errors::EngineError;
+use crate::engine::functions::{Category, FunctionDef};
+use crate::engine::limits::Guard;
+use crate::engine::types::Number;
+use num::{FromPrimitive, Integer, One, Signed, ToPrimitive, Zero};
+use num_bigint::BigInt;
+
+// An integral argument; integral reals such as 6.0 are accepted
+fn integer(arg: &Number, name: &str) -> Result<BigInt, EngineError> {
+    let not_integral = || EngineError::DomainError(format!("{} needs integer arguments", name));
+    match arg {
+        Number::Integer(i) => Ok(i.clone()),
+        Number::Rational(r) if r.is_integer() => Ok(r.to_integer()),
+        Number::Float(f) if f.fract() == 0.0 => BigInt::from_f64(*f).ok_or_else(not_integral),
+        Number::BigFloat(b) if b.is_integer() => Ok(b.trunc()),
+        Number::Rational(_) | Number::Float(_) | Number::BigFloat(_) => Err(not_integral()),
+        other => Err(EngineError::TypeMismatch(
+            "integer".into(),
+            other.type_name().into(),
+        )),
+    }
+}
+
+// A modulus, which has to be positive
+fn modulus(arg: &Number, name: &str) -> Result<BigInt, EngineError> {
+    let m = integer(arg, name)?;
+    if !m.is_positive() {
+        return Err(EngineError::DomainError(format!(
+            "{} needs a positive modulus",
+            name
+        )));
+    }
+    Ok(m)
+}
+
+/// `gcd(values...)`: the greatest common divisor, never negative.
+pub fn gcd(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let mut acc = BigInt::zero();
+    for arg in args {
+        acc = acc.gcd(&integer(arg, "gcd")?);
+    }
+    Ok(Number::Integer(acc))
+}
+
+/// `lcm(values...)`: the least common multiple, 0 if any value is 0.
+pub fn lcm(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let guard = ctx.guard();
+    let mut acc = BigInt::one();
+    for arg in args {
+        acc = acc.lcm(&integer(arg, "lcm")?);
+        guard.check_bits(acc.bits())?;
+    }
+    Ok(Number::Integer(acc))
+}
+
+// Stirling's approximation of ln(n!), to reject huge results up front
+fn ln_factorial(n: f64) -> f64 {
+    if n <= 1.0 {
+        return 0.0;
+    }
+    n * n.ln() - n + 0.5 * (std::f64::consts::TAU * n).ln()
+}
+
+fn check_estimate(ln_result: f64, guard: &Guard) -> Result<(), EngineError> {
+    let bits = ln_result / std::f64::consts::LN_2;
+    guard.check_bits(bits.max(0.0).floor().min(u64::MAX as f64) as u64)
+}
+
+// Ranges at most this long are multiplied out in a plain loop
+const PRODUCT_LEAF: u32 = 16;
+
+// The product of the integers in (from, to], checking interruption as it
+// goes. The halves are multiplied recursively so both operands of each
+// product have similar sizes; a running product would be quadratic in the
+// size of the result.
+fn falling_product(from: &BigInt, to: &BigInt, guard: &Guard) -> Result<BigInt, EngineError> {
+    guard.check_interrupt()?;
+    let len = to - from;
+    if len <= BigInt::from(PRODUCT_LEAF) {
+        let mut acc = BigInt::one();
+        let mut k = from + 1;
+        while k <= *to {
+            acc *= &k;
+            k += 1;
+        }
+        return Ok(acc);
+    }
+    let mid = from + (len >> 1);
+    Ok(falling_product(from, &mid, guard)? * falling_product(&mid, to, guard)?)
+}
+
+/// C(n, k) for 0 <= k <= n, as a quotient of two balanced products.
+pub(crate) fn choose(n: &BigInt, k: &BigInt, guard: &Guard) -> Result<BigInt, EngineError> {
+    let numerator = falling_product(&(n - k), n, guard)?;
+    let denominator = falling_product(&BigInt::zero(), k, guard)?;
+    Ok(numerator / denominator)
+}
+
+// n and k of nCr and nPr: n >= 0, with k outside 0..=n giving no selections
+fn selection_args(args: &[Number], name: &str) -> Result<Option<(BigInt, BigInt)>, EngineError> {
+    let (n, k) = (integer(&args[0], name)?, integer(&args[1], name)?);
+    if n.is_negative() {
+        return Err(EngineError::DomainError(format!(
+            "{} needs a non-negative n",
+            name
+        )));
+    }
+    Ok((!k.is_negative() && k <= n).then_some((n, k)))
+}
+
+/// `nCr(n, k)`: the number of ways to choose `k` of `n` items.
+pub fn ncr(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let Some((n, k)) = selection_args(args, "nCr")? else {
+        return Ok(Number::Integer(BigInt::zero()));
+    };
+    // C(n, k) = C(n, n - k); the smaller one needs fewer steps
+    let k = k.clone().min(&n - &k);
+    let guard = ctx.guard();
+    let (nf, kf) = (
+        n.to_f64().unwrap_or(f64::INFINITY),
+        k.to_f64().unwrap_or(f64::INFINITY),
+    );
+    check_estimate(
+        ln_factorial(nf) - ln_factorial(kf) - ln_factorial(nf - kf),
+        &guard,
+    )?;
+    Ok(Number::Integer(choose(&n, &k, &guard)?))
+}
+
+/// `nPr(n, k)`: the number of ordered arrangements of `k` of `n` items.
+pub fn npr(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let Some((n, k)) = selection_args(args, "nPr")? else {
+        return Ok(Number::Integer(BigInt::zero()));
+    };
+    let guard = ctx.guard();
+    let (nf, kf) = (
+        n.to_f64().unwrap_or(f64::INFINITY),
+        k.to_f64().unwrap_or(f64::INFINITY),
+    );
+    check_estimate(ln_factorial(nf) - ln_factorial(nf - kf), &guard)?;
+    let product = falling_product(&(&n - &k), &n, &guard)?;
+    guard.check_bits(product.bits())?;
+    Ok(Number::Integer(product))
+}
+
+/// `binomial(n, k)`: the generalized binomial coefficient
+/// n(n-1)...(n-k+1)/k!, defined for any `n`, so `binomial(-1, 3) = -1` and
+/// `binomial(1/2, 2) = -1/8`. Equal to `nCr` for natural `n`.
+pub fn binomial(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let k = integer(&args[1], "binomial")?;
+    if k.is_negative() {
+        return Ok(Number::Integer(BigInt::zero()));
+    }
+    if let Number::Integer(n) = &args[0]
+        && !n.is_negative()
+    {
+        return ncr(args, ctx);
+    }
+    if let Number::List(_) | Number::Matrix(_) | Number::Text(_) | Number::Quantity(_) = args[0] {
+        return Err(EngineError::TypeMismatch(
+            "number".into(),
+            args[0].type_name().into(),
+        ));
+    }
+    let guard = ctx.guard();
+    let mut acc = Number::Integer(BigInt::one());
+    let mut i = BigInt::zero();
+    while i < k {
+        let factor = args[0].clone() - Number::Integer(i.clone());
+        i += 1;
+        acc = acc * factor / Number::Integer(i.clone());
+        if (&i % 256u32).is_zero() {
+            guard.check_interrupt()?;
+            guard.check_number(&acc)?;
+        }
+    }
+    guard.check_number(&acc)?;
+    Ok(acc)
+}
+
+// The inverse of `a` modulo `m`, if they are coprime
+fn inverse(a: &BigInt, m: &BigInt) -> Option<BigInt> {
+    let egcd = a.mod_floor(m).extended_gcd(m);
+    egcd.gcd.is_one().then(|| egcd.x.mod_floor(m))
+}
+
+/// `mod_pow(base, exp, m)`: `base^exp mod m` without building the power. A
+/// negative exponent raises the modular inverse.
+pub fn mod_pow(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let base = integer(&args[0], "mod_pow")?;
+    let exp = integer(&args[1], "mod_pow")?;
+    let m = modulus(&args[2], "mod_pow")?;
+    let base = if exp.is_negative() {
+        inverse(&base, &m).ok_or_else(|| {
+            EngineError::DomainError(format!("{} has no inverse modulo {}", base, m))
+        })?
+    } else {
+        base
+    };
+    Ok(Number::Integer(base.modpow(&exp.abs(), &m)))
+}
+
+/// `mod_inv(a, m)`: the `x` in 0..m with `a·x ≡ 1 (mod m)`.
+pub fn mod_inv(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let a = integer(&args[0], "mod_inv")?;
+    let m = modulus(&args[1], "mod_inv")?;
+    inverse(&a, &m)
+        .map(Number::Integer)
+        .ok_or_else(|| EngineError::DomainError(format!("{} has no inverse modulo {}", a, m)))
+}
+
+const SMALL_PRIMES: [u32; 30] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113,
+];
+
+// Miller–Rabin with the first 13 primes as bases is exact below 3.3·10^24;
+// larger numbers are also tested against the rest of `SMALL_PRIMES`
+fn is_prime(n: &BigInt) -> bool {
+    if *n < BigInt::from(2) {
+        return false;
+    }
+    for p in SMALL_PRIMES {
+        if (n % p).is_zero() {
+            return *n == BigInt::from(p);
+        }
+    }
+    let n_minus_one: BigInt = n - 1;
+    let s = n_minus_one.trailing_zeros().unwrap_or(0);
+    let d = &n_minus_one >> s;
+    let exact_below = BigInt::from(3_317_044_064_679_887_385_961_981u128);
+    let bases = if *n < exact_below {
+        13
+    } else {
+        SMALL_PRIMES.len()
+    };
+    'bases: for &a in &SMALL_PRIMES[..bases] {
+        let mut x = BigInt::from(a).modpow(&d, n);
+        if x.is_one() || x == n_minus_one {
+            continue;
+        }
+        for _ in 1..s {
+            x = &x * &x % n;
+            if x == n_minus_one {
+                continue 'bases;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/// `isprime(n)`: whether `n` is prime.
+pub fn isprime(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    Ok(Number::from(is_prime(&integer(&args[0], "isprime")?)))
+}
+
+/// `nextprime(n)`: the smallest prime greater than `n`.
+pub fn nextprime(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let n = integer(&args[0], "nextprime")?;
+    if n < BigInt::from(2) {
+        return Ok(Number::Integer(BigInt::from(2)));
+    }
+    let guard = ctx.guard();
+    // Odd candidates only
+    let mut candidate = if n.is_even() { n + 1 } else { n + 2 };
+    let mut iterations: u32 = 0;
+    while !is_prime(&candidate) {
+        candidate += 2;
+        iterations = iterations.wrapping_add(1);
+        if iterations.is_multiple_of(256) {
+            guard.check_interrupt()?;
+        }
+    }
+    Ok(Number::Integer(candidate))
+}
+
+// A non-trivial factor of an odd composite `n` by Pollard's rho, using
+// Brent's cycle detection and batching the gcds
+fn pollard_rho(n: &BigInt, guard: &Guard) -> Result<BigInt, EngineError> {
+    const BATCH: u64 = 128;
+    for c in 1u32.. {
+        let f = |x: &BigInt| (x * x + c) % n;
+        let (mut x, mut y, mut ys) = (BigInt::from(2), BigInt::from(2), BigInt::from(2));
+        let (mut g, mut q, mut r) = (BigInt::one(), BigInt::one(), 1u64);
+        while g.is_one() {
+            x = y.clone();
+            for _ in 0..r {
+                y = f(&y);
+            }
+            let mut k = 0;
+            while k < r && g.is_one() {
+                ys = y.clone();
+                for _ in 0..BATCH.min(r - k) {
+                    y = f(&y);
+                    q = q * (&x - &y).abs() % n;
+                }
+                g = q.gcd(n);
+                k += BATCH;
+                guard.check_interrupt()?;
+            }
+            r *= 2;
+        }
+        // The batch overshot: step through it one value at a time
+        if g == *n {
+            loop {
+                ys = f(&ys);
+                g = (&x - &ys).abs().gcd(n);
+                if !g.is_one() {
+                    break;
+                }
+            }
+        }
+        if g != *n {
+            return Ok(g);
+        }
+    }
+    unreachable!("some c splits every composite")
+}
+
+// The prime factors of |n| (n != 0) with multiplicity, in increasing order
+fn prime_factors(n: &BigInt, guard: &Guard) -> Result<Vec<BigInt>, EngineError> {
+    let mut factors = Vec::new();
+    let mut rest = n.abs();
+    let mut p = BigInt::from(2);
+    let trial_limit = BigInt::from(1000);
+    while p < trial_limit && &p * &p <= rest {
+        while (&rest % &p).is_zero() {
+            rest /= &p;
+            factors.push(p.clone());
+        }
+        p += if p == BigInt::from(2) { 1 } else { 2 };
+    }
+    let mut composites = vec![rest];
+    while let Some(m) = composites.pop() {
+        if m.is_one() {
+            continue;
+        }
+        if is_prime(&m) {
+            factors.push(m);
+        } else {
+            let d = pollard_rho(&m, guard)?;
+            composites.push(&m / &d);
+            composites.push(d);
+        }
+    }
+    factors.sort();
+    Ok(factors)
+}
+
+// The distinct primes of a factor list with their exponents
+fn powers(factors: Vec<BigInt>) -> Vec<(BigInt, u32)> {
+    let mut powers: Vec<(BigInt, u32)> = Vec::new();
+    for p in factors {
+        match powers.last_mut() {
+            Some((last, count)) if *last == p => *count += 1,
+            _ => powers.push((p, 1)),
+        }
+    }
+    powers
+}
+
+fn nonzero(arg: &Number, name: &str) -> Result<BigInt, EngineError> {
+    let n = integer(arg, name)?;
+    if n.is_zero() {
+        return Err(EngineError::DomainError(format!(
+            "{} of 0 is undefined",
+            name
+        )));
+    }
+    Ok(n)
+}
+
+/// `factor(n, [form])`: the prime factors of `n` as a list, with -1 first for
+/// negative `n`; `factor(n, "product")` writes them as text instead,
+/// `factor(360, "product")` being "2^3 * 3^2 * 5".
+pub fn factor(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let n = nonzero(&args[0], "factor")?;
+    let factors = prime_factors(&n, &ctx.guard())?;
+    match args.get(1) {
+        None => {
+            let sign = n.is_negative().then(|| Number::Integer(-BigInt::one()));
+            Ok(Number::List(
+                sign.into_iter()
+                    .chain(factors.into_iter().map(Number::Integer))
+                    .collect(),
+            ))
+        }
+        Some(Number::Text(form)) if form.eq_ignore_ascii_case("product") => {
+            let terms: Vec<String> = powers(factors)
+                .into_iter()
+                .map(|(p, k)| {
+                    if k == 1 {
+                        p.to_string()
+                    } else {
+                        format!("{}^{}", p, k)
+                    }
+                })
+                .collect();
+            let product = if terms.is_empty() {
+                "1".to_string()
+            } else {
+                terms.join(" * ")
+            };
+            let sign = if n.is_negative() { "-" } else { "" };
+            Ok(Number::Text(format!("{}{}", sign, product)))
+        }
+        Some(Number::Text(form)) => Err(EngineError::DomainError(format!(
+            "unknown factor form '{}'",
+            form
+        ))),
+        Some(other) => Err(EngineError::TypeMismatch(
+            "text".into(),
+            other.type_name().into(),
+        )),
+    }
+}
+
+/// `totient(n)`: how many of 1..=n are coprime to `n` (Euler's φ).
+pub fn totient(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let n = integer(&args[0], "totient")?;
+    if !n.is_positive() {
+        return Err(EngineError::DomainError(
+            "totient needs a positive n".into(),
+        ));
+    }
+    let mut phi = BigInt::one();
+    for (p, k) in powers(prime_factors(&n, &ctx.guard())?) {
+        phi *= (&p - 1) * p.pow(k - 1);
+    }
+    Ok(Number::Integer(phi))
+}
+
+/// `divisors(n)`: the positive divisors of `n` in increasing order.
+pub fn divisors(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let n = nonzero(&args[0], "divisors")?;
+    let guard = ctx.guard();
+    let powers = powers(prime_factors(&n, &guard)?);
+    let count = powers
+        .iter()
+        .try_fold(1usize, |acc, (_, k)| acc.checked_mul(*k as usize + 1))
+        .unwrap_or(usize::MAX);
+    guard.check_elements(count)?;
+    let mut divisors = vec![BigInt::one()];
+    for (p, k) in powers {
+        let mut next = Vec::with_capacity(divisors.len() * (k as usize + 1));
+        for d in &divisors {
+            let mut power = d.clone();
+            next.push(power.clone());
+            for _ in 0..k {
+                power *= &p;
+                next.push(power.clone());
+            }
+        }
+        divisors = next;
+    }
+    divisors.sort();
+    Ok(Number::List(
+        divisors.into_iter().map(Number::Integer).collect(),
+    ))
+}
+
+// F(n) and F(n + 1) by fast doubling
+fn fib_pair(n: &BigInt, guard: &Guard) -> Result<(BigInt, BigInt), EngineError> {
+    if n.is_zero() {
+        return Ok((BigInt::zero(), BigInt::one()));
+    }
+    let (a, b) = fib_pair(&(n >> 1), guard)?;
+    guard.check_interrupt()?;
+    let c = &a * (&b * 2 - &a);
+    let d = &a * &a + &b * &b;
+    Ok(if n.is_odd() {
+        (d.clone(), c + d)
+    } else {
+        (c, d)
+    })
+}
+
+/// `fib(n)`: the nth Fibonacci number, extended to negative `n` by
+/// F(-n) = (-1)^(n+1) F(n).
+pub fn fib(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let n = integer(&args[0], "fib")?;
+    let guard = ctx.guard();
+    // F(n) has about n·log2(φ) bits
+    let bits = n.abs().to_f64().unwrap_or(f64::INFINITY) * 0.6942419136306174;
+    guard.check_bits(bits.floor().min(u64::MAX as f64) as u64)?;
+    let (f, _) = fib_pair(&n.abs(), &guard)?;
+    Ok(Number::Integer(if n.is_negative() && n.is_even() {
+        -f
+    } else {
+        f
+    }))
+}
+
+inventory::submit! { FunctionDef::new("gcd", gcd, Category::NumberTheory, "fn-gcd").params(&["values"]).variadic(1).splat() }
+inventory::submit! { FunctionDef::new("lcm", lcm, Category::NumberTheory, "fn-lcm").params(&["values"]).variadic(1).splat() }
+inventory::submit! { FunctionDef::new("nCr", ncr, Category::NumberTheory, "fn-ncr").params(&["n", "k"]).aliases(&["comb"]) }
+inventory::submit! { FunctionDef::new("nPr", npr, Category::NumberTheory, "fn-npr").params(&["n", "k"]).aliases(&["perm"]) }
+inventory::submit! { FunctionDef::new("binomial", binomial, Category::NumberTheory, "fn-binomial").params(&["n", "k"]) }
+inventory::submit! { FunctionDef::new("mod_pow", mod_pow, Category::NumberTheory, "fn-mod-pow").params(&["base", "exp", "m"]).aliases(&["powmod"]) }
+inventory::submit! { FunctionDef::new("mod_inv", mod_inv, Category::NumberTheory, "fn-mod-inv").params(&["a", "m"]).aliases(&["modinv"]) }
+inventory::submit! { FunctionDef::new("isprime", isprime, Category::NumberTheory, "fn-isprime").params(&["n"]) }
+inventory::submit! { FunctionDef::new("nextprime", nextprime, Category::NumberTheory, "fn-nextprime").params(&["n"]) }
+inventory::submit! { FunctionDef::new("factor", factor, Category::NumberTheory, "fn-factor").params(&["n", "form"]).optional(1).takes_text() }
+inventory::submit! { FunctionDef::new("totient", totient, Category::NumberTheory, "fn-totient").params(&["n"]) }
+inventory::submit! { FunctionDef::new("divisors", divisors, Category::NumberTheory, "fn-divisors").params(&["n"]) }
+inventory::submit! { FunctionDef::new("fib", fib, Category::NumberTheory, "fn-fib").params(&["n"]) }
@@ -1,11 +1,32 @@
-use crate::engine::types::Number;
+use crate::engine::ast::Context;
 use crate::engine::errors::EngineError;
-use crate::engine::functions::FunctionDef;
-use num::Zero;
+use crate::engine::functions::{Category, FunctionDef};
+use crate::engine::types::{Number, from_ratio, numbers_equal, pow};
+use num::{One, ToPrimitive, Zero};
 use num_bigint::BigInt;
+use num_rational::BigRational;
 
-pub fn mean(args: &[Number]) -> Result<Number, EngineError> {
-    if args.is_empty() { return Err(EngineError::ArgumentMismatch("mean".into(), 1)); }
+/// `sum(values...)`: the total of the arguments and list elements. The
+/// series `sum(expr, var, from, to)` is a special form that falls back to
+/// this for any other call.
+pub fn sum(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let mut total = Number::Integer(BigInt::zero());
+    for arg in args {
+        total = total + arg.clone();
+    }
+    Ok(total)
+}
+
+/// `prod(values...)`: the product of the arguments and list elements.
+pub fn prod(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let mut total = Number::Integer(BigInt::one());
+    for arg in args {
+        total = total * arg.clone();
+    }
+    Ok(total)
+}
+
+pub fn mean(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
     let mut sum = Number::Integer(BigInt::zero());
     for arg in args {
         sum = sum + arg.clone();
@@ -14,54 +35,575 @@ pub fn mean(args: &[Number]) -> Result<Number, EngineError> {
     Ok(sum / count)
 }
 
-pub fn median(args: &[Number]) -> Result<Number, EngineError> {
-    if args.is_empty() { return Err(EngineError::ArgumentMismatch("median".into(), 1)); }
-    
+pub fn median(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
     // Validate inputs are real numbers (not Complex)
     for n in args {
         if let Number::Complex(_) = n {
-             return Err(EngineError::TypeMismatch("Median requires real numbers".into(), "Complex".into()));
+            return Err(EngineError::TypeMismatch(
+                "Median requires real numbers".into(),
+                "Complex".into(),
+            ));
         }
     }
 
     // Optimization: Collect references to avoid cloning heavy BigInts during sort
     let mut refs: Vec<&Number> = args.iter().collect();
-    
+
     // Sort using PartialOrd which uses exact Integer/Rational comparison where possible
     refs.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
-    
+
     let mid = refs.len() / 2;
     if refs.len() % 2 == 1 {
         Ok(refs[mid].clone())
     } else {
-        let left = refs[mid-1].clone();
+        let left = refs[mid - 1].clone();
         let right = refs[mid].clone();
         // Mean of the two middle elements
         Ok((left + right) / Number::Integer(BigInt::from(2)))
     }
 }
 
-pub fn variance(args: &[Number]) -> Result<Number, EngineError> {
-    if args.len() < 2 { return Err(EngineError::ArgumentMismatch("variance".into(), 2)); }
-    
-    let m = mean(args)?;
+pub fn variance(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let m = mean(args, ctx)?;
     let mut sum_sq_diff = Number::Integer(BigInt::zero());
-    
+
     for x in args {
         let diff = x.clone() - m.clone();
         sum_sq_diff = sum_sq_diff + (diff.clone() * diff);
     }
-    
+
     Ok(sum_sq_diff / Number::Integer(BigInt::from(args.len() - 1)))
 }
 
-pub fn std_dev(args: &[Number]) -> Result<Number, EngineError> {
-    let v = variance(args)?;
+pub fn std_dev(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let v = variance(args, ctx)?;
+    root(v, args, ctx)
+}
+
+// The square root of a variance of `args`
+fn root(v: Number, args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    // The variance is usually exact; convert it with spare digits so the root
+    // is correctly rounded
+    if let Some(p) = ctx.precision_for(args)
+        && let Some(v) = v.to_bigfloat(p + 10).filter(|v| !v.is_negative())
+    {
+        return Ok(Number::BigFloat(v.sqrt()?.with_precision(p)));
+    }
     let c = v.to_complex();
     Ok(Number::Complex(c.sqrt()))
 }
 
-inventory::submit! { FunctionDef { name: "mean", func: mean } }
-inventory::submit! { FunctionDef { name: "median", func: median } }
-inventory::submit! { FunctionDef { name: "var", func: variance } }
-inventory::submit! { FunctionDef { name: "std", func: std_dev } }
+// Sample statistics only make sense for real values
+fn check_real(values: &[Number]) -> Result<(), EngineError> {
+    match values.iter().find(|n| n.to_f64().is_none()) {
+        Some(n) => Err(EngineError::TypeMismatch(
+            "real number".into(),
+            n.type_name().into(),
+        )),
+        None => Ok(()),
+    }
+}
+
+fn integer(n: usize) -> Number {
+    Number::Integer(BigInt::from(n))
+}
+
+fn is_zero(n: &Number) -> bool {
+    n.to_f64() == Some(0.0)
+}
+
+// Σ (x - mean)^power over the values, exact for exact values
+fn central_sum(values: &[Number], m: &Number, power: u32) -> Number {
+    let mut sum = Number::Integer(BigInt::zero());
+    for x in values {
+        let diff = x.clone() - m.clone();
+        let mut term = diff.clone();
+        for _ in 1..power {
+            term = term * diff.clone();
+        }
+        sum = sum + term;
+    }
+    sum
+}
+
+/// `var.p(values...)`: the population variance, which divides by n where
+/// `var` divides by n - 1.
+pub fn variance_p(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    check_real(args)?;
+    let m = mean(args, ctx)?;
+    Ok(central_sum(args, &m, 2) / integer(args.len()))
+}
+
+/// `stdev.p(values...)`: the population standard deviation.
+pub fn std_dev_p(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let v = variance_p(args, ctx)?;
+    root(v, args, ctx)
+}
+
+/// `mode(values...)`: the most frequent value, the smallest one on a tie.
+pub fn mode(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    check_real(args)?;
+    let mut refs: Vec<&Number> = args.iter().collect();
+    refs.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let mut best = (refs[0], 1);
+    let mut run = 1;
+    for pair in refs.windows(2) {
+        run = if numbers_equal(pair[0], pair[1]) {
+            run + 1
+        } else {
+            1
+        };
+        if run > best.1 {
+            best = (pair[1], run);
+        }
+    }
+    if best.1 == 1 {
+        return Err(EngineError::DomainError(
+            "mode needs a value that repeats".into(),
+        ));
+    }
+    Ok(best.0.clone())
+}
+
+/// How `percentile` and `quartile` pick a value between two data points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Interpolation {
+    /// Linear between the points, ranking over 0..n-1 (spreadsheet `.INC`)
+    Inclusive,
+    /// Linear between the points, ranking over 1..n with 0 and n+1 outside
+    /// the data (spreadsheet `.EXC`)
+    Exclusive,
+    /// The data point below, above, closest to or halfway between the rank
+    /// of `Inclusive`
+    Lower,
+    Higher,
+    Nearest,
+    Midpoint,
+}
+
+impl Interpolation {
+    fn parse(arg: Option<&Number>) -> Result<Self, EngineError> {
+        let name = match arg {
+            None => return Ok(Interpolation::Inclusive),
+            Some(Number::Text(s)) => s.to_lowercase(),
+            Some(other) => {
+                return Err(EngineError::TypeMismatch(
+                    "text".into(),
+                    other.type_name().into(),
+                ));
+            }
+        };
+        match name.as_str() {
+            "inclusive" | "linear" => Ok(Interpolation::Inclusive),
+            "exclusive" => Ok(Interpolation::Exclusive),
+            "lower" => Ok(Interpolation::Lower),
+            "higher" => Ok(Interpolation::Higher),
+            "nearest" => Ok(Interpolation::Nearest),
+            "midpoint" => Ok(Interpolation::Midpoint),
+            _ => Err(EngineError::DomainError(format!(
+                "Unknown interpolation '{}'; use inclusive, exclusive, lower, \
+                 higher, nearest or midpoint",
+                name
+            ))),
+        }
+    }
+}
+
+// The elements of a list or matrix argument, which must be real numbers
+fn sample(arg: &Number) -> Result<Vec<Number>, EngineError> {
+    let values = match arg {
+        Number::List(items) => items.clone(),
+        Number::Matrix(m) => m.data().to_vec(),
+        other => {
+            return Err(EngineError::TypeMismatch(
+                "list".into(),
+                other.type_name().into(),
+            ));
+        }
+    };
+    check_real(&values)?;
+    Ok(values)
+}
+
+// The whole part and the fraction of a non-negative rank, exact for exact
+// ranks
+fn split_rank(h: Number) -> (usize, Number) {
+    match h {
+        Number::Integer(i) => {
+            let index = i.to_usize().unwrap_or(usize::MAX);
+            (index, Number::Integer(BigInt::zero()))
+        }
+        Number::Rational(r) => {
+            let whole = r.floor();
+            let index = whole.to_integer().to_usize().unwrap_or(usize::MAX);
+            (index, from_ratio(r - whole))
+        }
+        other => {
+            let f = other.to_f64().unwrap_or(f64::NAN);
+            (f.floor() as usize, Number::Float(f - f.floor()))
+        }
+    }
+}
+
+// The `p`-quantile of sorted values
+fn quantile(
+    sorted: &[Number],
+    p: &Number,
+    method: Interpolation,
+    name: &str,
+) -> Result<Number, EngineError> {
+    if p.to_f64().is_none_or(|p| !(0.0..=1.0).contains(&p)) {
+        return Err(EngineError::DomainError(format!(
+            "{} needs a fraction from 0 to 1",
+            name
+        )));
+    }
+    let n = sorted.len();
+    // The 0-based rank of the quantile
+    let h = if method == Interpolation::Exclusive {
+        let h = p.clone() * integer(n + 1) - Number::Integer(BigInt::one());
+        if h < Number::Integer(BigInt::zero()) || h > integer(n - 1) {
+            return Err(EngineError::DomainError(format!(
+                "{} needs a fraction from 1/{} to {}/{} for exclusive interpolation",
+                name,
+                n + 1,
+                n,
+                n + 1
+            )));
+        }
+        h
+    } else {
+        p.clone() * integer(n - 1)
+    };
+    let (lo, fraction) = split_rank(h);
+    if is_zero(&fraction) {
+        return Ok(sorted[lo].clone());
+    }
+    let (low, high) = (sorted[lo].clone(), sorted[lo + 1].clone());
+    let half = from_ratio(BigRational::new(BigInt::one(), BigInt::from(2)));
+    Ok(match method {
+        Interpolation::Inclusive | Interpolation::Exclusive => {
+            low.clone() + fraction * (high - low)
+        }
+        Interpolation::Lower => low,
+        Interpolation::Higher => high,
+        // Halfway picks the point with the even rank
+        Interpolation::Nearest => match fraction.partial_cmp(&half) {
+            Some(std::cmp::Ordering::Less) => low,
+            Some(std::cmp::Ordering::Greater) => high,
+            _ if lo % 2 == 0 => low,
+            _ => high,
+        },
+        Interpolation::Midpoint => (low + high) * half,
+    })
+}
+
+fn sorted_sample(arg: &Number) -> Result<Vec<Number>, EngineError> {
+    let mut values = sample(arg)?;
+    if values.is_empty() {
+        return Err(EngineError::DomainError(
+            "Cannot take a percentile of an empty list".into(),
+        ));
+    }
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    Ok(values)
+}
+
+/// `percentile(values, p, [method])`: the value below which a fraction `p`
+/// of the data lies, interpolated by `method` (see `Interpolation`).
+pub fn percentile(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let values = sorted_sample(&args[0])?;
+    let method = Interpolation::parse(args.get(2))?;
+    quantile(&values, &args[1], method, "percentile")
+}
+
+/// `quartile(values, q, [method])`: the `q`th quartile for q from 0
+/// (the minimum) to 4 (the maximum).
+pub fn quartile(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let values = sorted_sample(&args[0])?;
+    let method = Interpolation::parse(args.get(2))?;
+    let q = match &args[1] {
+        Number::Integer(q) if q.to_u8().is_some_and(|q| q <= 4) => q.clone(),
+        _ => {
+            return Err(EngineError::DomainError(
+                "quartile needs a quartile from 0 to 4".into(),
+            ));
+        }
+    };
+    let p = from_ratio(BigRational::new(q, BigInt::from(4)));
+    quantile(&values, &p, method, "quartile")
+}
+
+/// `skew(values...)`: the sample skewness, as spreadsheets compute it.
+pub fn skew(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    check_real(args)?;
+    let n = args.len();
+    let m = mean(args, ctx)?;
+    let v = central_sum(args, &m, 2) / integer(n - 1);
+    if is_zero(&v) {
+        return Err(EngineError::DivisionByZero);
+    }
+    // s³ = v^(3/2), exact when the variance is a perfect square
+    let cube = pow(
+        v,
+        from_ratio(BigRational::new(BigInt::from(3), BigInt::from(2))),
+        ctx,
+    )?;
+    Ok(integer(n) / (integer(n - 1) * integer(n - 2)) * central_sum(args, &m, 3) / cube)
+}
+
+/// `kurt(values...)`: the sample excess kurtosis, as spreadsheets compute it.
+pub fn kurt(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    check_real(args)?;
+    let n = args.len();
+    let m = mean(args, ctx)?;
+    let v = central_sum(args, &m, 2) / integer(n - 1);
+    if is_zero(&v) {
+        return Err(EngineError::DivisionByZero);
+    }
+    let scale = integer(n) * integer(n + 1) / (integer(n - 1) * integer(n - 2) * integer(n - 3));
+    let correction =
+        integer(3) * integer(n - 1) * integer(n - 1) / (integer(n - 2) * integer(n - 3));
+    Ok(scale * central_sum(args, &m, 4) / (v.clone() * v) - correction)
+}
+
+// The geometric and harmonic means need positive values
+fn check_positive(args: &[Number], name: &str) -> Result<(), EngineError> {
+    check_real(args)?;
+    if args.iter().any(|n| n.to_f64().is_some_and(|f| f <= 0.0)) {
+        return Err(EngineError::DomainError(format!(
+            "{} needs positive values",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// `geomean(values...)`: the nth root of the product of n positive values,
+/// exact when that root is.
+pub fn geomean(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    check_positive(args, "geomean")?;
+    let n = args.len();
+    if args
+        .iter()
+        .all(|x| matches!(x, Number::Integer(_) | Number::Rational(_)))
+        && let Ok(n) = u32::try_from(n)
+    {
+        let product = args
+            .iter()
+            .fold(Number::Integer(BigInt::one()), |acc, x| acc * x.clone());
+        // The root of the exact product is exact when it can be, and
+        // correctly rounded otherwise, unless the product is out of range
+        if ctx.guard().check_number(&product).is_ok() {
+            let inverse = from_ratio(BigRational::new(BigInt::one(), BigInt::from(n)));
+            match pow(product, inverse, ctx)? {
+                Number::Float(f) if !f.is_finite() || f == 0.0 => {}
+                root => return Ok(root),
+            }
+        }
+    }
+    // Through the mean logarithm otherwise, so that nothing can overflow
+    let logs: f64 = args.iter().filter_map(|x| x.to_f64()).map(f64::ln).sum();
+    Ok(Number::Float((logs / n as f64).exp()))
+}
+
+/// `harmean(values...)`: n divided by the sum of the reciprocals of n
+/// positive values.
+pub fn harmean(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    check_positive(args, "harmean")?;
+    let one = Number::Integer(BigInt::one());
+    let mut sum = Number::Integer(BigInt::zero());
+    for x in args {
+        sum = sum + one.clone() / x.clone();
+    }
+    Ok(integer(args.len()) / sum)
+}
+
+// Paired data: two lists of the same length with their means
+struct Pairs {
+    xs: Vec<Number>,
+    ys: Vec<Number>,
+    mean_x: Number,
+    mean_y: Number,
+}
+
+impl Pairs {
+    fn new(
+        xs: &Number,
+        ys: &Number,
+        name: &str,
+        min: usize,
+        ctx: &Context,
+    ) -> Result<Self, EngineError> {
+        let (xs, ys) = (sample(xs)?, sample(ys)?);
+        if xs.len() != ys.len() {
+            return Err(EngineError::DimensionMismatch(format!(
+                "lists of length {} and {} passed to '{}'",
+                xs.len(),
+                ys.len(),
+                name
+            )));
+        }
+        if xs.len() < min {
+            return Err(EngineError::DomainError(format!(
+                "{} needs at least {} pairs of values",
+                name, min
+            )));
+        }
+        let (mean_x, mean_y) = (mean(&xs, ctx)?, mean(&ys, ctx)?);
+        Ok(Pairs {
+            xs,
+            ys,
+            mean_x,
+            mean_y,
+        })
+    }
+
+    // The regression functions take `ys` before `xs`, and report lengths in
+    // the order of their arguments
+    fn regression(
+        ys: &Number,
+        xs: &Number,
+        name: &str,
+        ctx: &Context,
+    ) -> Result<Self, EngineError> {
+        let Pairs {
+            xs: ys,
+            ys: xs,
+            mean_x: mean_y,
+            mean_y: mean_x,
+        } = Pairs::new(ys, xs, name, 2, ctx)?;
+        Ok(Pairs {
+            xs,
+            ys,
+            mean_x,
+            mean_y,
+        })
+    }
+
+    fn len(&self) -> Number {
+        integer(self.xs.len())
+    }
+
+    // Σ (x - mean x)(y - mean y)
+    fn sxy(&self) -> Number {
+        let mut sum = Number::Integer(BigInt::zero());
+        for (x, y) in self.xs.iter().zip(&self.ys) {
+            sum = sum + (x.clone() - self.mean_x.clone()) * (y.clone() - self.mean_y.clone());
+        }
+        sum
+    }
+
+    fn sxx(&self) -> Number {
+        central_sum(&self.xs, &self.mean_x, 2)
+    }
+
+    fn syy(&self) -> Number {
+        central_sum(&self.ys, &self.mean_y, 2)
+    }
+
+    // The least-squares line y = intercept + slope x
+    fn line(&self) -> Result<(Number, Number), EngineError> {
+        let sxx = self.sxx();
+        if is_zero(&sxx) {
+            return Err(EngineError::DivisionByZero);
+        }
+        let slope = self.sxy() / sxx;
+        let intercept = self.mean_y.clone() - slope.clone() * self.mean_x.clone();
+        Ok((intercept, slope))
+    }
+}
+
+/// `covar(xs, ys)`: the population covariance of paired data.
+pub fn covar(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let pairs = Pairs::new(&args[0], &args[1], "covar", 1, ctx)?;
+    Ok(pairs.sxy() / pairs.len())
+}
+
+/// `covariance.s(xs, ys)`: the sample covariance of paired data.
+pub fn covariance_s(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let pairs = Pairs::new(&args[0], &args[1], "covariance.s", 2, ctx)?;
+    Ok(pairs.sxy() / integer(pairs.xs.len() - 1))
+}
+
+/// `correl(xs, ys)`: Pearson's correlation coefficient.
+pub fn correl(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let pairs = Pairs::new(&args[0], &args[1], "correl", 2, ctx)?;
+    let product = pairs.sxx() * pairs.syy();
+    if is_zero(&product) {
+        return Err(EngineError::DivisionByZero);
+    }
+    let half = from_ratio(BigRational::new(BigInt::one(), BigInt::from(2)));
+    Ok(pairs.sxy() / pow(product, half, ctx)?)
+}
+
+/// `slope(ys, xs)`: the slope of the least-squares line through the points.
+pub fn slope(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let pairs = Pairs::regression(&args[0], &args[1], "slope", ctx)?;
+    Ok(pairs.line()?.1)
+}
+
+/// `intercept(ys, xs)`: where the least-squares line crosses x = 0.
+pub fn intercept(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let pairs = Pairs::regression(&args[0], &args[1], "intercept", ctx)?;
+    Ok(pairs.line()?.0)
+}
+
+/// `rsq(ys, xs)`: the square of Pearson's correlation coefficient, the
+/// fraction of the variance of `ys` the least-squares line explains.
+pub fn rsq(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let pairs = Pairs::regression(&args[0], &args[1], "rsq", ctx)?;
+    let product = pairs.sxx() * pairs.syy();
+    if is_zero(&product) {
+        return Err(EngineError::DivisionByZero);
+    }
+    let sxy = pairs.sxy();
+    Ok(sxy.clone() * sxy / product)
+}
+
+/// `forecast(x, ys, xs)`: the least-squares line evaluated at `x`, or at each
+/// element of a list of `x`.
+pub fn forecast(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let pairs = Pairs::regression(&args[1], &args[2], "forecast", ctx)?;
+    let (intercept, slope) = pairs.line()?;
+    let at = |x: &Number| {
+        if x.to_f64().is_none() {
+            return Err(EngineError::TypeMismatch(
+                "real number".into(),
+                x.type_name().into(),
+            ));
+        }
+        Ok(intercept.clone() + slope.clone() * x.clone())
+    };
+    match &args[0] {
+        Number::List(items) => items
+            .iter()
+            .map(at)
+            .collect::<Result<_, _>>()
+            .map(Number::List),
+        x => at(x),
+    }
+}
+
+inventory::submit! { FunctionDef::new("sum", sum, Category::Statistics, "fn-sum").params(&["values"]).variadic(1).splat() }
+inventory::submit! { FunctionDef::new("prod", prod, Category::Statistics, "fn-prod").params(&["values"]).variadic(1).splat() }
+inventory::submit! { FunctionDef::new("mean", mean, Category::Statistics, "fn-mean").params(&["values"]).variadic(1).splat() }
+inventory::submit! { FunctionDef::new("median", median, Category::Statistics, "fn-median").params(&["values"]).variadic(1).splat() }
+inventory::submit! { FunctionDef::new("var", variance, Category::Statistics, "fn-var").params(&["values"]).variadic(2).splat().aliases(&["var.s"]) }
+inventory::submit! { FunctionDef::new("std", std_dev, Category::Statistics, "fn-std").params(&["values"]).variadic(2).splat().aliases(&["stdev.s", "stdev"]) }
+inventory::submit! { FunctionDef::new("var.p", variance_p, Category::Statistics, "fn-var-p").params(&["values"]).variadic(1).splat() }
+inventory::submit! { FunctionDef::new("stdev.p", std_dev_p, Category::Statistics, "fn-stdev-p").params(&["values"]).variadic(1).splat() }
+inventory::submit! { FunctionDef::new("mode", mode, Category::Statistics, "fn-mode").params(&["values"]).variadic(1).splat() }
+inventory::submit! { FunctionDef::new("percentile", percentile, Category::Statistics, "fn-percentile").params(&["values", "p", "method"]).optional(1).takes_lists() }
+inventory::submit! { FunctionDef::new("quartile", quartile, Category::Statistics, "fn-quartile").params(&["values", "q", "method"]).optional(1).takes_lists() }
+inventory::submit! { FunctionDef::new("skew", skew, Category::Statistics, "fn-skew").params(&["values"]).variadic(3).splat() }
+inventory::submit! { FunctionDef::new("kurt", kurt, Category::Statistics, "fn-kurt").params(&["values"]).variadic(4).splat() }
+inventory::submit! { FunctionDef::new("geomean", geomean, Category::Statistics, "fn-geomean").params(&["values"]).variadic(1).splat() }
+inventory::submit! { FunctionDef::new("harmean", harmean, Category::Statistics, "fn-harmean").params(&["values"]).variadic(1).splat() }
+inventory::submit! { FunctionDef::new("covar", covar, Category::Statistics, "fn-covar").params(&["xs", "ys"]).aliases(&["covariance.p"]).takes_lists() }
+inventory::submit! { FunctionDef::new("covariance.s", covariance_s, Category::Statistics, "fn-covariance-s").params(&["xs", "ys"]).takes_lists() }
+inventory::submit! { FunctionDef::new("correl", correl, Category::Statistics, "fn-correl").params(&["xs", "ys"]).takes_lists() }
+inventory::submit! { FunctionDef::new("slope", slope, Category::Statistics, "fn-slope").params(&["ys", "xs"]).takes_lists() }
+inventory::submit! { FunctionDef::new("intercept", intercept, Category::Statistics, "fn-intercept").params(&["ys", "xs"]).takes_lists() }
+inventory::submit! { FunctionDef::new("rsq", rsq, Category::Statistics, "fn-rsq").params(&["ys", "xs"]).takes_lists() }
+inventory::submit! { FunctionDef::new("forecast", forecast, Category::Statistics, "fn-forecast").params(&["x", "ys", "xs"]).aliases(&["forecast.linear"]).takes_lists() }
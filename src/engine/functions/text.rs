@@ -0,0 +1,338 @@
+use crate::engine::angle::AngleUnit;
+use crate::engine::ast::{Context, Expr, UnaryOp};
+use crate::engine::bigfloat::BigFloat;
+use crate::engine::errors::EngineError;
+use crate::engine::functions::{Category, FunctionDef};
+use crate::engine::parser;
+use crate::engine::types::{Number, from_ratio};
+use crate::utils::{format_number, format_radix};
+use num::{Signed, ToPrimitive, Zero};
+use num_bigint::BigInt;
+use num_rational::BigRational;
+
+fn expect_text(arg: &Number) -> Result<&str, EngineError> {
+    match arg {
+        Number::Text(s) => Ok(s),
+        other => Err(EngineError::TypeMismatch(
+            "text".into(),
+            other.type_name().into(),
+        )),
+    }
+}
+
+// Exact value of a real number; floats by their shortest decimal form so
+// text(2.675, "0.00") rounds like the number reads
+fn exact_decimal(n: &Number) -> Result<BigRational, EngineError> {
+    let exact = match n {
+        Number::Integer(i) => Some(BigRational::from_integer(i.clone())),
+        Number::Rational(r) => Some(r.clone()),
+        Number::Float(f) => BigFloat::from_f64(*f, 17).map(|b| b.to_rational()),
+        Number::BigFloat(b) => Some(b.to_rational()),
+        Number::Complex(c) if c.im == 0.0 => BigFloat::from_f64(c.re, 17).map(|b| b.to_rational()),
+        _ => {
+            return Err(EngineError::TypeMismatch(
+                "real number".into(),
+                n.type_name().into(),
+            ));
+        }
+    };
+    exact.ok_or_else(|| EngineError::DomainError("cannot format an infinite value".into()))
+}
+
+fn pow10(e: usize) -> BigRational {
+    BigRational::from_integer(num::pow(BigInt::from(10), e))
+}
+
+// Inserts a comma between groups of three digits
+fn group_thousands(digits: &str) -> String {
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    grouped
+}
+
+/// A numeric format such as `#,##0.00`, `0.0#%` or `0.00E+00`, with any
+/// literal text around it (`$#,##0`, `0.0 kg`).
+struct Pattern {
+    prefix: String,
+    suffix: String,
+    int_digits: usize,
+    grouping: bool,
+    min_decimals: usize,
+    max_decimals: usize,
+    /// Exponent digits, and whether `+` is written for positive exponents
+    exponent: Option<(usize, bool)>,
+    percent: bool,
+}
+
+impl Pattern {
+    fn parse(format: &str) -> Option<Pattern> {
+        let chars: Vec<char> = format.chars().collect();
+        let is_digit = |i: usize| matches!(chars.get(i), Some('0' | '#'));
+        let start =
+            (0..chars.len()).find(|&i| is_digit(i) || (chars[i] == '.' && is_digit(i + 1)))?;
+        let mut i = start;
+        let (mut int_digits, mut grouping) = (0, false);
+        while let Some(&c) = chars.get(i).filter(|c| matches!(c, '0' | '#' | ',')) {
+            int_digits += usize::from(c == '0');
+            grouping |= c == ',';
+            i += 1;
+        }
+        let (mut min_decimals, mut max_decimals) = (0, 0);
+        if chars.get(i) == Some(&'.') {
+            i += 1;
+            while is_digit(i) {
+                if chars[i] == '0' && min_decimals == max_decimals {
+                    min_decimals += 1;
+                }
+                max_decimals += 1;
+                i += 1;
+            }
+        }
+        let mut exponent = None;
+        if matches!(chars.get(i), Some('E' | 'e'))
+            && matches!(chars.get(i + 1), Some('+' | '-'))
+            && chars.get(i + 2) == Some(&'0')
+        {
+            let plus = chars[i + 1] == '+';
+            i += 2;
+            let digits = chars[i..].iter().take_while(|&&c| c == '0').count();
+            i += digits;
+            exponent = Some((digits, plus));
+        }
+        let prefix: String = chars[..start].iter().collect();
+        let suffix: String = chars[i..].iter().collect();
+        let percent = prefix.contains('%') || suffix.contains('%');
+        Some(Pattern {
+            prefix,
+            suffix,
+            int_digits,
+            grouping,
+            min_decimals,
+            max_decimals,
+            exponent,
+            percent,
+        })
+    }
+
+    // Digits of |value| rounded to `max_decimals`, half away from zero
+    fn fixed(&self, value: &BigRational) -> String {
+        let scaled = (value.abs() * pow10(self.max_decimals))
+            .round()
+            .to_integer();
+        let digits = format!("{:0>width$}", scaled, width = self.max_decimals + 1);
+        let (int_part, frac_part) = digits.split_at(digits.len() - self.max_decimals);
+        let frac_part = frac_part.trim_end_matches('0');
+        let frac_part = format!("{:0<width$}", frac_part, width = self.min_decimals);
+        let int_part = int_part.trim_start_matches('0');
+        let int_part = format!("{:0>width$}", int_part, width = self.int_digits);
+        let int_part = if self.grouping {
+            group_thousands(&int_part)
+        } else {
+            int_part
+        };
+        if frac_part.is_empty() {
+            int_part
+        } else {
+            format!("{}.{}", int_part, frac_part)
+        }
+    }
+
+    fn format(&self, value: &Number) -> Result<String, EngineError> {
+        let mut value = exact_decimal(value)?;
+        if self.percent {
+            value *= BigRational::from_integer(100.into());
+        }
+        let body = match self.exponent {
+            None => self.fixed(&value),
+            Some((digits, plus)) => self.scientific(&value, digits, plus),
+        };
+        let negative = value.is_negative() && body.chars().any(|c| matches!(c, '1'..='9'));
+        let sign = if negative { "-" } else { "" };
+        Ok(format!("{}{}{}{}", sign, self.prefix, body, self.suffix))
+    }
+
+    fn scientific(&self, value: &BigRational, digits: usize, plus: bool) -> String {
+        let magnitude = value.abs();
+        let scale = |e: i64| {
+            if e >= 0 {
+                pow10(e as usize)
+            } else {
+                pow10(e.unsigned_abs() as usize).recip()
+            }
+        };
+        let mut exponent: i64 = 0;
+        if !magnitude.is_zero() {
+            // Correct the float estimate exactly, then again if rounding the
+            // mantissa carries it up to 10
+            exponent = magnitude.to_f64().map_or(0.0, |f| f.log10().floor()) as i64;
+            while magnitude >= scale(exponent + 1) {
+                exponent += 1;
+            }
+            while magnitude < scale(exponent) {
+                exponent -= 1;
+            }
+            let rounded = (magnitude.clone() / scale(exponent) * pow10(self.max_decimals)).round();
+            if rounded >= pow10(self.max_decimals + 1) {
+                exponent += 1;
+            }
+        }
+        format!(
+            "{}E{}{:0>width$}",
+            self.fixed(&(magnitude / scale(exponent))),
+            if exponent < 0 {
+                "-"
+            } else if plus {
+                "+"
+            } else {
+                ""
+            },
+            exponent.unsigned_abs(),
+            width = digits
+        )
+    }
+}
+
+// An angle in the context's unit as degrees, minutes and seconds: 30°30'0"
+fn format_dms(value: &Number, ctx: &Context) -> Result<String, EngineError> {
+    let degrees = exact_decimal(&ctx.angle_unit.convert(value.clone(), AngleUnit::Degrees))?;
+    let hundredths = (degrees.abs() * BigRational::from_integer(360_000.into()))
+        .round()
+        .to_integer();
+    let (d, rest) = (&hundredths / 360_000, &hundredths % 360_000);
+    let (m, s) = (&rest / 6000, &rest % 6000);
+    let seconds = from_ratio(BigRational::new(s, 100.into()));
+    let sign = if degrees.is_negative() && !hundredths.is_zero() {
+        "-"
+    } else {
+        ""
+    };
+    Ok(format!(
+        "{}{}°{}'{}\"",
+        sign,
+        d,
+        m,
+        format_number(seconds, true)
+    ))
+}
+
+/// `text(x, [format])` writes a number as text. Formats are spreadsheet-style
+/// patterns (`0.00`, `#,##0`, `0%`, `0.00E+00`, with literal text around
+/// them) or one of `hex`, `bin`, `oct` and `dms`. Without a format the number
+/// is written as it is displayed; text is returned unchanged.
+pub fn text(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let value = &args[0];
+    if let Number::Text(_) = value {
+        return Ok(value.clone());
+    }
+    let Some(format) = args.get(1) else {
+        return Ok(Number::Text(format_number(value.clone(), true)));
+    };
+    let format = expect_text(format)?;
+    let radix = match format.to_ascii_lowercase().as_str() {
+        "hex" => Some(16),
+        "oct" => Some(8),
+        "bin" => Some(2),
+        "dms" => return format_dms(value, ctx).map(Number::Text),
+        _ => None,
+    };
+    if let Some(radix) = radix {
+        let integer = match value {
+            Number::Integer(i) => Some(i.clone()),
+            other => exact_decimal(other)
+                .ok()
+                .filter(|r| r.is_integer())
+                .map(|r| r.to_integer()),
+        };
+        let integer = integer.ok_or_else(|| {
+            EngineError::DomainError(format!("'{}' format needs an integer", format))
+        })?;
+        // Programmer mode shows negative values as their two's complement
+        let text = match ctx.word_size {
+            Some(word) => word.format_radix(&integer, radix),
+            None => format_radix(&integer, radix),
+        };
+        return Ok(Number::Text(text.unwrap_or_default()));
+    }
+    let pattern = Pattern::parse(format)
+        .ok_or_else(|| EngineError::DomainError(format!("unknown number format '{}'", format)))?;
+    pattern.format(value).map(Number::Text)
+}
+
+// A literal as evaluation would produce it: decimals become exact in
+// precision mode, floats otherwise
+fn literal_value(n: &Number, ctx: &Context) -> Option<Number> {
+    match n {
+        Number::Float(f) => Some(
+            ctx.precision()
+                .and_then(|p| n.to_bigfloat(p))
+                .map_or(Number::Float(*f), Number::BigFloat),
+        ),
+        Number::BigFloat(b) => Some(match ctx.precision() {
+            Some(p) => Number::BigFloat(b.with_precision(p)),
+            None => Number::Float(b.to_f64()),
+        }),
+        Number::Text(_) | Number::List(_) | Number::Matrix(_) => None,
+        other => Some(other.clone()),
+    }
+}
+
+/// `value(text)` reads a number written as text, in any form the calculator
+/// accepts as a literal: `"42"`, `"-1.5e3"`, `"0x1F"`, `"36#ZZ"`, `"2i"`. A
+/// trailing `%` divides by 100.
+pub fn value(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let source = match &args[0] {
+        Number::Text(s) => s.trim(),
+        other => return Ok(other.clone()),
+    };
+    let not_a_number = || EngineError::DomainError(format!("\"{}\" is not a number", source));
+    let (literal, percent) = match source.strip_suffix('%') {
+        Some(rest) => (rest.trim_end(), true),
+        None => (source, false),
+    };
+    let number = match parser::parse(literal).map_err(|_| not_a_number())? {
+        Expr::Literal(n) => literal_value(&n, ctx),
+        Expr::UnaryOp(UnaryOp::Neg, inner) => match *inner {
+            Expr::Literal(n) => literal_value(&n, ctx).map(|n| -n),
+            _ => None,
+        },
+        _ => None,
+    }
+    .ok_or_else(not_a_number)?;
+    if percent {
+        return Ok(number / Number::Integer(100.into()));
+    }
+    Ok(number)
+}
+
+/// `char(code)` is the character with the given Unicode code point.
+pub fn char(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let code = match &args[0] {
+        Number::Integer(i) => i.to_u32(),
+        other => exact_decimal(other)
+            .ok()
+            .filter(|r| r.is_integer())
+            .and_then(|r| r.to_integer().to_u32()),
+    };
+    code.and_then(char::from_u32)
+        .map(|c| Number::Text(c.to_string()))
+        .ok_or_else(|| EngineError::DomainError("not a Unicode code point".into()))
+}
+
+/// `code(text)` is the Unicode code point of the first character.
+pub fn code(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    let text = expect_text(&args[0])?;
+    text.chars()
+        .next()
+        .map(|c| Number::Integer(BigInt::from(c as u32)))
+        .ok_or_else(|| EngineError::DomainError("code of empty text".into()))
+}
+
+inventory::submit! { FunctionDef::new("text", text, Category::Text, "fn-text").params(&["value", "format"]).optional(1).takes_text().takes_booleans() }
+inventory::submit! { FunctionDef::new("value", value, Category::Text, "fn-value").params(&["text"]).takes_text() }
+inventory::submit! { FunctionDef::new("char", char, Category::Text, "fn-char").params(&["code"]) }
+inventory::submit! { FunctionDef::new("code", code, Category::Text, "fn-code").params(&["text"]).takes_text() }
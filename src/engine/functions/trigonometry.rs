@@ -1,44 +1,219 @@
-use crate::engine::types::Number;
+use crate::engine::angle::AngleUnit;
+use crate::engine::ast::Context;
+use crate::engine::bigfloat::BigFloat;
 use crate::engine::errors::EngineError;
-use crate::engine::functions::FunctionDef;
+use crate::engine::functions::{Category, FunctionDef, precise_arg, real_or_complex};
+use crate::engine::limits::Guard;
+use crate::engine::types::{Number, from_ratio};
 use num::complex::Complex64;
+use num_rational::BigRational;
 
-fn one_arg(args: &[Number], name: &str) -> Result<Complex64, EngineError> {
-    if args.len() != 1 {
-        return Err(EngineError::ArgumentMismatch(name.into(), 1));
+// Exact sines at multiples of 15°, indexed by 24ths of a turn. Multiples of
+// 30° and 90° are rational; the rest are left to floating point. This lets
+// sin(pi) be exactly 0 instead of 1.2e-16 and sin(30°) exactly 1/2.
+fn exact_sin(k: i64) -> Option<Number> {
+    match k.rem_euclid(24) {
+        0 | 12 => Some(Number::Integer(0.into())),
+        6 => Some(Number::Integer(1.into())),
+        18 => Some(Number::Integer((-1).into())),
+        2 | 10 => Some(Number::Rational(BigRational::new(1.into(), 2.into()))),
+        14 | 22 => Some(Number::Rational(BigRational::new((-1).into(), 2.into()))),
+        _ => None,
     }
-    Ok(args[0].to_complex())
 }
 
-pub fn sin(args: &[Number]) -> Result<Number, EngineError> {
-    Ok(Number::Complex(one_arg(args, "sin")?.sin()))
+// Evaluates a forward trig function: exact at special angles, otherwise on
+// the argument converted to radians
+fn forward(
+    args: &[Number],
+    ctx: &Context,
+    exact: fn(i64) -> Result<Option<Number>, EngineError>,
+    precise: fn(&BigFloat, &Guard) -> Result<BigFloat, EngineError>,
+    real: fn(f64) -> f64,
+    complex: fn(Complex64) -> Complex64,
+) -> Result<Number, EngineError> {
+    if let Some(k) = ctx.angle_unit.twenty_fourths(&args[0])
+        && let Some(result) = exact(k)?
+    {
+        return Ok(result);
+    }
+    let x = ctx.angle_unit.convert(args[0].clone(), AngleUnit::Radians);
+    match precise_arg(std::slice::from_ref(&x), ctx) {
+        Some(x) => Ok(Number::BigFloat(precise(&x, &ctx.guard())?)),
+        None => Ok(real_or_complex(x.to_complex(), real, complex)),
+    }
+}
+
+pub fn sin(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    forward(
+        args,
+        ctx,
+        |k| Ok(exact_sin(k)),
+        BigFloat::sin,
+        f64::sin,
+        Complex64::sin,
+    )
+}
+
+pub fn cos(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    forward(
+        args,
+        ctx,
+        |k| Ok(exact_sin(k + 6)),
+        BigFloat::cos,
+        f64::cos,
+        Complex64::cos,
+    )
+}
+
+pub fn tan(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let exact = |k: i64| match k.rem_euclid(12) {
+        0 => Ok(Some(Number::Integer(0.into()))),
+        3 => Ok(Some(Number::Integer(1.into()))),
+        9 => Ok(Some(Number::Integer((-1).into()))),
+        6 => Err(EngineError::DomainError(
+            "tan is undefined at odd multiples of π/2".into(),
+        )),
+        _ => Ok(None),
+    };
+    forward(args, ctx, exact, BigFloat::tan, f64::tan, Complex64::tan)
+}
+
+// Inverse functions compute in radians and report in the context's unit.
+// In degrees and gradians the standard angles come out exact (asin(1/2) = 30)
+// given their 24ths of a turn for the argument.
+fn inverse(ctx: &Context, special: Option<i64>, radians: Number) -> Number {
+    special
+        .and_then(|k| ctx.angle_unit.from_twenty_fourths(k))
+        .unwrap_or_else(|| AngleUnit::Radians.convert(radians, ctx.angle_unit))
+}
+
+// 24ths of a turn for asin at 0, ±1/2 and ±1
+fn special_asin(x: &Number) -> Option<i64> {
+    let x = x.to_f64()?;
+    [(0.0, 0), (0.5, 2), (-0.5, -2), (1.0, 6), (-1.0, -6)]
+        .iter()
+        .find(|(v, _)| *v == x)
+        .map(|(_, k)| *k)
+}
+
+// Real on [-1, 1]; outside it the inverse sine and cosine are complex
+fn in_unit_interval(z: Complex64) -> bool {
+    z.im == 0.0 && z.re.abs() <= 1.0
 }
 
-pub fn cos(args: &[Number]) -> Result<Number, EngineError> {
-    Ok(Number::Complex(one_arg(args, "cos")?.cos()))
+pub fn asin(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let z = args[0].to_complex();
+    let precise = precise_arg(args, ctx).map(|x| x.asin(&ctx.guard()));
+    let radians = match precise.transpose()?.flatten() {
+        Some(r) => Number::BigFloat(r),
+        None if in_unit_interval(z) => Number::Float(z.re.asin()),
+        None => Number::Complex(z.asin()),
+    };
+    Ok(inverse(ctx, special_asin(&args[0]), radians))
 }
 
-pub fn tan(args: &[Number]) -> Result<Number, EngineError> {
-    Ok(Number::Complex(one_arg(args, "tan")?.tan()))
+pub fn acos(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let z = args[0].to_complex();
+    let precise = precise_arg(args, ctx).map(|x| x.acos(&ctx.guard()));
+    let radians = match precise.transpose()?.flatten() {
+        Some(r) => Number::BigFloat(r),
+        None if in_unit_interval(z) => Number::Float(z.re.acos()),
+        None => Number::Complex(z.acos()),
+    };
+    Ok(inverse(ctx, special_asin(&args[0]).map(|k| 6 - k), radians))
 }
 
-pub fn asin(args: &[Number]) -> Result<Number, EngineError> {
-    Ok(Number::Complex(one_arg(args, "asin")?.asin()))
+pub fn atan(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let z = args[0].to_complex();
+    let radians = match precise_arg(args, ctx) {
+        Some(x) => Number::BigFloat(x.atan(&ctx.guard())?),
+        None => real_or_complex(z, f64::atan, Complex64::atan),
+    };
+    let special = match args[0].to_f64() {
+        Some(0.0) => Some(0),
+        Some(1.0) => Some(3),
+        Some(-1.0) => Some(-3),
+        _ => None,
+    };
+    Ok(inverse(ctx, special, radians))
 }
 
-pub fn acos(args: &[Number]) -> Result<Number, EngineError> {
-    Ok(Number::Complex(one_arg(args, "acos")?.acos()))
+/// `deg(x)` and `grad(x)` convert radians to degrees or gradians and `rad(x)`
+/// converts degrees to radians, as in spreadsheets, whatever the context's
+/// angle unit. An angle literal in another unit is converted first.
+fn convert(args: &[Number], from: AngleUnit, to: AngleUnit) -> Result<Number, EngineError> {
+    Ok(from.convert(args[0].clone(), to))
 }
 
-pub fn atan(args: &[Number]) -> Result<Number, EngineError> {
-    Ok(Number::Complex(one_arg(args, "atan")?.atan()))
+pub fn deg(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    convert(args, AngleUnit::Radians, AngleUnit::Degrees)
+}
+
+pub fn rad(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    convert(args, AngleUnit::Degrees, AngleUnit::Radians)
+}
+
+pub fn grad(args: &[Number], _ctx: &Context) -> Result<Number, EngineError> {
+    convert(args, AngleUnit::Radians, AngleUnit::Gradians)
+}
+
+/// `dms(d, m[, s])` builds an angle in the context's unit from degrees,
+/// minutes and seconds; `dms(x)` goes the other way and gives the angle as
+/// sexagesimal D.MMSS (`dms(30.5°)` in degree mode is 30.3, i.e. 30°30'00").
+pub fn dms(args: &[Number], ctx: &Context) -> Result<Number, EngineError> {
+    let sixty = || Number::Integer(60.into());
+    if args.iter().any(|a| matches!(a, Number::Complex(_))) {
+        return Err(EngineError::TypeMismatch(
+            "real angle".into(),
+            "complex number".into(),
+        ));
+    }
+    match args.len() {
+        1 => {
+            let degrees = ctx.angle_unit.convert(args[0].clone(), AngleUnit::Degrees);
+            let negative =
+                degrees.partial_cmp(&Number::Integer(0.into())) == Some(std::cmp::Ordering::Less);
+            let x = if negative { -degrees } else { degrees };
+            // Floats are taken at their shortest decimal value so 30.5 splits cleanly
+            let x = match x {
+                Number::Float(f) => {
+                    BigFloat::from_f64(f, 17).map_or(x, |b| from_ratio(b.to_rational()))
+                }
+                other => other,
+            };
+            // Whole part, exact for exact inputs
+            let whole = |n: &Number| match n {
+                Number::Rational(r) => Number::Integer(r.floor().to_integer()),
+                Number::BigFloat(b) => Number::Integer(b.floor()),
+                Number::Float(f) => Number::Float(f.floor()),
+                other => other.clone(),
+            };
+            let d = whole(&x);
+            let minutes = (x - d.clone()) * sixty();
+            let m = whole(&minutes);
+            let s = (minutes - m.clone()) * sixty();
+            let hundred = Number::Integer(100.into());
+            let result = d + m / hundred.clone() + s / (hundred.clone() * hundred);
+            Ok(if negative { -result } else { result })
+        }
+        _ => {
+            let mut degrees = args[0].clone() + args[1].clone() / sixty();
+            if let Some(s) = args.get(2) {
+                degrees = degrees + s.clone() / (sixty() * sixty());
+            }
+            Ok(AngleUnit::Degrees.convert(degrees, ctx.angle_unit))
+        }
+    }
 }
 
-inventory::submit! { FunctionDef { name: "sin", func: sin } }
-inventory::submit! { FunctionDef { name: "cos", func: cos } }
-inventory::submit! { FunctionDef { name: "tan", func: tan } }
-inventory::submit! { FunctionDef { name: "asin", func: asin } }
-inventory::submit! { FunctionDef { name: "acos", func: acos } }
-inventory::submit! { FunctionDef { name: "atan", func: atan } }
-// Aliases
-inventory::submit! { FunctionDef { name: "cosin", func: acos } }
+inventory::submit! { FunctionDef::new("sin", sin, Category::Trigonometry, "fn-sin").params(&["angle"]) }
+inventory::submit! { FunctionDef::new("cos", cos, Category::Trigonometry, "fn-cos").params(&["angle"]) }
+inventory::submit! { FunctionDef::new("tan", tan, Category::Trigonometry, "fn-tan").params(&["angle"]) }
+inventory::submit! { FunctionDef::new("asin", asin, Category::Trigonometry, "fn-asin").params(&["x"]) }
+inventory::submit! { FunctionDef::new("acos", acos, Category::Trigonometry, "fn-acos").params(&["x"]).aliases(&["cosin"]) }
+inventory::submit! { FunctionDef::new("atan", atan, Category::Trigonometry, "fn-atan").params(&["x"]) }
+inventory::submit! { FunctionDef::new("deg", deg, Category::Trigonometry, "fn-deg").params(&["angle"]) }
+inventory::submit! { FunctionDef::new("rad", rad, Category::Trigonometry, "fn-rad").params(&["angle"]) }
+inventory::submit! { FunctionDef::new("grad", grad, Category::Trigonometry, "fn-grad").params(&["angle"]) }
+inventory::submit! { FunctionDef::new("dms", dms, Category::Trigonometry, "fn-dms").params(&["degrees", "minutes", "seconds"]).optional(2) }
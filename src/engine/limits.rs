@@ -0,0 +1,165 @@
+//! Resource limits and cooperative cancellation for evaluation.
+//!
+//! The engine is meant to be "infinite" (arbitrary size integers, unbounded
+//! recursion in user functions), which also means a single keystroke can ask
+//! for `2^4000000000` or `f(n) = f(n)`. Hosts bound that work with
+//! `EvalLimits` and can abort a running `evaluate` from another thread with a
+//! `CancellationToken`.
+
+use super::errors::EngineError;
+use super::types::Number;
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EvalLimits {
+    /// Maximum nesting of expression evaluation, which includes user function
+    /// recursion. Each call takes two or three levels, so the default allows
+    /// recursion some 200 calls deep, within the 2 MiB stack of a spawned
+    /// thread. A higher limit needs a larger stack; see
+    /// `engine::evaluate_on_thread`.
+    pub max_depth: usize,
+    /// Maximum number of expression nodes evaluated by a single `evaluate`.
+    pub max_steps: u64,
+    /// Maximum bit length of any integer produced, including the numerator
+    /// and denominator of rationals.
+    pub max_bits: u64,
+    /// Wall-clock budget for a single `evaluate`.
+    pub timeout: Option<Duration>,
+    /// Maximum number of elements in a single list.
+    pub max_elements: usize,
+}
+
+impl Default for EvalLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: 500,
+            max_steps: 10_000_000,
+            max_bits: 1 << 22,
+            timeout: None,
+            max_elements: 1 << 20,
+        }
+    }
+}
+
+impl EvalLimits {
+    /// No limits at all; the pre-limits behavior of the engine.
+    pub fn unlimited() -> Self {
+        Self {
+            max_depth: usize::MAX,
+            max_steps: u64::MAX,
+            max_bits: u64::MAX,
+            timeout: None,
+            max_elements: usize::MAX,
+        }
+    }
+}
+
+/// A flag shared between the evaluating thread and the host.
+///
+/// Clones share the same flag, so the host keeps one clone and calls
+/// `cancel()` while another thread is inside `evaluate`.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Clears a previous cancellation so the token can be reused.
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+}
+
+/// Per-evaluation bookkeeping, reset by `Context::begin_evaluation`.
+#[derive(Debug, Clone, Default)]
+pub struct EvalState {
+    pub depth: usize,
+    pub steps: u64,
+    pub deadline: Option<Instant>,
+    /// Set when an integer result had to wrap to fit `Context::word_size`;
+    /// a `Cell` so operators that only borrow the context can note it.
+    pub overflow: Cell<bool>,
+}
+
+/// Everything long-running code needs to decide whether to keep going.
+/// Borrowed from the `Context` so builtins can poll it without `&mut`.
+pub struct Guard<'a> {
+    pub limits: &'a EvalLimits,
+    pub cancel: &'a CancellationToken,
+    pub deadline: Option<Instant>,
+}
+
+impl Guard<'_> {
+    /// Fails if the host cancelled or the deadline passed.
+    pub fn check_interrupt(&self) -> Result<(), EngineError> {
+        if self.cancel.is_cancelled() {
+            return Err(EngineError::Cancelled);
+        }
+        if let Some(deadline) = self.deadline
+            && Instant::now() >= deadline
+        {
+            return Err(EngineError::LimitExceeded("time limit".into()));
+        }
+        Ok(())
+    }
+
+    /// Fails if an integer of `bits` bits would exceed the size limit.
+    pub fn check_bits(&self, bits: u64) -> Result<(), EngineError> {
+        if bits > self.limits.max_bits {
+            return Err(EngineError::LimitExceeded(format!(
+                "integer size ({} bits, at most {} allowed)",
+                bits, self.limits.max_bits
+            )));
+        }
+        Ok(())
+    }
+
+    /// Applies `check_bits` to the exact parts of a number.
+    pub fn check_number(&self, n: &Number) -> Result<(), EngineError> {
+        match n {
+            Number::Integer(i) => self.check_bits(i.bits()),
+            Number::Rational(r) => self.check_bits(r.numer().bits().max(r.denom().bits())),
+            // Inexact values are bounded by their precision
+            Number::Float(_) | Number::BigFloat(_) | Number::Complex(_) | Number::Boolean(_) => {
+                Ok(())
+            }
+            Number::List(items) => {
+                self.check_elements(items.len())?;
+                items.iter().try_for_each(|item| self.check_number(item))
+            }
+            Number::Text(s) => self.check_elements(s.chars().count()),
+            Number::Quantity(q) => self.check_number(q.value()),
+            Number::Matrix(m) => {
+                self.check_elements(m.data().len())?;
+                m.data().iter().try_for_each(|item| self.check_number(item))
+            }
+        }
+    }
+
+    /// Fails if a list or matrix of `count` elements, or a text of `count`
+    /// characters, would exceed the size limit.
+    pub fn check_elements(&self, count: usize) -> Result<(), EngineError> {
+        if count > self.limits.max_elements {
+            return Err(EngineError::LimitExceeded(format!(
+                "value size ({} elements, at most {} allowed)",
+                count, self.limits.max_elements
+            )));
+        }
+        Ok(())
+    }
+}
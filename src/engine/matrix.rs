@@ -0,0 +1,577 @@
+//! Dense matrices of `Number`s.
+//!
+//! Entries keep their own numeric type, so a matrix of integers and rationals
+//! is inverted, reduced and solved exactly (`inv([2, 1; 1, 1])` is
+//! `[1, -1; -1, 2]`, not a float approximation). Matrices with float or complex
+//! entries use the same algorithms with partial pivoting, treating entries that
+//! are tiny relative to the matrix as zero.
+
+use super::errors::EngineError;
+use super::limits::Guard;
+use super::types::{Number, exact_root, from_ratio};
+use num::complex::Complex64;
+use num::{Signed, ToPrimitive, Zero};
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use serde::{Deserialize, Serialize};
+
+/// Largest matrix `eigenvalues` accepts.
+pub const MAX_EIGEN_SIZE: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Matrix {
+    rows: usize,
+    cols: usize,
+    /// Entries in row-major order
+    data: Vec<Number>,
+}
+
+fn zero() -> Number {
+    Number::Integer(BigInt::zero())
+}
+
+fn one() -> Number {
+    Number::Integer(BigInt::from(1))
+}
+
+fn is_exact(n: &Number) -> bool {
+    matches!(n, Number::Integer(_) | Number::Rational(_))
+}
+
+fn is_exact_zero(n: &Number) -> bool {
+    match n {
+        Number::Integer(i) => i.is_zero(),
+        Number::Rational(r) => r.is_zero(),
+        _ => false,
+    }
+}
+
+// Integer-valued rationals become integers
+fn tidy(n: Number) -> Number {
+    match n {
+        Number::Rational(r) => from_ratio(r),
+        other => other,
+    }
+}
+
+fn magnitude(n: &Number) -> f64 {
+    let m = n.to_complex().norm();
+    if m.is_nan() { 0.0 } else { m }
+}
+
+fn not_square(what: &str, m: &Matrix) -> EngineError {
+    EngineError::DimensionMismatch(format!(
+        "{} requires a square matrix, got {}×{}",
+        what, m.rows, m.cols
+    ))
+}
+
+fn singular() -> EngineError {
+    EngineError::DomainError("matrix is singular".into())
+}
+
+/// The result of Gauss-Jordan elimination.
+struct Reduction {
+    matrix: Matrix,
+    /// Pivot column of each nonzero row
+    pivots: Vec<usize>,
+    /// Product of the pivots, with the sign of the row swaps
+    determinant: Number,
+}
+
+impl Matrix {
+    /// A matrix from row-major entries; `data` must hold `rows * cols` values.
+    pub fn new(rows: usize, cols: usize, data: Vec<Number>) -> Result<Self, EngineError> {
+        if rows == 0 || cols == 0 || data.len() != rows * cols {
+            return Err(EngineError::DimensionMismatch(format!(
+                "{} entries do not make a {}×{} matrix",
+                data.len(),
+                rows,
+                cols
+            )));
+        }
+        Ok(Matrix { rows, cols, data })
+    }
+
+    /// A matrix from its rows, which must be non-empty and of equal length.
+    pub fn from_rows(rows: Vec<Vec<Number>>) -> Result<Self, EngineError> {
+        let cols = rows.first().map_or(0, Vec::len);
+        if let Some(row) = rows.iter().find(|r| r.len() != cols) {
+            return Err(EngineError::DimensionMismatch(format!(
+                "matrix rows of length {} and {}",
+                cols,
+                row.len()
+            )));
+        }
+        let count = rows.len();
+        Matrix::new(count, cols, rows.into_iter().flatten().collect())
+    }
+
+    pub fn identity(n: usize) -> Self {
+        let data = (0..n * n)
+            .map(|k| if k / n == k % n { one() } else { zero() })
+            .collect();
+        Matrix {
+            rows: n,
+            cols: n,
+            data,
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> &Number {
+        &self.data[row * self.cols + col]
+    }
+
+    pub fn row(&self, row: usize) -> Vec<Number> {
+        self.data[row * self.cols..(row + 1) * self.cols].to_vec()
+    }
+
+    /// Entries in row-major order.
+    pub fn data(&self) -> &[Number] {
+        &self.data
+    }
+
+    pub fn into_data(self) -> Vec<Number> {
+        self.data
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.rows == self.cols
+    }
+
+    pub fn map<F: FnMut(Number) -> Number>(self, f: F) -> Matrix {
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.into_iter().map(f).collect(),
+        }
+    }
+
+    pub fn try_map<F>(self, f: F) -> Result<Matrix, EngineError>
+    where
+        F: FnMut(Number) -> Result<Number, EngineError>,
+    {
+        Ok(Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.into_iter().map(f).collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Pairs up the entries of two matrices of the same shape.
+    pub fn zip_with<F: FnMut(Number, Number) -> Number>(self, other: Matrix, mut f: F) -> Matrix {
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self
+                .data
+                .into_iter()
+                .zip(other.data)
+                .map(|(a, b)| f(a, b))
+                .collect(),
+        }
+    }
+
+    pub fn try_zip_with<F>(self, other: Matrix, mut f: F) -> Result<Matrix, EngineError>
+    where
+        F: FnMut(Number, Number) -> Result<Number, EngineError>,
+    {
+        Ok(Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self
+                .data
+                .into_iter()
+                .zip(other.data)
+                .map(|(a, b)| f(a, b))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    pub fn transpose(&self) -> Matrix {
+        let data = (0..self.rows * self.cols)
+            .map(|k| self.get(k % self.rows, k / self.rows).clone())
+            .collect();
+        Matrix {
+            rows: self.cols,
+            cols: self.rows,
+            data,
+        }
+    }
+
+    /// The matrix product `self × other`.
+    pub fn mul(&self, other: &Matrix, guard: &Guard) -> Result<Matrix, EngineError> {
+        if self.cols != other.rows {
+            return Err(EngineError::DimensionMismatch(format!(
+                "cannot multiply {}×{} by {}×{}",
+                self.rows, self.cols, other.rows, other.cols
+            )));
+        }
+        let mut data = Vec::with_capacity(self.rows * other.cols);
+        for i in 0..self.rows {
+            guard.check_interrupt()?;
+            for j in 0..other.cols {
+                let mut sum = zero();
+                for k in 0..self.cols {
+                    sum = sum + self.get(i, k).clone() * other.get(k, j).clone();
+                }
+                guard.check_number(&sum)?;
+                data.push(tidy(sum));
+            }
+        }
+        Ok(Matrix {
+            rows: self.rows,
+            cols: other.cols,
+            data,
+        })
+    }
+
+    /// `self^n` for an integer `n`; negative powers invert first.
+    pub fn pow(&self, n: &BigInt, guard: &Guard) -> Result<Matrix, EngineError> {
+        if !self.is_square() {
+            return Err(not_square("a matrix power", self));
+        }
+        let mut base = if n.is_negative() {
+            self.inverse(guard)?
+        } else {
+            self.clone()
+        };
+        let mut e = n.abs();
+        let mut result = Matrix::identity(self.rows);
+        while !e.is_zero() {
+            if e.bit(0) {
+                result = result.mul(&base, guard)?;
+            }
+            e >>= 1;
+            if !e.is_zero() {
+                base = base.mul(&base, guard)?;
+            }
+        }
+        Ok(result)
+    }
+
+    // Entries below this magnitude count as zero when the matrix is inexact
+    fn tolerance(&self) -> f64 {
+        let scale = self.data.iter().map(magnitude).fold(0.0, f64::max);
+        scale * 1e-12
+    }
+
+    // Gauss-Jordan elimination to reduced row echelon form, only choosing
+    // pivots among the first `pivot_cols` columns
+    fn reduce(&self, pivot_cols: usize, guard: &Guard) -> Result<Reduction, EngineError> {
+        let tolerance = self.tolerance();
+        let negligible = |n: &Number| {
+            if is_exact(n) {
+                is_exact_zero(n)
+            } else {
+                magnitude(n) <= tolerance
+            }
+        };
+        let mut m = self.clone();
+        let mut pivots = Vec::new();
+        let mut determinant = one();
+        let mut row = 0;
+        for col in 0..pivot_cols {
+            if row == m.rows {
+                break;
+            }
+            guard.check_interrupt()?;
+            // Largest entry as the pivot, for stability with floats
+            let best = (row..m.rows)
+                .filter(|&r| !negligible(m.get(r, col)))
+                .max_by(|&a, &b| magnitude(m.get(a, col)).total_cmp(&magnitude(m.get(b, col))));
+            let Some(best) = best else {
+                continue;
+            };
+            if best != row {
+                for c in 0..m.cols {
+                    m.data.swap(row * m.cols + c, best * m.cols + c);
+                }
+                determinant = -determinant;
+            }
+            let pivot = m.get(row, col).clone();
+            determinant = determinant * pivot.clone();
+            for c in 0..m.cols {
+                let k = row * m.cols + c;
+                m.data[k] = tidy(m.data[k].clone() / pivot.clone());
+            }
+            m.data[row * m.cols + col] = one();
+            for r in (0..m.rows).filter(|&r| r != row) {
+                let factor = m.get(r, col).clone();
+                if negligible(&factor) {
+                    continue;
+                }
+                for c in 0..m.cols {
+                    let value = m.get(r, c).clone() - factor.clone() * m.get(row, c).clone();
+                    guard.check_number(&value)?;
+                    m.data[r * m.cols + c] = tidy(value);
+                }
+                m.data[r * m.cols + col] = zero();
+            }
+            pivots.push(col);
+            row += 1;
+        }
+        // Clean up float noise left in the reduced rows
+        for value in m.data.iter_mut() {
+            if !is_exact(value) && magnitude(value) <= tolerance {
+                *value = zero();
+            }
+        }
+        Ok(Reduction {
+            matrix: m,
+            pivots,
+            determinant: tidy(determinant),
+        })
+    }
+
+    /// The reduced row echelon form.
+    pub fn rref(&self, guard: &Guard) -> Result<Matrix, EngineError> {
+        Ok(self.reduce(self.cols, guard)?.matrix)
+    }
+
+    pub fn rank(&self, guard: &Guard) -> Result<usize, EngineError> {
+        Ok(self.reduce(self.cols, guard)?.pivots.len())
+    }
+
+    pub fn determinant(&self, guard: &Guard) -> Result<Number, EngineError> {
+        if !self.is_square() {
+            return Err(not_square("the determinant", self));
+        }
+        let reduction = self.reduce(self.cols, guard)?;
+        if reduction.pivots.len() < self.rows {
+            return Ok(zero());
+        }
+        Ok(reduction.determinant)
+    }
+
+    // Reduces [self | rhs] and returns the right-hand block
+    fn solve_augmented(&self, rhs: &Matrix, guard: &Guard) -> Result<Matrix, EngineError> {
+        if !self.is_square() {
+            return Err(not_square("solving", self));
+        }
+        if rhs.rows != self.rows {
+            return Err(EngineError::DimensionMismatch(format!(
+                "a {}×{} system needs {} right-hand side rows, got {}",
+                self.rows, self.cols, self.rows, rhs.rows
+            )));
+        }
+        let width = self.cols + rhs.cols;
+        let mut data = Vec::with_capacity(self.rows * width);
+        for r in 0..self.rows {
+            data.extend(self.row(r));
+            data.extend(rhs.row(r));
+        }
+        let augmented = Matrix::new(self.rows, width, data)?;
+        let reduction = augmented.reduce(self.cols, guard)?;
+        if reduction.pivots.len() < self.rows {
+            return Err(singular());
+        }
+        let m = reduction.matrix;
+        let data = (0..m.rows)
+            .flat_map(|r| m.row(r).into_iter().skip(self.cols))
+            .collect();
+        Matrix::new(self.rows, rhs.cols, data)
+    }
+
+    pub fn inverse(&self, guard: &Guard) -> Result<Matrix, EngineError> {
+        if !self.is_square() {
+            return Err(not_square("the inverse", self));
+        }
+        self.solve_augmented(&Matrix::identity(self.rows), guard)
+    }
+
+    /// The solution `x` of `self × x = rhs` for a square, non-singular matrix.
+    pub fn solve(&self, rhs: &Matrix, guard: &Guard) -> Result<Matrix, EngineError> {
+        self.solve_augmented(rhs, guard)
+    }
+
+    /// Coefficients of the characteristic polynomial `det(λI - A)`, highest
+    /// degree first, by the Faddeev-LeVerrier recurrence (exact for exact
+    /// entries).
+    pub fn characteristic_polynomial(&self, guard: &Guard) -> Result<Vec<Number>, EngineError> {
+        if !self.is_square() {
+            return Err(not_square("the characteristic polynomial", self));
+        }
+        let n = self.rows;
+        let mut coefficients = vec![one()];
+        let mut m = Matrix {
+            rows: n,
+            cols: n,
+            data: vec![zero(); n * n],
+        };
+        for k in 1..=n {
+            // M_k = A M_{k-1} + c_{k-1} I,  c_k = -tr(A M_k) / k
+            let mut next = self.mul(&m, guard)?;
+            let c = coefficients[k - 1].clone();
+            for i in 0..n {
+                let d = i * n + i;
+                next.data[d] = tidy(next.data[d].clone() + c.clone());
+            }
+            let product = self.mul(&next, guard)?;
+            let trace = (0..n).fold(zero(), |acc, i| acc + product.get(i, i).clone());
+            coefficients.push(tidy(-(trace / Number::Integer(BigInt::from(k)))));
+            m = next;
+        }
+        Ok(coefficients)
+    }
+
+    /// Eigenvalues in ascending order of real part, with multiplicity. Exact
+    /// where they are rational (or quadratic surds of perfect squares),
+    /// otherwise floats or complex numbers.
+    pub fn eigenvalues(&self, guard: &Guard) -> Result<Vec<Number>, EngineError> {
+        if !self.is_square() {
+            return Err(not_square("eigenvalues", self));
+        }
+        if self.rows > MAX_EIGEN_SIZE {
+            return Err(EngineError::DomainError(format!(
+                "eigenvalues are only computed for matrices up to {}×{}",
+                MAX_EIGEN_SIZE, MAX_EIGEN_SIZE
+            )));
+        }
+        let mut polynomial = self.characteristic_polynomial(guard)?;
+        let mut roots = Vec::new();
+
+        // Divide out rational roots exactly, so repeated ones stay exact
+        while polynomial.len() > 3 && polynomial.iter().all(is_exact) {
+            let Some(root) = find_rational_root(&polynomial) else {
+                break;
+            };
+            polynomial = deflate(&polynomial, &root);
+            roots.push(root);
+        }
+        match polynomial.len() {
+            2 => roots.push(tidy(-polynomial[1].clone())),
+            3 => roots.extend(quadratic_roots(&polynomial[1], &polynomial[2])),
+            _ => roots.extend(durand_kerner(&polynomial).into_iter().map(real_if_close)),
+        }
+        roots.sort_by(|a, b| {
+            let (a, b) = (a.to_complex(), b.to_complex());
+            a.re.total_cmp(&b.re).then(a.im.total_cmp(&b.im))
+        });
+        Ok(roots)
+    }
+}
+
+// Value of a polynomial (highest degree first) by Horner's rule
+fn evaluate(polynomial: &[Number], x: &Number) -> Number {
+    polynomial
+        .iter()
+        .fold(zero(), |acc, c| acc * x.clone() + c.clone())
+}
+
+// Divides by (λ - root), dropping the zero remainder
+fn deflate(polynomial: &[Number], root: &Number) -> Vec<Number> {
+    let mut quotient: Vec<Number> = Vec::with_capacity(polynomial.len() - 1);
+    let mut carry = zero();
+    for c in &polynomial[..polynomial.len() - 1] {
+        carry = tidy(carry * root.clone() + c.clone());
+        quotient.push(carry.clone());
+    }
+    quotient
+}
+
+// A rational root near one of the numerical roots, confirmed exactly
+fn find_rational_root(polynomial: &[Number]) -> Option<Number> {
+    for z in durand_kerner(polynomial) {
+        if z.im.abs() > 1e-6 * (1.0 + z.re.abs()) || !z.re.is_finite() {
+            continue;
+        }
+        for q in 1..=64i64 {
+            let p = (z.re * q as f64).round();
+            let Some(p) = (p.abs() < 1e15).then(|| p.to_i64()).flatten() else {
+                continue;
+            };
+            let candidate = from_ratio(BigRational::new(BigInt::from(p), BigInt::from(q)));
+            if is_exact_zero(&tidy(evaluate(polynomial, &candidate))) {
+                return Some(candidate);
+            }
+        }
+    }
+    None
+}
+
+// Roots of λ² + bλ + c
+fn quadratic_roots(b: &Number, c: &Number) -> Vec<Number> {
+    let two = Number::Integer(BigInt::from(2));
+    let discriminant = tidy(b.clone() * b.clone() - Number::Integer(BigInt::from(4)) * c.clone());
+    if let Some(root) = exact_root(&discriminant, 2) {
+        return vec![
+            tidy((-b.clone() - root.clone()) / two.clone()),
+            tidy((-b.clone() + root) / two),
+        ];
+    }
+    let (b, d) = (b.to_complex(), discriminant.to_complex());
+    let s = if d.im == 0.0 && d.re >= 0.0 {
+        Complex64::new(d.re.sqrt(), 0.0)
+    } else {
+        d.sqrt()
+    };
+    vec![real_if_close((-b - s) / 2.0), real_if_close((-b + s) / 2.0)]
+}
+
+fn real_if_close(z: Complex64) -> Number {
+    if z.im.abs() <= 1e-12 * (1.0 + z.re.abs()) {
+        Number::Float(z.re)
+    } else {
+        Number::Complex(z)
+    }
+}
+
+// All complex roots of a monic polynomial by the Durand-Kerner iteration,
+// polished with a few Newton steps
+fn durand_kerner(polynomial: &[Number]) -> Vec<Complex64> {
+    let coefficients: Vec<Complex64> = polynomial.iter().map(Number::to_complex).collect();
+    let degree = coefficients.len() - 1;
+    let value = |z: Complex64| {
+        coefficients
+            .iter()
+            .fold(Complex64::zero(), |acc, c| acc * z + c)
+    };
+    let derivative = |z: Complex64| {
+        coefficients[..degree]
+            .iter()
+            .enumerate()
+            .fold(Complex64::zero(), |acc, (i, c)| {
+                acc * z + c * (degree - i) as f64
+            })
+    };
+    let seed = Complex64::new(0.4, 0.9);
+    let mut roots: Vec<Complex64> = (0..degree).map(|k| seed.powu(k as u32)).collect();
+    for _ in 0..2000 {
+        let mut change: f64 = 0.0;
+        for k in 0..degree {
+            let denominator = (0..degree)
+                .filter(|&j| j != k)
+                .fold(Complex64::new(1.0, 0.0), |acc, j| {
+                    acc * (roots[k] - roots[j])
+                });
+            if denominator.norm() == 0.0 {
+                continue;
+            }
+            let step = value(roots[k]) / denominator;
+            roots[k] -= step;
+            change = change.max(step.norm() / (1.0 + roots[k].norm()));
+        }
+        if change < 1e-15 {
+            break;
+        }
+    }
+    for root in roots.iter_mut() {
+        for _ in 0..3 {
+            let d = derivative(*root);
+            if d.norm() == 0.0 {
+                break;
+            }
+            *root -= value(*root) / d;
+        }
+    }
+    roots
+}
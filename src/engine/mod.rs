@@ -1,16 +1,66 @@
+pub mod angle;
 pub mod ast;
+pub mod bigfloat;
+pub mod constants;
+pub mod currency;
 pub mod errors;
 pub mod functions;
+pub mod limits;
+pub mod matrix;
+pub mod native;
 pub mod parser;
+pub mod special_forms;
 pub mod tokens;
 pub mod types;
+pub mod units;
+pub mod word;
 
 use crate::engine::errors::EngineError;
 use crate::engine::types::Number;
 
 use crate::engine::ast::Context;
+use std::panic;
+use std::thread;
 
+// Native stack reserved per level of `EvalLimits::max_depth`, with room for
+// unoptimized builds, and the bounds of the stack `evaluate_on_thread` uses
+const STACK_PER_LEVEL: usize = 8 << 10;
+const MIN_STACK: usize = 8 << 20;
+const MAX_STACK: usize = 1 << 30;
+
+/// Parses and evaluates `expression` on the calling thread.
+///
+/// The default `EvalLimits::max_depth` fits the 2 MiB stack of a spawned
+/// thread. Hosts that raise it further should give the evaluation a larger
+/// stack, for instance through `evaluate_on_thread`.
 pub fn evaluate(expression: &str, context: &mut Context) -> Result<Number, EngineError> {
     let expr = parser::parse(expression)?;
+    context.begin_evaluation();
     expr.eval(context).map(|arc_num| (*arc_num).clone())
 }
+
+/// Like `evaluate`, but on a thread of its own whose stack fits
+/// `context.limits.max_depth`: 8 KiB of address space per level, between
+/// 8 MiB and 1 GiB, committed only as deep recursion touches it. The thread
+/// lives for this one call. If it cannot be spawned, the evaluation runs on
+/// the calling thread instead.
+pub fn evaluate_on_thread(expression: &str, context: &mut Context) -> Result<Number, EngineError> {
+    let stack = context
+        .limits
+        .max_depth
+        .saturating_mul(STACK_PER_LEVEL)
+        .clamp(MIN_STACK, MAX_STACK);
+    let outcome = thread::scope(|scope| {
+        thread::Builder::new()
+            .name("neocalc-eval".into())
+            .stack_size(stack)
+            .spawn_scoped(scope, || evaluate(expression, context))
+            .ok()
+            .map(|worker| worker.join())
+    });
+    match outcome {
+        Some(Ok(result)) => result,
+        Some(Err(panic)) => panic::resume_unwind(panic),
+        None => evaluate(expression, context),
+    }
+}
@@ -0,0 +1,126 @@
+//! Native functions registered by the host application at runtime.
+//!
+//! Builtins are collected at compile time and shared by every `Context`. An
+//! embedding application can also give a single context its own functions,
+//! backed by closures (to read a value from the app's data model, say):
+//!
+//! ```
+//! use neocalc_core::engine::native::NativeFunction;
+//! use neocalc_core::{Context, Number, evaluate};
+//!
+//! let mut context = Context::new();
+//! context.register_native(
+//!     NativeFunction::new("cell", |args, _ctx| Ok(args[0].clone() * Number::Integer(2.into())))
+//!         .params(&["index"]),
+//! );
+//! assert_eq!(evaluate("cell(21)", &mut context), Ok(Number::Integer(42.into())));
+//! ```
+//!
+//! Natives take precedence over builtins and special forms of the same name,
+//! which they match case-insensitively like the builtins do.
+//! User definitions cannot replace them unless the native was registered with
+//! `allow_shadowing`. They hold closures, so they are not serialized with the
+//! context and must be registered again after a session is loaded.
+
+use super::ast::Context;
+use super::errors::EngineError;
+use super::functions;
+use super::types::Number;
+use std::fmt;
+use std::sync::Arc;
+
+pub type NativeFn = dyn Fn(&[Number], &Context) -> Result<Number, EngineError> + Send + Sync;
+
+/// A host function with the same arity metadata as a builtin `FunctionDef`.
+#[derive(Clone)]
+pub struct NativeFunction {
+    name: String,
+    params: Vec<String>,
+    min_args: usize,
+    max_args: Option<usize>,
+    shadowable: bool,
+    func: Arc<NativeFn>,
+}
+
+impl NativeFunction {
+    /// A function taking no arguments; add them with `params`.
+    pub fn new<F>(name: impl Into<String>, func: F) -> Self
+    where
+        F: Fn(&[Number], &Context) -> Result<Number, EngineError> + Send + Sync + 'static,
+    {
+        NativeFunction {
+            name: name.into(),
+            params: Vec::new(),
+            min_args: 0,
+            max_args: Some(0),
+            shadowable: false,
+            func: Arc::new(func),
+        }
+    }
+
+    /// Sets the parameter names; every one of them is required.
+    pub fn params(mut self, params: &[&str]) -> Self {
+        self.params = params.iter().map(|p| p.to_string()).collect();
+        self.min_args = params.len();
+        self.max_args = Some(params.len());
+        self
+    }
+
+    /// Makes the last `count` parameters optional.
+    pub fn optional(mut self, count: usize) -> Self {
+        self.min_args = self.params.len().saturating_sub(count);
+        self
+    }
+
+    /// Lets the last parameter repeat, requiring at least `min` arguments.
+    pub fn variadic(mut self, min: usize) -> Self {
+        self.min_args = min;
+        self.max_args = None;
+        self
+    }
+
+    /// Lets user definitions such as `name(x) = ...` replace this function.
+    pub fn allow_shadowing(mut self) -> Self {
+        self.shadowable = true;
+        self
+    }
+
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn param_names(&self) -> &[String] {
+        &self.params
+    }
+
+    pub fn min_args(&self) -> usize {
+        self.min_args
+    }
+
+    pub fn max_args(&self) -> Option<usize> {
+        self.max_args
+    }
+
+    pub fn is_shadowable(&self) -> bool {
+        self.shadowable
+    }
+
+    pub fn signature(&self) -> String {
+        functions::signature(&self.name, &self.params, self.min_args, self.max_args)
+    }
+
+    /// Checks the argument count and calls the closure.
+    pub fn call(&self, args: &[Number], context: &Context) -> Result<Number, EngineError> {
+        functions::check_arity(&self.name, self.min_args, self.max_args, args.len())?;
+        (self.func)(args, context)
+    }
+}
+
+impl fmt::Debug for NativeFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NativeFunction")
+            .field("signature", &self.signature())
+            .field("shadowable", &self.shadowable)
+            .finish_non_exhaustive()
+    }
+}
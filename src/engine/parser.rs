@@ -1,35 +1,51 @@
-use super::types::Number;
+use super::angle::AngleUnit;
+use super::ast::{BinaryOp, Expr, UnaryOp};
+use super::bigfloat::{BigFloat, MAX_PRECISION};
+use super::constants;
+use super::errors::{EngineError, ParseError};
 use super::tokens::Token;
-use super::errors::EngineError;
-use super::ast::{Expr, BinaryOp, UnaryOp};
+use super::types::Number;
+use super::units;
 use logos::Logos;
-
-
+use num::complex::Complex64;
+use std::ops::Range;
 
 /// Parses the expression into an Abstract Syntax Tree (AST).
 /// Does NOT evaluate it.
 pub fn parse(expression: &str) -> Result<Expr, EngineError> {
     /* Initialize the parser with the lexer directly */
-    let mut parser = Parser::new(Token::lexer(expression).spanned());
+    let mut parser = Parser::new(expression);
     let result = parser.parse_bp(0)?;
 
     /* Ensure all tokens were consumed */
     if parser.current() != &Token::Eof {
-        return Err(EngineError::ParserError(format!("Unexpected token at end: {:?}", parser.current())));
+        return Err(parser.error_here(&["operator"]));
     }
 
     Ok(result)
 }
 
 struct Parser<'a> {
+    source: &'a str,
     lexer: logos::SpannedIter<'a, Token<'a>>,
     current: Token<'a>,
+    /* Byte range of `current` in the source */
+    span: Range<usize>,
+    /* Byte range of the most recently consumed token */
+    prev_span: Range<usize>,
 }
 
 impl<'a> Parser<'a> {
-    fn new(mut lexer: logos::SpannedIter<'a, Token<'a>>) -> Self {
-        let current = fetch_next_token(&mut lexer);
-        Parser { lexer, current }
+    fn new(source: &'a str) -> Self {
+        let mut lexer = Token::lexer(source).spanned();
+        let (current, span) = fetch_next_token(&mut lexer, source.len());
+        Parser {
+            source,
+            lexer,
+            current,
+            span,
+            prev_span: 0..0,
+        }
     }
 
     fn current(&self) -> &Token<'a> {
@@ -37,22 +53,66 @@ impl<'a> Parser<'a> {
     }
 
     fn advance(&mut self) {
-        self.current = fetch_next_token(&mut self.lexer);
+        self.advance_with_token();
     }
 
     fn advance_with_token(&mut self) -> Token<'a> {
-        let next = fetch_next_token(&mut self.lexer);
+        let (next, next_span) = fetch_next_token(&mut self.lexer, self.source.len());
+        self.prev_span = std::mem::replace(&mut self.span, next_span);
         std::mem::replace(&mut self.current, next)
     }
 
+    /* Build an error pointing at `span`, quoting the source text found there */
+    fn error_at(&self, span: Range<usize>, expected: &[&str]) -> EngineError {
+        let found = if span.start >= self.source.len() {
+            None
+        } else {
+            Some(self.source[span.clone()].to_string())
+        };
+        let expected = expected.iter().map(|s| s.to_string()).collect();
+        ParseError::new(span, expected, found).into()
+    }
+
+    /* Build an error pointing at the current (not yet consumed) token */
+    fn error_here(&self, expected: &[&str]) -> EngineError {
+        self.error_at(self.span.clone(), expected)
+    }
+
+    /* A float literal with more digits than an f64 holds keeps them as an exact
+    decimal; evaluation turns it back into a float unless a working precision is set */
+    fn decimal_literal(&self, f: f64) -> Number {
+        let text = self.source[self.prev_span.clone()].replace('_', "");
+        match BigFloat::parse(&text, MAX_PRECISION) {
+            Some(exact) if Some(&exact) != BigFloat::from_f64(f, MAX_PRECISION).as_ref() => {
+                Number::BigFloat(exact)
+            }
+            _ => Number::Float(f),
+        }
+    }
+
+    /* A number literal written directly before a lone `i` is imaginary: 3i, 2.5e3i.
+    Longer names after a number (2index) are left to implicit multiplication */
+    fn imaginary_suffix(&mut self, value: Number) -> Number {
+        if self.current() == &Token::Identifier("i") && self.span.start == self.prev_span.end {
+            self.advance();
+            Number::Complex(Complex64::new(0.0, value.to_f64().unwrap_or(f64::NAN)))
+        } else {
+            value
+        }
+    }
+
     /* Pratt parsing algorithm: Parse with a minimum binding power */
     fn parse_bp(&mut self, min_bp: u8) -> Result<Expr, EngineError> {
         let token = self.advance_with_token();
 
         /* Handle the prefix part (numbers, identifiers, parentheses, unary ops) */
         let mut lhs = match token {
-            Token::Float(f) => Expr::Literal(Number::Float(f)),
-            Token::Integer(i) => Expr::Literal(Number::Integer(i)),
+            Token::Float(f) => {
+                let value = self.decimal_literal(f);
+                Expr::Literal(self.imaginary_suffix(value))
+            }
+            Token::Integer(i) => Expr::Literal(self.imaginary_suffix(Number::Integer(i))),
+            Token::Str(s) => Expr::Literal(Number::Text(unescape(&s[1..s.len() - 1]))),
             Token::Identifier(s) => self.handle_identifier(s.to_string())?,
             Token::LParen => {
                 let val = self.parse_bp(0)?;
@@ -60,16 +120,17 @@ impl<'a> Parser<'a> {
                     self.advance();
                     val
                 } else {
-                    return Err(EngineError::ParserError("Expected ')'".to_string()));
+                    return Err(self.error_here(&["')'"]));
                 }
             }
+            Token::LBracket => self.parse_brackets()?,
             Token::Minus => {
-                let ((), r_bp) = prefix_binding_power(&Token::Minus)?;
+                let r_bp = prefix_binding_power(&Token::Minus)
+                    .ok_or_else(|| self.error_at(self.prev_span.clone(), &["expression"]))?;
                 let rhs = self.parse_bp(r_bp)?;
                 Expr::UnaryOp(UnaryOp::Neg, Box::new(rhs))
             }
-            Token::Eof => return Err(EngineError::ParserError("Unexpected EOF".to_string())),
-            t => return Err(EngineError::ParserError(format!("Unexpected token: {:?}", t))),
+            _ => return Err(self.error_at(self.prev_span.clone(), &["expression"])),
         };
 
         /* Handle infix and postfix operators while their binding power is high enough */
@@ -81,7 +142,7 @@ impl<'a> Parser<'a> {
 
             // Handle Postfix operators (Factorial)
             if let Token::Factorial = op {
-                let l_bp = 11; // Postfix binding power
+                let l_bp = 15; // Postfix binding power
                 if l_bp < min_bp {
                     break;
                 }
@@ -90,13 +151,76 @@ impl<'a> Parser<'a> {
                 continue;
             }
 
+            // Indexing binds like factorial: xs[2], f(x)[1]
+            if let Token::LBracket = op {
+                if 15 < min_bp {
+                    break;
+                }
+                self.advance();
+                let index = self.parse_bp(0)?;
+                if let Token::RBracket = self.current() {
+                    self.advance();
+                } else {
+                    return Err(self.error_here(&["']'"]));
+                }
+                lhs = Expr::Index(Box::new(lhs), Box::new(index));
+                continue;
+            }
+
+            // Angle units bind like factorial: 30°, or deg/rad/grad written
+            // directly after a number literal (30deg)
+            let angle_unit = match op {
+                Token::Degree => Some(AngleUnit::Degrees),
+                Token::Identifier(name)
+                    if matches!(lhs, Expr::Literal(_)) && self.span.start == self.prev_span.end =>
+                {
+                    AngleUnit::from_suffix(name)
+                }
+                _ => None,
+            };
+            if let Some(unit) = angle_unit {
+                if 15 < min_bp {
+                    break;
+                }
+                self.advance();
+                lhs = Expr::UnaryOp(UnaryOp::Angle(unit), Box::new(lhs));
+                continue;
+            }
+
+            // So does a unit after a number literal, making `5 km / 20 min`
+            // a ratio of two quantities. A power belongs to the unit
+            // (`2 m^2`), and a name followed by '(' is still a call
+            if let Token::Identifier(name) = op
+                && matches!(lhs, Expr::Literal(_))
+                && units::is_unit(name)
+                && !constants::is_constant(name)
+                && !self.source[self.span.end..].trim_start().starts_with('(')
+            {
+                if 15 < min_bp {
+                    break;
+                }
+                let symbol = name.to_string();
+                self.advance();
+                lhs = if let Token::Power = self.current() {
+                    self.advance();
+                    let exponent = self.parse_bp(9)?;
+                    let one = Expr::Literal(Number::Integer(1.into()));
+                    let unit = Expr::UnaryOp(UnaryOp::Unit(symbol), Box::new(one));
+                    let unit = Expr::BinaryOp(BinaryOp::Pow, Box::new(unit), Box::new(exponent));
+                    Expr::BinaryOp(BinaryOp::Mul, Box::new(lhs), Box::new(unit))
+                } else {
+                    Expr::UnaryOp(UnaryOp::Unit(symbol), Box::new(lhs))
+                };
+                continue;
+            }
+
             // Check for explicit Infix or Implicit Multiplication
             let (is_explicit, l_bp, r_bp) = match infix_binding_power(op) {
                 Some((l, r)) => (true, l, r),
                 None => {
                     // Check for Implicit Multiplication:
                     if matches!(op, Token::LParen | Token::Identifier(_)) {
-                        (false, 3, 4)
+                        (false, 7, 8)
                     } else {
                         break;
                     }
@@ -117,13 +241,30 @@ impl<'a> Parser<'a> {
                     Token::Divide => BinaryOp::Div,
                     Token::Power => BinaryOp::Pow,
                     Token::Percent => BinaryOp::Mod,
-                    _ => return Err(EngineError::ParserError(format!("Unknown infix operator: {:?}", token))),
+                    Token::Less => BinaryOp::Lt,
+                    Token::Greater => BinaryOp::Gt,
+                    Token::LessEqual => BinaryOp::Le,
+                    Token::GreaterEqual => BinaryOp::Ge,
+                    Token::EqualEqual => BinaryOp::Eq,
+                    Token::NotEqual => BinaryOp::Ne,
+                    Token::Ampersand => BinaryOp::Concat,
+                    Token::Identifier(_) => BinaryOp::Convert,
+                    _ => return Err(self.error_at(self.prev_span.clone(), &["operator"])),
                 }
             } else {
                 BinaryOp::Mul
             };
 
             let rhs = self.parse_bp(r_bp)?;
+            // Unit names are read as units only where a unit is expected: after a
+            // number, as in `(1/4) /s`, `6 kg m^2/s^2` or `3 (km/h)`, and as a
+            // conversion target
+            let rhs = match bin_op {
+                BinaryOp::Convert => unit_target(rhs),
+                BinaryOp::Div if is_numeric(&lhs) => unit_target(rhs),
+                BinaryOp::Mul if !is_explicit && is_numeric(&lhs) => unit_target(rhs),
+                _ => rhs,
+            };
             lhs = Expr::BinaryOp(bin_op, Box::new(lhs), Box::new(rhs));
         }
 
@@ -135,38 +276,41 @@ impl<'a> Parser<'a> {
             Token::LParen => {
                 /* Function call OR Function Definition: name(arg1, ...) = body */
                 self.advance(); /* eat '(' */
-                
+
                 // We need to parse arguments. Accessing args logic.
                 // If it's a definition, args must be identifiers.
                 // But parse_arguments parses Exprs.
                 // We can parse generic Exprs. If we hit '=', check if all args were Variables.
-                let args = self.parse_arguments()?;
-                
+                let args = self.parse_sequence(Token::RParen, "')'")?;
+
                 if let Token::Equals = self.current() {
                     // Function Definition
                     self.advance(); // eat '='
                     let body = self.parse_bp(0)?; // Parse body
-                    
+
                     // Validate args are variables
                     let mut params = Vec::new();
-                    for arg in args {
+                    for (arg, span) in args {
                         if let Expr::Variable(param_name) = arg {
                             params.push(param_name);
                         } else {
-                            return Err(EngineError::ParserError("Function parameters must be identifiers".to_string()));
+                            return Err(self.error_at(span, &["parameter name"]));
                         }
                     }
                     Ok(Expr::FunctionDef(name, params, Box::new(body)))
                 } else {
                     // Function Call
-                    Ok(Expr::FunctionCall(name, args))
+                    Ok(Expr::FunctionCall(
+                        name,
+                        args.into_iter().map(|(arg, _)| arg).collect(),
+                    ))
                 }
             }
             Token::Equals => {
-                 // Assignment: name = expr
-                 self.advance(); // eat '='
-                 let expr = self.parse_bp(0)?;
-                 Ok(Expr::Assignment(name, Box::new(expr)))
+                // Assignment: name = expr
+                self.advance(); // eat '='
+                let expr = self.parse_bp(0)?;
+                Ok(Expr::Assignment(name, Box::new(expr)))
             }
             _ => {
                 /* Variable access */
@@ -175,52 +319,180 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn parse_arguments(&mut self) -> Result<Vec<Expr>, EngineError> {
+    /// Parses the inside of `[...]` after the opening bracket: a list
+    /// `[1, 2, 3]`, or a matrix when rows are separated by semicolons
+    /// (`[1, 2; 3, 4]`). A trailing semicolon makes a one-row matrix.
+    fn parse_brackets(&mut self) -> Result<Expr, EngineError> {
+        let mut rows = vec![Vec::new()];
+        let mut semicolons = false;
+        if let Token::RBracket = self.current() {
+            self.advance();
+            return Ok(Expr::List(Vec::new()));
+        }
+
+        loop {
+            let item = self.parse_bp(0)?;
+            rows.last_mut().unwrap().push(item);
+
+            match self.current() {
+                Token::Comma => {
+                    self.advance();
+                }
+                Token::Semicolon => {
+                    self.advance();
+                    semicolons = true;
+                    if let Token::RBracket = self.current() {
+                        self.advance();
+                        break;
+                    }
+                    rows.push(Vec::new());
+                }
+                Token::RBracket => {
+                    self.advance();
+                    break;
+                }
+                _ => return Err(self.error_here(&["','", "';'", "']'"])),
+            }
+        }
+        if semicolons {
+            Ok(Expr::Matrix(rows))
+        } else {
+            Ok(Expr::List(rows.pop().unwrap_or_default()))
+        }
+    }
+
+    /* Parses comma separated expressions up to `close` (function arguments or
+    list items), keeping each one's source span */
+    fn parse_sequence(
+        &mut self,
+        close: Token<'a>,
+        close_name: &str,
+    ) -> Result<Vec<(Expr, Range<usize>)>, EngineError> {
         let mut args = Vec::new();
-        if let Token::RParen = self.current() {
+        if self.current() == &close {
             self.advance();
             return Ok(args);
         }
 
         loop {
-            args.push(self.parse_bp(0)?);
+            let start = self.span.start;
+            let arg = self.parse_bp(0)?;
+            args.push((arg, start..self.prev_span.end));
 
             match self.current() {
                 Token::Comma => {
                     self.advance();
                 }
-                Token::RParen => {
+                token if token == &close => {
                     self.advance();
                     break;
                 }
-                _ => return Err(EngineError::ParserError("Expected ',' or ')' in argument list".to_string())),
+                _ => return Err(self.error_here(&["','", close_name])),
             }
         }
         Ok(args)
     }
 }
 
-fn prefix_binding_power(op: &Token) -> Result<((), u8), EngineError> {
+/* Resolve backslash escapes in a string literal: \" \\ \n \t; any other
+escaped character stands for itself */
+fn unescape(body: &str) -> String {
+    let mut text = String::with_capacity(body.len());
+    let mut chars = body.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => text.push('\n'),
+            Some('t') => text.push('\t'),
+            Some(other) => text.push(other),
+            None => {}
+        }
+    }
+    text
+}
+
+/* Whether an expression is a number written out, possibly with units: 5, (1/4), -2 m */
+fn is_numeric(expr: &Expr) -> bool {
+    match expr {
+        Expr::Literal(_) => true,
+        Expr::UnaryOp(_, operand) => is_numeric(operand),
+        Expr::BinaryOp(op, lhs, rhs) => {
+            let arithmetic = matches!(
+                op,
+                BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Pow
+            );
+            arithmetic && is_numeric(lhs) && is_numeric(rhs)
+        }
+        _ => false,
+    }
+}
+
+/* A unit name, or a power of one, as one of that unit; anything else is unchanged */
+fn unit_operand(expr: Expr) -> Expr {
+    match expr {
+        Expr::Variable(name) if units::is_unit(&name) && !constants::is_constant(&name) => {
+            let one = Expr::Literal(Number::Integer(1.into()));
+            Expr::UnaryOp(UnaryOp::Unit(name), Box::new(one))
+        }
+        Expr::BinaryOp(BinaryOp::Pow, base, exponent) => {
+            Expr::BinaryOp(BinaryOp::Pow, Box::new(unit_operand(*base)), exponent)
+        }
+        other => other,
+    }
+}
+
+/* The target of `to` or `in`, where every factor may be a unit: km/h, kg m^2/s^2, or a
+parenthesized group of units after a number: 3 (km/h) */
+fn unit_target(expr: Expr) -> Expr {
+    match expr {
+        Expr::BinaryOp(op @ (BinaryOp::Mul | BinaryOp::Div), lhs, rhs) => {
+            Expr::BinaryOp(op, Box::new(unit_target(*lhs)), Box::new(unit_target(*rhs)))
+        }
+        other => unit_operand(other),
+    }
+}
+
+fn prefix_binding_power(op: &Token) -> Option<u8> {
     match op {
-        Token::Minus => Ok(((), 9)), // Unary minus
-        _ => Err(EngineError::ParserError(format!("Bad prefix operator: {:?}", op))),
+        Token::Minus => Some(13), // Unary minus
+        _ => None,
     }
 }
 
 fn infix_binding_power(op: &Token) -> Option<(u8, u8)> {
     match op {
-        Token::Plus | Token::Minus => Some((1, 2)),
-        Token::Multiply | Token::Divide | Token::Percent => Some((3, 4)),
-        Token::Power => Some((6, 5)), // Right associative: 2^3^4 = 2^(3^4)
+        // Comparisons bind loosest so that `x + 1 > y * 2` compares the two sums
+        Token::Less
+        | Token::Greater
+        | Token::LessEqual
+        | Token::GreaterEqual
+        | Token::EqualEqual
+        | Token::NotEqual => Some((1, 2)),
+        // So do unit conversions: 5 km / 20 min to km/h
+        // `in` is also the inch: right after a number (12 in) and as a
+        // target (1 ft to in) it is read as the unit instead
+        Token::Identifier("to" | "in") => Some((1, 2)),
+        // Concatenation sits between, as in spreadsheets: "n = " & n + 1
+        Token::Ampersand => Some((3, 4)),
+        Token::Plus | Token::Minus => Some((5, 6)),
+        Token::Multiply | Token::Divide | Token::Percent => Some((7, 8)),
+        Token::Power => Some((10, 9)), // Right associative: 2^3^4 = 2^(3^4)
         _ => None,
     }
 }
 
-// Helper function to fetch the next token from the lexer
-fn fetch_next_token<'a>(lexer: &mut logos::SpannedIter<'a, Token<'a>>) -> Token<'a> {
+// Helper function to fetch the next token and its span from the lexer.
+// End of input is reported as an empty span at `source_len`.
+fn fetch_next_token<'a>(
+    lexer: &mut logos::SpannedIter<'a, Token<'a>>,
+    source_len: usize,
+) -> (Token<'a>, Range<usize>) {
     match lexer.next() {
-        Some((Ok(token), _)) => token,
-        Some((Err(_), _)) => Token::Error, // Simple error token
-        None => Token::Eof,
+        Some((Ok(token), span)) => (token, span),
+        Some((Err(_), span)) => (Token::Error, span), // Simple error token
+        None => (Token::Eof, source_len..source_len),
     }
 }
@@ -0,0 +1,308 @@
+//! Special forms: built-ins that receive their arguments unevaluated.
+//!
+//! Regular functions see fully evaluated `Number`s, which is wrong for
+//! conditionals: `if(x, 1/x, 0)` must not divide by zero and a recursive
+//! `f(n) = if(n, n*f(n-1), 1)` must stop at the base case. The forms here are
+//! intercepted by `Expr::eval` before argument evaluation and only evaluate the
+//! branches they need.
+//!
+//! The calculus forms `deriv`, `integrate`, `integrate.err`, `sum` and `prod`
+//! are here for the same reason: `sum(k^2, k, 1, 10)` evaluates `k^2` once
+//! per `k`, in a scope where `k` is bound, instead of once up front. Calls of
+//! `sum` and `prod` that do not have that shape, such as `sum(1, 2, 3)` or
+//! `sum(xs)`, are plain aggregates over their evaluated arguments.
+
+use super::ast::{BinaryOp, Context, Expr, apply_binary};
+use super::constants;
+use super::errors::EngineError;
+use super::functions::calculus::{derivative, integral};
+use super::functions::logic::{any_truthy, is_truthy};
+use super::functions::{self, FunctionDef};
+use super::types::Number;
+use num::FromPrimitive;
+use num_bigint::BigInt;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpecialForm {
+    If,
+    And,
+    Or,
+    IfError,
+    Deriv,
+    Integrate,
+    IntegrateError,
+    Sum,
+    Prod,
+}
+
+impl SpecialForm {
+    /// Matches names case-insensitively, like the function registry.
+    pub fn lookup(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "if" => Some(SpecialForm::If),
+            "and" => Some(SpecialForm::And),
+            "or" => Some(SpecialForm::Or),
+            "iferror" => Some(SpecialForm::IfError),
+            "deriv" => Some(SpecialForm::Deriv),
+            "integrate" => Some(SpecialForm::Integrate),
+            "integrate.err" => Some(SpecialForm::IntegrateError),
+            "sum" => Some(SpecialForm::Sum),
+            "prod" => Some(SpecialForm::Prod),
+            _ => None,
+        }
+    }
+
+    // Arguments of the function name form of a calculus form: the function
+    // followed by its point or bounds
+    fn calculus_args(self) -> usize {
+        match self {
+            SpecialForm::Deriv => 2,
+            _ => 3,
+        }
+    }
+
+    /// The registry entry describing this form, used for its arity and help.
+    pub fn definition(self) -> &'static FunctionDef {
+        let name = match self {
+            SpecialForm::If => "if",
+            SpecialForm::And => "and",
+            SpecialForm::Or => "or",
+            SpecialForm::IfError => "iferror",
+            SpecialForm::Deriv => "deriv",
+            SpecialForm::Integrate => "integrate",
+            SpecialForm::IntegrateError => "integrate.err",
+            SpecialForm::Sum => "sum",
+            SpecialForm::Prod => "prod",
+        };
+        functions::lookup(name).expect("special forms are registered as builtins")
+    }
+
+    pub fn eval(self, args: &[Expr], context: &mut Context) -> Result<Arc<Number>, EngineError> {
+        self.definition().check_arity(args.len())?;
+        // Each kind of form has its own function, which keeps the frame of
+        // this one small as user recursion goes through `if` at every level
+        match self {
+            SpecialForm::If => {
+                let condition = args[0].eval(context)?;
+                let branch = if is_truthy(&condition) { 1 } else { 2 };
+                args[branch].eval(context)
+            }
+            SpecialForm::And | SpecialForm::Or | SpecialForm::IfError => {
+                self.eval_logical(args, context)
+            }
+            SpecialForm::Deriv | SpecialForm::Integrate | SpecialForm::IntegrateError => {
+                self.eval_calculus(args, context)
+            }
+            SpecialForm::Sum | SpecialForm::Prod => self.eval_series(args, context),
+        }
+    }
+
+    fn eval_logical(
+        self,
+        args: &[Expr],
+        context: &mut Context,
+    ) -> Result<Arc<Number>, EngineError> {
+        match self {
+            SpecialForm::And => {
+                // Stop at the first false argument
+                for arg in args {
+                    if !is_truthy(&*arg.eval(context)?) {
+                        return Ok(Arc::new(Number::from(false)));
+                    }
+                }
+                Ok(Arc::new(Number::from(true)))
+            }
+            SpecialForm::Or => {
+                // Stop at the first true argument; a list is true if any element is
+                for arg in args {
+                    if any_truthy(&*arg.eval(context)?) {
+                        return Ok(Arc::new(Number::from(true)));
+                    }
+                }
+                Ok(Arc::new(Number::from(false)))
+            }
+            SpecialForm::IfError => {
+                match args[0].eval(context) {
+                    Ok(value) if !is_failed(&value) => Ok(value),
+                    Ok(_) => args[1].eval(context),
+                    // Aborts are not formula errors and must keep propagating
+                    Err(e @ (EngineError::Cancelled | EngineError::LimitExceeded(_))) => Err(e),
+                    Err(_) => args[1].eval(context),
+                }
+            }
+            _ => unreachable!("eval_logical needs a logical form"),
+        }
+    }
+
+    fn eval_calculus(
+        self,
+        args: &[Expr],
+        context: &mut Context,
+    ) -> Result<Arc<Number>, EngineError> {
+        let name = self.definition().name;
+        let (body, rest) = Body::split(self, args, context)?;
+        if self == SpecialForm::Deriv {
+            let x = real_arg(name, &rest[0], context)?;
+            let value = derivative(&mut |x| body.real_at(x, context), x)?;
+            return Ok(Arc::new(Number::Float(value)));
+        }
+        let a = real_arg(name, &rest[0], context)?;
+        let b = real_arg(name, &rest[1], context)?;
+        let estimate = integral(&mut |x| body.real_at(x, context), a, b)?;
+        Ok(Arc::new(Number::Float(if self == SpecialForm::Integrate {
+            estimate.value
+        } else {
+            estimate.error
+        })))
+    }
+
+    fn eval_series(self, args: &[Expr], context: &mut Context) -> Result<Arc<Number>, EngineError> {
+        if !Body::is_series(args, context) {
+            let mut values = Vec::with_capacity(args.len());
+            for arg in args {
+                values.push((*arg.eval(context)?).clone());
+            }
+            return functions::apply(self.definition().name, values, context).map(Arc::new);
+        }
+        let (name, op, mut total) = if self == SpecialForm::Sum {
+            ("sum", BinaryOp::Add, Number::Integer(BigInt::from(0)))
+        } else {
+            ("prod", BinaryOp::Mul, Number::Integer(BigInt::from(1)))
+        };
+        let (body, rest) = Body::split(self, args, context)?;
+        let mut k = integer_arg(name, &rest[0], context)?;
+        let to = integer_arg(name, &rest[1], context)?;
+        // An empty range gives 0 or 1
+        while k <= to {
+            context.guard().check_interrupt()?;
+            let term = body.at(Number::Integer(k.clone()), context)?;
+            total = apply_binary(&op, total, (*term).clone(), context)?;
+            k += 1;
+        }
+        Ok(context.fit_word(Arc::new(total), false))
+    }
+}
+
+// Division by zero and other invalid real operations give an infinity or
+// NaN rather than an error; IFERROR treats them as failures all the same
+fn is_failed(value: &Number) -> bool {
+    match value {
+        Number::Float(f) => !f.is_finite(),
+        Number::Complex(c) => !c.is_finite(),
+        _ => false,
+    }
+}
+
+// What a calculus form evaluates: a function called by name, or an
+// expression in a variable that is bound in a scope of its own
+enum Body<'a> {
+    Function(&'a str),
+    Expression(&'a Expr, &'a str),
+}
+
+impl<'a> Body<'a> {
+    // Whether a call of `sum` or `prod` is a series, `(expr, var, from, to)`
+    // or `(f, from, to)` with a callable `f`, rather than a plain aggregate.
+    // The variable must be a name the expression uses, or one that means
+    // nothing yet, so `sum(x, y, 3, 4)` with `y` defined adds four values.
+    fn is_series(args: &[Expr], context: &Context) -> bool {
+        match args {
+            [body, Expr::Variable(var), _, _] => {
+                !context.is_callable(var)
+                    && (body.mentions(var)
+                        || (context.get_var(var).is_none() && !constants::is_constant(var)))
+            }
+            [Expr::Variable(name), _, _] => context.is_callable(name),
+            _ => false,
+        }
+    }
+
+    // Splits the arguments into the body and the rest. The form takes one
+    // more argument than its function name form when the body is an
+    // expression, which is then followed by its variable.
+    fn split(
+        form: SpecialForm,
+        args: &'a [Expr],
+        context: &Context,
+    ) -> Result<(Self, &'a [Expr]), EngineError> {
+        let def = form.definition();
+        let params = form.calculus_args();
+        let body = if args.len() > params {
+            match &args[1] {
+                Expr::Variable(var) => Body::Expression(&args[0], var),
+                _ => {
+                    return Err(EngineError::DomainError(format!(
+                        "{} needs a variable name after the expression",
+                        def.name
+                    )));
+                }
+            }
+        } else {
+            match &args[0] {
+                Expr::Variable(name) if context.is_callable(name) => Body::Function(name),
+                _ => {
+                    return Err(EngineError::DomainError(format!(
+                        "{} needs a function name, or an expression followed by its variable",
+                        def.name
+                    )));
+                }
+            }
+        };
+        let rest = &args[args.len() - params + 1..];
+        Ok((body, rest))
+    }
+
+    fn at(&self, value: Number, context: &mut Context) -> Result<Arc<Number>, EngineError> {
+        match self {
+            Body::Function(name) => context.call(name, vec![Arc::new(value)]),
+            Body::Expression(expr, var) => {
+                // Bound like a function parameter, so it may shadow a
+                // constant such as `i` or `e`
+                context.push_scope();
+                context.define_var(var.to_string(), Arc::new(value));
+                let result = expr.eval(context);
+                context.pop_scope();
+                result
+            }
+        }
+    }
+
+    // The value at `x` for the numeric methods, which need finite reals
+    fn real_at(&self, x: f64, context: &mut Context) -> Result<f64, EngineError> {
+        let value = self.at(Number::Float(x), context)?;
+        match value.to_f64() {
+            Some(y) if y.is_finite() => Ok(y),
+            Some(_) => Err(EngineError::DomainError(format!(
+                "the function is not finite at {:?}",
+                x
+            ))),
+            None => Err(EngineError::TypeMismatch(
+                "real number".to_string(),
+                value.type_name().to_string(),
+            )),
+        }
+    }
+}
+
+// A real argument of a calculus form: a point or a bound, which may be infinite
+fn real_arg(name: &str, arg: &Expr, context: &mut Context) -> Result<f64, EngineError> {
+    let value = arg.eval(context)?;
+    match value.to_f64() {
+        Some(x) if !x.is_nan() => Ok(x),
+        _ => Err(EngineError::DomainError(format!(
+            "{} needs real arguments",
+            name
+        ))),
+    }
+}
+
+// A bound of `sum` or `prod`, which must be an integer
+fn integer_arg(name: &str, arg: &Expr, context: &mut Context) -> Result<BigInt, EngineError> {
+    let bound = match &*arg.eval(context)? {
+        Number::Integer(i) => Some(i.clone()),
+        Number::Float(f) if f.fract() == 0.0 => BigInt::from_f64(*f),
+        _ => None,
+    };
+    bound.ok_or_else(|| EngineError::DomainError(format!("{} needs integer bounds", name)))
+}
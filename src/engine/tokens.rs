@@ -23,28 +23,77 @@ pub enum Token<'a> {
     LParen,
     #[token(")")]
     RParen,
+    #[token("[")]
+    LBracket,
+    #[token("]")]
+    RBracket,
     #[token(",")]
     Comma,
+    #[token(";")]
+    Semicolon,
     #[token("=")]
     Equals,
+    #[token("==")]
+    EqualEqual,
+    #[token("!=")]
+    NotEqual,
+    #[token("<")]
+    Less,
+    #[token(">")]
+    Greater,
+    #[token("<=")]
+    LessEqual,
+    #[token(">=")]
+    GreaterEqual,
+    #[token("°")]
+    Degree,
+    #[token("&")]
+    Ampersand, // Text concatenation
+
+    /* Match string literals in double quotes, with backslash escapes */
+    #[regex(r#""([^"\\]|\\.)*""#, |lex| lex.slice())]
+    Str(&'a str),
 
     /* Match Floats: explicit dot or scientific notation */
     /* Needs to be checked BEFORE Integer to avoid greedy matching issues for things like 1.0 */
     /* Regex for float: digits dot digits (opt) exponent (opt) OR digits exponent */
-    #[regex(r"[0-9]+\.[0-9]*([eE][+-]?[0-9]+)?", |lex| lex.slice().parse::<f64>().ok())]
-    #[regex(r"[0-9]+[eE][+-]?[0-9]+", |lex| lex.slice().parse::<f64>().ok())]
+    /* Single underscores may separate digits: 1_000.000_1 */
+    #[regex(r"[0-9](_?[0-9])*\.([0-9](_?[0-9])*)?([eE][+-]?[0-9]+)?", |lex| lex.slice().replace('_', "").parse::<f64>().ok())]
+    #[regex(r"[0-9](_?[0-9])*[eE][+-]?[0-9]+", |lex| lex.slice().replace('_', "").parse::<f64>().ok())]
     Float(f64),
 
-    /* Match Integers: digits only */
-    #[regex(r"[0-9]+", |lex| lex.slice().parse::<BigInt>().ok())]
-    #[regex(r"0x[0-9a-fA-F]+", |lex| BigInt::parse_bytes(&lex.slice()[2..].as_bytes(), 16))]
-    #[regex(r"0b[01]+", |lex| BigInt::parse_bytes(&lex.slice()[2..].as_bytes(), 2))]
+    /* Match Integers: decimal, 0x hex, 0b binary, 0o octal, all allowing '_' between digits */
+    /* A leading, trailing or doubled '_' is left over and fails to lex: 1_, 1__0, 0xFF_ */
+    /* Arbitrary radix 2..=36 is written radix#digits, e.g. 36#ZZ or 3#1201 */
+    /* Binary and octal take any decimal digits so that 0o8 is an error, not 0 * o8 */
+    #[regex(r"[0-9](_?[0-9])*", |lex| parse_digits(lex.slice(), 10))]
+    #[regex(r"0x[0-9a-fA-F](_?[0-9a-fA-F])*", |lex| parse_digits(&lex.slice()[2..], 16))]
+    #[regex(r"0b[0-9](_?[0-9])*", |lex| parse_digits(&lex.slice()[2..], 2))]
+    #[regex(r"0o[0-9](_?[0-9])*", |lex| parse_digits(&lex.slice()[2..], 8))]
+    #[regex(r"[0-9]+#[0-9a-zA-Z](_?[0-9a-zA-Z])*", |lex| parse_radix_literal(lex.slice()))]
     Integer(BigInt),
 
     /* Match variable names or function identifiers */
-    #[regex("[a-zA-Z][a-zA-Z0-9_]*", |lex| lex.slice())]
+    /* A number directly followed by `i` (3i, 2.5i) is read as imaginary by the parser */
+    /* Dotted segments allow spreadsheet-style names such as NORM.DIST or T.DIST.2T */
+    #[regex(r"[a-zA-Z][a-zA-Z0-9_]*(\.[a-zA-Z0-9_]+)*", |lex| lex.slice())]
+    /* Temperature units, which start with a degree sign: 20°C */
+    #[regex("°[CF]", |lex| lex.slice())]
     Identifier(&'a str),
 
     Eof,
     Error,
 }
+
+/* Parse digits in the given radix, ignoring '_' separators */
+fn parse_digits(digits: &str, radix: u32) -> Option<BigInt> {
+    let digits: Vec<u8> = digits.bytes().filter(|&b| b != b'_').collect();
+    BigInt::parse_bytes(&digits, radix)
+}
+
+/* Parse `radix#digits`; invalid radixes or out-of-range digits become lexer errors */
+fn parse_radix_literal(slice: &str) -> Option<BigInt> {
+    let (radix, digits) = slice.split_once('#')?;
+    let radix = radix.parse::<u32>().ok().filter(|r| (2..=36).contains(r))?;
+    parse_digits(digits, radix)
+}
@@ -1,10 +1,17 @@
+use crate::engine::ast::Context;
+use crate::engine::bigfloat::BigFloat;
 use crate::engine::errors::EngineError;
+use crate::engine::functions::special;
+use crate::engine::limits::Guard;
+use crate::engine::matrix::Matrix;
+use crate::engine::units::{self, Quantity};
 use num::complex::Complex64;
 use num::traits::Pow;
-use num::{One, ToPrimitive, Zero};
+use num::{One, Signed, ToPrimitive, Zero};
 use num_bigint::BigInt;
 use num_rational::BigRational;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -12,8 +19,26 @@ pub enum Number {
     Integer(BigInt),
     Rational(BigRational),
     Float(f64),
+    /// Decimal float carrying its own precision, produced when
+    /// `Context::precision` is set.
+    BigFloat(BigFloat),
     #[serde(with = "complex_serde")]
     Complex(Complex64),
+    /// `true` or `false`, produced by comparisons and the logic functions.
+    /// It is not a number: arithmetic and numeric functions refuse it, and
+    /// it only equals and orders against other booleans.
+    Boolean(bool),
+    /// A list of values, written `[1, 2, 3]`. Arithmetic on lists is
+    /// element-wise (see `elementwise`) and scalar functions map over them.
+    List(Vec<Number>),
+    /// A matrix, written `[1, 2; 3, 4]`. `+` and `-` are element-wise; `*`
+    /// and `^` are the matrix product and power (see `ast::apply_binary`).
+    Matrix(Matrix),
+    /// Text, written `"abc"`. Joined with `&`; arithmetic does not accept it.
+    Text(String),
+    /// A number with physical units, written `5 km`. Arithmetic checks and
+    /// converts the units (see `engine::units`).
+    Quantity(Quantity),
 }
 
 /// Custom serde for Complex64 since it doesn't implement Serialize/Deserialize
@@ -49,7 +74,13 @@ impl Number {
             Number::Integer(i) => Complex64::new(i.to_f64().unwrap_or(f64::INFINITY), 0.0),
             Number::Rational(r) => Complex64::new(r.to_f64().unwrap_or(f64::NAN), 0.0),
             Number::Float(f) => Complex64::new(*f, 0.0),
+            Number::BigFloat(b) => Complex64::new(b.to_f64(), 0.0),
             Number::Complex(c) => *c,
+            Number::List(_)
+            | Number::Matrix(_)
+            | Number::Text(_)
+            | Number::Boolean(_)
+            | Number::Quantity(_) => Complex64::new(f64::NAN, f64::NAN),
         }
     }
 
@@ -58,6 +89,7 @@ impl Number {
             Number::Integer(i) => i.to_f64(),
             Number::Rational(r) => r.to_f64(),
             Number::Float(f) => Some(*f),
+            Number::BigFloat(b) => Some(b.to_f64()),
             Number::Complex(c) => {
                 if c.im == 0.0 {
                     Some(c.re)
@@ -65,27 +97,176 @@ impl Number {
                     None
                 }
             }
+            Number::List(_)
+            | Number::Matrix(_)
+            | Number::Text(_)
+            | Number::Boolean(_)
+            | Number::Quantity(_) => None,
+        }
+    }
+
+    /// Real values as a `BigFloat` with the given precision. Floats go through
+    /// their shortest decimal form; infinities, NaN and complex give `None`.
+    pub fn to_bigfloat(&self, precision: u32) -> Option<BigFloat> {
+        match self {
+            Number::Integer(i) => Some(BigFloat::from_integer(i.clone(), precision)),
+            Number::Rational(r) => Some(BigFloat::from_rational(r, precision)),
+            Number::Float(f) => BigFloat::from_f64(*f, precision),
+            Number::BigFloat(b) => Some(b.clone()),
+            Number::Complex(_)
+            | Number::List(_)
+            | Number::Matrix(_)
+            | Number::Text(_)
+            | Number::Boolean(_)
+            | Number::Quantity(_) => None,
+        }
+    }
+
+    /// A short name of the value's type for error messages.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Number::Complex(_) => "complex number",
+            Number::List(_) => "list",
+            Number::Matrix(_) => "matrix",
+            Number::Text(_) => "text",
+            Number::Boolean(_) => "boolean",
+            Number::Quantity(_) => "quantity",
+            _ => "real number",
         }
     }
-    // Number struct definition
 }
 
 impl PartialOrd for Number {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if let (Number::Text(l), Number::Text(r)) = (self, other) {
+            return Some(l.cmp(r));
+        }
         match promote(self.clone(), other.clone()) {
             (Number::Integer(l), Number::Integer(r)) => Some(l.cmp(&r)),
             (Number::Rational(l), Number::Rational(r)) => Some(l.cmp(&r)),
             (Number::Float(l), Number::Float(r)) => l.partial_cmp(&r),
+            (Number::BigFloat(l), Number::BigFloat(r)) => l.partial_cmp(&r),
             (Number::Complex(_), _) | (_, Number::Complex(_)) => None,
             _ => None, // Should be unreachable given promote
         }
     }
 }
 
+impl From<bool> for Number {
+    fn from(b: bool) -> Self {
+        Number::Boolean(b)
+    }
+}
+
+/// Orders two real numbers exactly, promoting them as arithmetic would
+/// (so `1/3 < 0.34` compares as floats but `1/3 < 1/2` stays rational).
+/// Two texts are ordered by their characters, two booleans with false
+/// first, and quantities after converting them to the same units.
+pub fn compare(lhs: &Number, rhs: &Number) -> Result<Ordering, EngineError> {
+    match (lhs, rhs) {
+        (Number::Quantity(_), _) | (_, Number::Quantity(_)) => {
+            return units::compare(lhs, rhs, None);
+        }
+        (Number::Text(l), Number::Text(r)) => return Ok(l.cmp(r)),
+        (Number::Text(_), other) | (other, Number::Text(_)) => {
+            return Err(EngineError::TypeMismatch(
+                "text to compare with text".into(),
+                other.type_name().into(),
+            ));
+        }
+        (Number::Boolean(l), Number::Boolean(r)) => return Ok(l.cmp(r)),
+        (Number::Boolean(_), other) | (other, Number::Boolean(_)) => {
+            return Err(EngineError::TypeMismatch(
+                "boolean to compare with boolean".into(),
+                other.type_name().into(),
+            ));
+        }
+        _ => {}
+    }
+    if let Some(other) = [lhs, rhs]
+        .into_iter()
+        .find(|n| matches!(n, Number::Complex(_) | Number::List(_) | Number::Matrix(_)))
+    {
+        return Err(EngineError::TypeMismatch(
+            "real numbers for ordering comparison".into(),
+            other.type_name().into(),
+        ));
+    }
+    lhs.partial_cmp(rhs)
+        .ok_or_else(|| EngineError::DomainError("Cannot compare NaN".into()))
+}
+
+/// Equality after promotion. Unlike `compare`, complex operands are allowed
+/// since equality of complex numbers is well defined. Lists are equal when
+/// they have the same length and equal elements, matrices when they have the
+/// same shape and equal elements.
+pub fn numbers_equal(lhs: &Number, rhs: &Number) -> bool {
+    match (lhs, rhs) {
+        (Number::Quantity(_), _) | (_, Number::Quantity(_)) => return units::equal(lhs, rhs, None),
+        (Number::Matrix(l), Number::Matrix(r)) => {
+            return l.rows() == r.rows()
+                && l.cols() == r.cols()
+                && l.data()
+                    .iter()
+                    .zip(r.data())
+                    .all(|(a, b)| numbers_equal(a, b));
+        }
+        (Number::Matrix(_), _) | (_, Number::Matrix(_)) => return false,
+        (Number::Text(l), Number::Text(r)) => return l == r,
+        (Number::Text(_), _) | (_, Number::Text(_)) => return false,
+        (Number::Boolean(l), Number::Boolean(r)) => return l == r,
+        (Number::Boolean(_), _) | (_, Number::Boolean(_)) => return false,
+        (Number::List(l), Number::List(r)) => {
+            return l.len() == r.len() && l.iter().zip(r).all(|(a, b)| numbers_equal(a, b));
+        }
+        (Number::List(_), _) | (_, Number::List(_)) => return false,
+        _ => {}
+    }
+    match promote(lhs.clone(), rhs.clone()) {
+        (Number::Complex(l), Number::Complex(r)) => l == r,
+        (l, r) => l.partial_cmp(&r) == Some(Ordering::Equal),
+    }
+}
+
 // Helper to promote types
-// Rank: Integer (0) -> Rational (1) -> Float (2) -> Complex (3)
+// Rank: Integer (0) -> Rational (1) -> Float (2) -> BigFloat (3) -> Complex (4)
+// Booleans are not numbers: the arithmetic operators refuse them (see
+// `elementwise`), builtins do unless they take booleans, and comparisons
+// match them only with booleans, so true + true, sum(true) and true < 2
+// are errors and true == 1 is false.
+// A scalar against a list is repeated to the list's length; element types are
+// promoted pairwise when the operation reaches them. Matrices work the same
+// way, with anything else repeated into a matrix of the same shape.
 fn promote(lhs: Number, rhs: Number) -> (Number, Number) {
     match (lhs, rhs) {
+        (Number::Matrix(l), Number::Matrix(r)) => (Number::Matrix(l), Number::Matrix(r)),
+        (Number::Matrix(l), r) => {
+            let repeated = l.clone().map(|_| r.clone());
+            (Number::Matrix(l), Number::Matrix(repeated))
+        }
+        (l, Number::Matrix(r)) => (
+            Number::Matrix(r.clone().map(|_| l.clone())),
+            Number::Matrix(r),
+        ),
+
+        (Number::List(l), Number::List(r)) => (Number::List(l), Number::List(r)),
+        (Number::List(l), r) => {
+            let repeated = vec![r; l.len()];
+            (Number::List(l), Number::List(repeated))
+        }
+        (l, Number::List(r)) => (Number::List(vec![l; r.len()]), Number::List(r)),
+
+        // Text and booleans have no numeric value and quantities need their
+        // units checked; the evaluator handles all three before this
+        (Number::Text(_) | Number::Boolean(_) | Number::Quantity(_), _)
+        | (_, Number::Text(_) | Number::Boolean(_) | Number::Quantity(_)) => {
+            (Number::Float(f64::NAN), Number::Float(f64::NAN))
+        }
+
+        // Anything vs Complex -> Complex
+        (Number::Complex(l), r) => (Number::Complex(l), Number::Complex(r.to_complex())),
+        (l, Number::Complex(r)) => (Number::Complex(l.to_complex()), Number::Complex(r)),
+
         (Number::Integer(l), Number::Integer(r)) => (Number::Integer(l), Number::Integer(r)),
 
         // Integer vs Rational -> Rational
@@ -99,6 +280,23 @@ fn promote(lhs: Number, rhs: Number) -> (Number, Number) {
         ),
         (Number::Rational(l), Number::Rational(r)) => (Number::Rational(l), Number::Rational(r)),
 
+        // Anything vs BigFloat -> BigFloat at its precision, unless the other
+        // side is a float infinity or NaN which BigFloat cannot represent
+        (Number::BigFloat(l), r) => match r.to_bigfloat(l.precision()) {
+            Some(r) => (Number::BigFloat(l), Number::BigFloat(r)),
+            None => (
+                Number::Float(l.to_f64()),
+                Number::Float(r.to_f64().unwrap_or(f64::NAN)),
+            ),
+        },
+        (l, Number::BigFloat(r)) => match l.to_bigfloat(r.precision()) {
+            Some(l) => (Number::BigFloat(l), Number::BigFloat(r)),
+            None => (
+                Number::Float(l.to_f64().unwrap_or(f64::NAN)),
+                Number::Float(r.to_f64()),
+            ),
+        },
+
         // Anything vs Float -> Float (Note: Rational -> Float loses precision)
         (Number::Float(l), r) => (
             Number::Float(l),
@@ -108,10 +306,6 @@ fn promote(lhs: Number, rhs: Number) -> (Number, Number) {
             Number::Float(l.to_f64().unwrap_or(f64::NAN)),
             Number::Float(r),
         ),
-
-        // Anything vs Complex -> Complex
-        (Number::Complex(l), r) => (Number::Complex(l), Number::Complex(r.to_complex())),
-        (l, Number::Complex(r)) => (Number::Complex(l.to_complex()), Number::Complex(r)),
     }
 }
 
@@ -125,7 +319,14 @@ macro_rules! impl_binary_op {
                     (Number::Integer(l), Number::Integer(r)) => Number::Integer(l.$method(r)),
                     (Number::Rational(l), Number::Rational(r)) => Number::Rational(l.$method(r)),
                     (Number::Float(l), Number::Float(r)) => Number::Float(l.$method(r)),
+                    (Number::BigFloat(l), Number::BigFloat(r)) => Number::BigFloat(l.$method(&r)),
                     (Number::Complex(l), Number::Complex(r)) => Number::Complex(l.$method(r)),
+                    (Number::List(l), Number::List(r)) => {
+                        Number::List(l.into_iter().zip(r).map(|(a, b)| a.$method(b)).collect())
+                    }
+                    (Number::Matrix(l), Number::Matrix(r)) => {
+                        Number::Matrix(l.zip_with(r, |a, b| a.$method(b)))
+                    }
                     _ => unreachable!("Promote should have handled all type combinations"),
                 }
             }
@@ -141,13 +342,14 @@ impl Div for Number {
     type Output = Number;
     fn div(self, rhs: Self) -> Self::Output {
         match (self, rhs) {
-            // Special Case: Integer / Integer = Rational to preserve precision
+            // Special Case: Integer / Integer = Rational to preserve precision,
+            // or Integer when it divides evenly
             (Number::Integer(l), Number::Integer(r)) => {
                 if r.is_zero() {
                     // Division by zero; promote to float to produce INF
                     return Number::Float(l.to_f64().unwrap_or(f64::NAN) / 0.0);
                 }
-                Number::Rational(BigRational::new(l, r))
+                from_ratio(BigRational::new(l, r))
             }
 
             (l, r) => match promote(l, r) {
@@ -156,10 +358,20 @@ impl Div for Number {
                         // Let float division handle infinity.
                         return Number::Float(l.to_f64().unwrap_or(f64::NAN) / 0.0);
                     }
-                    Number::Rational(l / r)
+                    from_ratio(l / r)
                 }
                 (Number::Float(l), Number::Float(r)) => Number::Float(l / r),
+                (Number::BigFloat(l), Number::BigFloat(r)) => match l.div(&r) {
+                    Some(q) => Number::BigFloat(q),
+                    None => Number::Float(l.to_f64() / 0.0),
+                },
                 (Number::Complex(l), Number::Complex(r)) => Number::Complex(l / r),
+                (Number::List(l), Number::List(r)) => {
+                    Number::List(l.into_iter().zip(r).map(|(a, b)| a / b).collect())
+                }
+                (Number::Matrix(l), Number::Matrix(r)) => {
+                    Number::Matrix(l.zip_with(r, |a, b| a / b))
+                }
                 _ => unreachable!(),
             },
         }
@@ -173,7 +385,13 @@ impl Neg for Number {
             Number::Integer(i) => Number::Integer(-i),
             Number::Rational(r) => Number::Rational(-r),
             Number::Float(f) => Number::Float(-f),
+            Number::BigFloat(b) => Number::BigFloat(b.neg()),
             Number::Complex(c) => Number::Complex(-c),
+            Number::List(items) => Number::List(items.into_iter().map(|n| -n).collect()),
+            Number::Matrix(m) => Number::Matrix(m.map(|n| -n)),
+            Number::Text(_) => Number::Float(f64::NAN),
+            Number::Quantity(q) => Number::Quantity(q.negate()),
+            Number::Boolean(b) => Number::Integer(-BigInt::from(u8::from(b))),
         }
     }
 }
@@ -182,48 +400,282 @@ impl Rem for Number {
     type Output = Number;
     fn rem(self, rhs: Self) -> Self::Output {
         match promote(self, rhs) {
+            // x % 0 is NaN, as it is for floats
+            (Number::Integer(_), Number::Integer(r)) if r.is_zero() => Number::Float(f64::NAN),
             (Number::Integer(l), Number::Integer(r)) => Number::Integer(l % r),
+            (Number::Rational(_), Number::Rational(r)) if r.is_zero() => Number::Float(f64::NAN),
             (Number::Rational(l), Number::Rational(r)) => Number::Rational(l % r),
             (Number::Float(l), Number::Float(r)) => Number::Float(l % r),
+            (Number::BigFloat(l), Number::BigFloat(r)) => {
+                l.rem(&r).map_or(Number::Float(f64::NAN), Number::BigFloat)
+            }
             // The remainder operator is not well-defined for complex numbers.
             // Returning NaN is a safe way to signal an invalid operation.
             (Number::Complex(_), Number::Complex(_)) => Number::Float(f64::NAN),
+            (Number::List(l), Number::List(r)) => {
+                Number::List(l.into_iter().zip(r).map(|(a, b)| a % b).collect())
+            }
+            (Number::Matrix(l), Number::Matrix(r)) => Number::Matrix(l.zip_with(r, |a, b| a % b)),
             _ => unreachable!(),
         }
     }
 }
 
-pub fn pow(base: Number, exp: Number) -> Number {
+/// Applies a binary operation element-wise. Two lists must have the same
+/// length and are paired up; a scalar is paired with every element of a list.
+/// Nested lists are handled recursively. Matrices pair up entries when they
+/// have the same shape and pair a scalar with every entry. The operators on
+/// `Number` do the same but cannot report mismatched lengths, so the
+/// evaluator goes through here.
+pub fn elementwise<F>(lhs: Number, rhs: Number, op: &F) -> Result<Number, EngineError>
+where
+    F: Fn(Number, Number) -> Result<Number, EngineError>,
+{
+    match (lhs, rhs) {
+        (Number::Matrix(l), Number::Matrix(r)) => {
+            if l.rows() != r.rows() || l.cols() != r.cols() {
+                return Err(EngineError::DimensionMismatch(format!(
+                    "matrices of size {}×{} and {}×{}",
+                    l.rows(),
+                    l.cols(),
+                    r.rows(),
+                    r.cols()
+                )));
+            }
+            l.try_zip_with(r, op).map(Number::Matrix)
+        }
+        (Number::Matrix(_), Number::List(_)) | (Number::List(_), Number::Matrix(_)) => Err(
+            EngineError::TypeMismatch("matrix or scalar".into(), "list".into()),
+        ),
+        (Number::Matrix(_), Number::Quantity(_)) | (Number::Quantity(_), Number::Matrix(_)) => Err(
+            EngineError::TypeMismatch("number as a matrix entry".into(), "quantity".into()),
+        ),
+        (Number::Matrix(l), r) => l.try_map(|a| op(a, r.clone())).map(Number::Matrix),
+        (l, Number::Matrix(r)) => r.try_map(|b| op(l.clone(), b)).map(Number::Matrix),
+        (Number::List(l), Number::List(r)) => {
+            if l.len() != r.len() {
+                return Err(EngineError::DimensionMismatch(format!(
+                    "lists of length {} and {}",
+                    l.len(),
+                    r.len()
+                )));
+            }
+            l.into_iter()
+                .zip(r)
+                .map(|(a, b)| elementwise(a, b, op))
+                .collect::<Result<_, _>>()
+                .map(Number::List)
+        }
+        (Number::List(l), r) => l
+            .into_iter()
+            .map(|a| elementwise(a, r.clone(), op))
+            .collect::<Result<_, _>>()
+            .map(Number::List),
+        (l, Number::List(r)) => r
+            .into_iter()
+            .map(|b| elementwise(l.clone(), b, op))
+            .collect::<Result<_, _>>()
+            .map(Number::List),
+        (Number::Text(_), _) | (_, Number::Text(_)) => {
+            Err(EngineError::TypeMismatch("number".into(), "text".into()))
+        }
+        // Booleans are compared and combined by the logic functions, but are
+        // not numbers to compute with: true + true is an error, not 2
+        (Number::Boolean(_), _) | (_, Number::Boolean(_)) => {
+            Err(EngineError::TypeMismatch("number".into(), "boolean".into()))
+        }
+        (l, r) => op(l, r),
+    }
+}
+
+/// Applies a unary operation to every element of a (possibly nested) list or
+/// matrix, or to a scalar directly.
+pub fn map_elements<F>(n: Number, op: &F) -> Result<Number, EngineError>
+where
+    F: Fn(Number) -> Result<Number, EngineError>,
+{
+    match n {
+        Number::List(items) => items
+            .into_iter()
+            .map(|item| map_elements(item, op))
+            .collect::<Result<_, _>>()
+            .map(Number::List),
+        Number::Matrix(m) => m.try_map(op).map(Number::Matrix),
+        other => op(other),
+    }
+}
+
+/// The exact real `n`th root of an integer or rational, when there is one.
+/// Negative values have a real root for odd `n`.
+pub fn exact_root(x: &Number, n: u32) -> Option<Number> {
+    let r = match x {
+        Number::Integer(i) => BigRational::from_integer(i.clone()),
+        Number::Rational(r) => r.clone(),
+        _ => return None,
+    };
+    if n == 0 || (r.is_negative() && n.is_multiple_of(2)) {
+        return None;
+    }
+    let root_of = |v: &BigInt| {
+        let root = v.abs().nth_root(n);
+        (Pow::pow(&root, n) == v.abs()).then_some(root)
+    };
+    let num = root_of(r.numer())?;
+    let den = root_of(r.denom())?;
+    let num = if r.is_negative() { -num } else { num };
+    Some(from_ratio(BigRational::new(num, den)))
+}
+
+// Integer when the denominator is one
+pub(crate) fn from_ratio(r: BigRational) -> Number {
+    if r.is_integer() {
+        Number::Integer(r.to_integer())
+    } else {
+        Number::Rational(r)
+    }
+}
+
+// Largest root degree tried for exact results of fractional powers
+const MAX_EXACT_ROOT: u32 = 1 << 16;
+
+// An exponent that is exactly p/q with q > 1, as written (1/3) or as a
+// float with an exact binary or decimal value (0.5, 1.25)
+fn exact_fraction(e: &Number) -> Option<(BigInt, u32)> {
+    let r = match e {
+        Number::Rational(r) => r.clone(),
+        Number::Float(f) => BigRational::from_float(*f)?,
+        Number::BigFloat(b) => b.to_rational(),
+        _ => return None,
+    };
+    let q = r
+        .denom()
+        .to_u32()
+        .filter(|q| (2..=MAX_EXACT_ROOT).contains(q))?;
+    Some((r.numer().clone(), q))
+}
+
+// Exact power of an integer or rational base
+fn rational_pow(b: BigRational, e: BigInt, guard: &Guard) -> Result<Number, EngineError> {
+    // 0, 1 and -1 stay small whatever the exponent
+    if b.is_zero() && e.is_positive() {
+        return Ok(Number::Integer(BigInt::zero()));
+    }
+    if b.is_one() {
+        return Ok(Number::Integer(BigInt::one()));
+    }
+    if (-&b).is_one() {
+        let odd = e.bit(0);
+        return Ok(Number::Integer(if odd {
+            -BigInt::one()
+        } else {
+            BigInt::one()
+        }));
+    }
+
+    // The result has about |e| * log2 of its larger part bits; refuse before computing it
+    let e_abs = e.magnitude();
+    let log2 = |v: &BigInt| {
+        v.to_f64()
+            .map(|f| f.abs().log2())
+            .filter(|l| l.is_finite())
+            .unwrap_or(v.bits() as f64)
+    };
+    let log2_b = log2(b.numer()).max(log2(b.denom()));
+    let estimated = e_abs.to_f64().unwrap_or(f64::INFINITY) * log2_b;
+    guard.check_bits(estimated.ceil().min(u64::MAX as f64) as u64)?;
+
+    match e_abs.to_u32() {
+        Some(e_u32) if b.is_integer() && !e.is_negative() => {
+            Ok(Number::Integer(Pow::pow(b.numer(), e_u32)))
+        }
+        Some(e_u32) => {
+            let (num, den) = (Pow::pow(b.numer(), e_u32), Pow::pow(b.denom(), e_u32));
+            let (num, den) = if e.is_negative() {
+                if num.is_zero() {
+                    return Ok(Number::Float(f64::INFINITY)); // e.g. 0^-2 -> inf
+                }
+                (den, num)
+            } else {
+                (num, den)
+            };
+            // A power of a reduced fraction is already reduced, and a gcd on
+            // multi-million-bit parts would be quadratic
+            let (num, den) = if den.is_negative() {
+                (-num, -den)
+            } else {
+                (num, den)
+            };
+            Ok(from_ratio(BigRational::new_raw(num, den)))
+        }
+        // Exponent is too large for u32, fall back to float calculation
+        None => {
+            let b_f64 = b.to_f64().unwrap_or(f64::NAN);
+            let e_f64 = e.to_f64().unwrap_or(f64::NAN);
+            Ok(Number::Float(b_f64.powf(e_f64)))
+        }
+    }
+}
+
+pub fn pow(base: Number, exp: Number, context: &Context) -> Result<Number, EngineError> {
+    if let Some(b) = [&base, &exp]
+        .into_iter()
+        .find(|n| matches!(n, Number::Boolean(_)))
+    {
+        return Err(EngineError::TypeMismatch(
+            "number".into(),
+            b.type_name().into(),
+        ));
+    }
+    let guard = context.guard();
+    let precision = context.precision_for(&[base.clone(), exp.clone()]);
+
+    // p/q powers are exact when the base has an exact q-th root: 8^(1/3) = 2
+    if let Some((p, q)) = exact_fraction(&exp)
+        && let Some(root) = exact_root(&base, q)
+    {
+        return pow(root, Number::Integer(p), context);
+    }
+
     match (base, exp) {
-        // Power of integer by integer
+        // Exact powers of integers and rationals
         (Number::Integer(b), Number::Integer(e)) => {
-            // Positive exponent
-            if e >= BigInt::zero() {
-                if let Some(e_u32) = e.to_u32() {
-                    return Number::Integer(b.pow(e_u32));
+            rational_pow(BigRational::from_integer(b), e, &guard)
+        }
+        (Number::Rational(b), Number::Integer(e)) => rational_pow(b, e, &guard),
+        // Real powers at the working precision; a negative base with a
+        // fractional exponent has a complex result and falls through
+        (b, e)
+            if precision.is_some()
+                && !matches!(b, Number::Complex(_))
+                && !matches!(e, Number::Complex(_)) =>
+        {
+            let p = precision.unwrap_or_default();
+            match (b.to_bigfloat(p), e.to_bigfloat(p)) {
+                (Some(b), Some(e)) if !b.is_negative() || e.is_integer() => {
+                    Ok(Number::BigFloat(b.pow(&e, &guard)?))
                 }
+                _ => Ok(Number::Complex(b.to_complex().powc(e.to_complex()))),
             }
-            // Negative exponent
-            else {
-                if let Some(e_u32) = (-&e).to_u32() {
-                    let den = b.pow(e_u32);
-                    if den.is_zero() {
-                        return Number::Float(f64::INFINITY);
-                    } // e.g. 0^-2 -> inf
-                    return Number::Rational(BigRational::new(BigInt::one(), den));
-                }
+        }
+        // Real powers stay real where they are defined: non-negative bases
+        // and integer exponents
+        (b, e) if !matches!(b, Number::Complex(_)) && !matches!(e, Number::Complex(_)) => {
+            let (b, e) = (
+                b.to_f64().unwrap_or(f64::NAN),
+                e.to_f64().unwrap_or(f64::NAN),
+            );
+            if b >= 0.0 || e.fract() == 0.0 {
+                Ok(Number::Float(b.powf(e)))
+            } else {
+                Ok(Number::Complex(Complex64::new(b, 0.0).powf(e)))
             }
-            // Exponent is too large for u32, fall back to float calculation
-            let b_f64 = b.to_f64().unwrap_or(f64::NAN);
-            let e_f64 = e.to_f64().unwrap_or(f64::NAN);
-            Number::Float(b_f64.powf(e_f64))
         }
         // Fallback to complex powers for all other cases
-        (b, e) => Number::Complex(b.to_complex().powc(e.to_complex())),
+        (b, e) => Ok(Number::Complex(b.to_complex().powc(e.to_complex()))),
     }
 }
 
-pub fn factorial(n: Number) -> Result<Number, EngineError> {
+pub fn factorial(n: Number, guard: &Guard) -> Result<Number, EngineError> {
     match n {
         Number::Integer(i) => {
             if i < BigInt::zero() {
@@ -231,19 +683,37 @@ pub fn factorial(n: Number) -> Result<Number, EngineError> {
                     "Factorial of negative integer".into(),
                 ));
             }
-            // Warning: Huge loop for big integers.
-            // Simplified loop:
+            // Stirling's approximation of log2(n!) rejects huge inputs up front;
+            // the loop stays cancellable while the product grows.
+            let n = i.to_f64().unwrap_or(f64::INFINITY);
+            if n > 1.0 {
+                let ln_fact = n * n.ln() - n + 0.5 * (std::f64::consts::TAU * n).ln();
+                let estimated = ln_fact / std::f64::consts::LN_2;
+                guard.check_bits(estimated.floor().min(u64::MAX as f64) as u64)?;
+            }
             let mut acc = BigInt::one();
             let mut k = BigInt::one();
+            let mut iterations: u32 = 0;
             while k <= i {
-                acc = acc * &k;
-                k = k + 1;
-                // Safety brake? No, user asked for "Infinite" calculator.
+                acc *= &k;
+                k += 1;
+                iterations = iterations.wrapping_add(1);
+                if iterations.is_multiple_of(256) {
+                    guard.check_interrupt()?;
+                    guard.check_bits(acc.bits())?;
+                }
             }
+            guard.check_bits(acc.bits())?;
             Ok(Number::Integer(acc))
         }
-        _ => Err(EngineError::DomainError(
-            "Factorial only implemented for Integers currently".into(),
+        // Other numbers through the gamma function: x! = Γ(x + 1), so
+        // (1/2)! = √π/2
+        n @ (Number::Rational(_) | Number::Float(_) | Number::BigFloat(_) | Number::Complex(_)) => {
+            special::gamma_of(&(n + Number::Integer(BigInt::one())))
+        }
+        other => Err(EngineError::TypeMismatch(
+            "number".into(),
+            other.type_name().into(),
         )),
     }
 }
@@ -0,0 +1,795 @@
+//! Physical units and dimensional analysis.
+//!
+//! A quantity is a number together with the units it was written in (`5 km`,
+//! `9.81 m/s^2`). Arithmetic keeps track of them: `*` and `/` combine the
+//! units, while `+`, `-`, `%` and comparisons convert the right operand into
+//! the units of the left one and fail when the dimensions differ (`1 m + 1 s`).
+//! `value to unit` (or `in`) converts explicitly. `in` is also the inch,
+//! read as the unit after a number (`12 in`) and as a target (`1 ft to in`). When the dimensions cancel
+//! out the result is a plain number, so `1 km / 1 m` is 1000.
+//!
+//! Conversion factors are exact rationals, so exact values stay exact.
+//! Currencies are units too, sized by the context's exchange rates (see
+//! `engine::currency`), which is why the operations take a `RateTable`.
+//! Temperatures with a zero point other than absolute zero (°C, °F) are read
+//! as differences in arithmetic, so `20 °C + 5 °C` is 25 °C; adding one to a
+//! temperature in another unit is refused rather than guessed at. Only
+//! converting a plain temperature applies the offset, so `20 °C to °F` is
+//! 68 °F.
+
+use super::ast::Context;
+use super::currency::{self, RateTable};
+use super::errors::EngineError;
+use super::types::{Number, compare as compare_values, from_ratio, numbers_equal, pow as power};
+use num::{One, ToPrimitive};
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Exponents of the base dimensions: length, mass, time, electric current,
+/// temperature, amount of substance, luminous intensity, information and
+/// money.
+pub type Dimension = [i32; 9];
+
+const LENGTH: Dimension = [1, 0, 0, 0, 0, 0, 0, 0, 0];
+const MASS: Dimension = [0, 1, 0, 0, 0, 0, 0, 0, 0];
+const TIME: Dimension = [0, 0, 1, 0, 0, 0, 0, 0, 0];
+const CURRENT: Dimension = [0, 0, 0, 1, 0, 0, 0, 0, 0];
+const TEMPERATURE: Dimension = [0, 0, 0, 0, 1, 0, 0, 0, 0];
+const AMOUNT: Dimension = [0, 0, 0, 0, 0, 1, 0, 0, 0];
+const LUMINOSITY: Dimension = [0, 0, 0, 0, 0, 0, 1, 0, 0];
+const INFORMATION: Dimension = [0, 0, 0, 0, 0, 0, 0, 1, 0];
+const MONEY: Dimension = [0, 0, 0, 0, 0, 0, 0, 0, 1];
+const VOLUME: Dimension = [3, 0, 0, 0, 0, 0, 0, 0, 0];
+const SPEED: Dimension = [1, 0, -1, 0, 0, 0, 0, 0, 0];
+const FREQUENCY: Dimension = [0, 0, -1, 0, 0, 0, 0, 0, 0];
+const FORCE: Dimension = [1, 1, -2, 0, 0, 0, 0, 0, 0];
+const PRESSURE: Dimension = [-1, 1, -2, 0, 0, 0, 0, 0, 0];
+const ENERGY: Dimension = [2, 1, -2, 0, 0, 0, 0, 0, 0];
+const POWER: Dimension = [2, 1, -3, 0, 0, 0, 0, 0, 0];
+const CHARGE: Dimension = [0, 0, 1, 1, 0, 0, 0, 0, 0];
+const VOLTAGE: Dimension = [2, 1, -3, -1, 0, 0, 0, 0, 0];
+const RESISTANCE: Dimension = [2, 1, -3, -2, 0, 0, 0, 0, 0];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Prefixes {
+    None,
+    Si,
+    /// SI prefixes and the binary ones (Ki, Mi, ...), for data sizes
+    Binary,
+}
+
+#[derive(Debug)]
+struct UnitDef {
+    symbol: &'static str,
+    /// Spelled out names accepted in place of the symbol, without prefixes
+    names: &'static [&'static str],
+    dimension: Dimension,
+    /// Size of the unit in SI base units, as numerator and denominator
+    factor: (i128, i128),
+    /// How far the unit's zero lies below absolute zero, in the unit itself
+    offset: (i128, i128),
+    prefixes: Prefixes,
+}
+
+const fn unit(
+    symbol: &'static str,
+    names: &'static [&'static str],
+    dimension: Dimension,
+    factor: (i128, i128),
+    prefixes: Prefixes,
+) -> UnitDef {
+    UnitDef {
+        symbol,
+        names,
+        dimension,
+        factor,
+        offset: (0, 1),
+        prefixes,
+    }
+}
+
+static UNITS: &[UnitDef] = &[
+    // Length
+    unit(
+        "m",
+        &["meter", "meters", "metre", "metres"],
+        LENGTH,
+        (1, 1),
+        Prefixes::Si,
+    ),
+    unit(
+        "inch",
+        &["inches", "in"],
+        LENGTH,
+        (254, 10_000),
+        Prefixes::None,
+    ),
+    unit(
+        "ft",
+        &["foot", "feet"],
+        LENGTH,
+        (3048, 10_000),
+        Prefixes::None,
+    ),
+    unit(
+        "yd",
+        &["yard", "yards"],
+        LENGTH,
+        (9144, 10_000),
+        Prefixes::None,
+    ),
+    unit(
+        "mi",
+        &["mile", "miles"],
+        LENGTH,
+        (1_609_344, 1000),
+        Prefixes::None,
+    ),
+    unit("nmi", &[], LENGTH, (1852, 1), Prefixes::None),
+    unit("au", &[], LENGTH, (149_597_870_700, 1), Prefixes::None),
+    unit(
+        "ly",
+        &["lightyear", "lightyears"],
+        LENGTH,
+        (9_460_730_472_580_800, 1),
+        Prefixes::None,
+    ),
+    // Mass
+    unit("g", &["gram", "grams"], MASS, (1, 1000), Prefixes::Si),
+    unit("t", &["tonne", "tonnes"], MASS, (1000, 1), Prefixes::None),
+    unit(
+        "lb",
+        &["pound", "pounds"],
+        MASS,
+        (45_359_237, 100_000_000),
+        Prefixes::None,
+    ),
+    unit(
+        "oz",
+        &["ounce", "ounces"],
+        MASS,
+        (45_359_237, 1_600_000_000),
+        Prefixes::None,
+    ),
+    // Time
+    unit("s", &["second", "seconds"], TIME, (1, 1), Prefixes::Si),
+    unit("min", &["minute", "minutes"], TIME, (60, 1), Prefixes::None),
+    unit("h", &["hour", "hours"], TIME, (3600, 1), Prefixes::None),
+    unit("day", &["days"], TIME, (86_400, 1), Prefixes::None),
+    unit("week", &["weeks"], TIME, (604_800, 1), Prefixes::None),
+    // Julian year, as used for light years
+    unit(
+        "yr",
+        &["year", "years"],
+        TIME,
+        (31_557_600, 1),
+        Prefixes::None,
+    ),
+    // Other base units
+    unit(
+        "A",
+        &["ampere", "amperes", "amp", "amps"],
+        CURRENT,
+        (1, 1),
+        Prefixes::Si,
+    ),
+    unit("K", &["kelvin"], TEMPERATURE, (1, 1), Prefixes::Si),
+    UnitDef {
+        symbol: "°C",
+        names: &["degC", "celsius"],
+        dimension: TEMPERATURE,
+        factor: (1, 1),
+        offset: (27_315, 100),
+        prefixes: Prefixes::None,
+    },
+    UnitDef {
+        symbol: "°F",
+        names: &["degF", "fahrenheit"],
+        dimension: TEMPERATURE,
+        factor: (5, 9),
+        offset: (45_967, 100),
+        prefixes: Prefixes::None,
+    },
+    unit("mol", &["mole", "moles"], AMOUNT, (1, 1), Prefixes::Si),
+    unit("cd", &["candela"], LUMINOSITY, (1, 1), Prefixes::Si),
+    // Volume
+    unit(
+        "L",
+        &["liter", "liters", "litre", "litres"],
+        VOLUME,
+        (1, 1000),
+        Prefixes::Si,
+    ),
+    // Speed
+    unit("mph", &[], SPEED, (1_609_344, 3_600_000), Prefixes::None),
+    unit(
+        "kn",
+        &["knot", "knots"],
+        SPEED,
+        (1852, 3600),
+        Prefixes::None,
+    ),
+    // Mechanics and electricity
+    unit("Hz", &["hertz"], FREQUENCY, (1, 1), Prefixes::Si),
+    unit("N", &["newton", "newtons"], FORCE, (1, 1), Prefixes::Si),
+    unit("Pa", &["pascal", "pascals"], PRESSURE, (1, 1), Prefixes::Si),
+    unit("bar", &[], PRESSURE, (100_000, 1), Prefixes::Si),
+    unit("atm", &[], PRESSURE, (101_325, 1), Prefixes::None),
+    // Pound-force (0.45359237 kg at 9.80665 m/s²) per square inch
+    unit(
+        "psi",
+        &[],
+        PRESSURE,
+        (4_448_221_615_260_500_000_000, 645_160_000_000_000_000),
+        Prefixes::None,
+    ),
+    unit("C", &["coulomb", "coulombs"], CHARGE, (1, 1), Prefixes::Si),
+    unit("V", &["volt", "volts"], VOLTAGE, (1, 1), Prefixes::Si),
+    unit("ohm", &["ohms"], RESISTANCE, (1, 1), Prefixes::Si),
+    // Energy and power
+    unit("J", &["joule", "joules"], ENERGY, (1, 1), Prefixes::Si),
+    unit(
+        "cal",
+        &["calorie", "calories"],
+        ENERGY,
+        (4184, 1000),
+        Prefixes::Si,
+    ),
+    unit(
+        "eV",
+        &["electronvolt", "electronvolts"],
+        ENERGY,
+        (1_602_176_634, 10_000_000_000_000_000_000_000_000_000),
+        Prefixes::Si,
+    ),
+    unit("Wh", &[], ENERGY, (3600, 1), Prefixes::Si),
+    unit("W", &["watt", "watts"], POWER, (1, 1), Prefixes::Si),
+    // Data sizes
+    unit("bit", &["bits"], INFORMATION, (1, 1), Prefixes::Binary),
+    unit(
+        "B",
+        &["byte", "bytes"],
+        INFORMATION,
+        (8, 1),
+        Prefixes::Binary,
+    ),
+];
+
+#[derive(Debug)]
+struct Prefix {
+    symbol: &'static str,
+    base: u32,
+    exponent: i32,
+    binary: bool,
+}
+
+const fn si(symbol: &'static str, exponent: i32) -> Prefix {
+    Prefix {
+        symbol,
+        base: 10,
+        exponent,
+        binary: false,
+    }
+}
+
+const fn binary(symbol: &'static str, exponent: i32) -> Prefix {
+    Prefix {
+        symbol,
+        base: 2,
+        exponent,
+        binary: true,
+    }
+}
+
+// Two letter prefixes come first so that "da" is not read as "d"
+static PREFIXES: &[Prefix] = &[
+    si("da", 1),
+    binary("Ki", 10),
+    binary("Mi", 20),
+    binary("Gi", 30),
+    binary("Ti", 40),
+    binary("Pi", 50),
+    binary("Ei", 60),
+    si("Y", 24),
+    si("Z", 21),
+    si("E", 18),
+    si("P", 15),
+    si("T", 12),
+    si("G", 9),
+    si("M", 6),
+    si("k", 3),
+    si("h", 2),
+    si("d", -1),
+    si("c", -2),
+    si("m", -3),
+    si("u", -6),
+    si("n", -9),
+    si("p", -12),
+    si("f", -15),
+    si("a", -18),
+    si("z", -21),
+    si("y", -24),
+];
+
+fn ratio(pair: (i128, i128)) -> BigRational {
+    BigRational::new(BigInt::from(pair.0), BigInt::from(pair.1))
+}
+
+/// A unit from the table, possibly with a prefix, or a currency.
+#[derive(Debug, Clone, Copy)]
+enum Unit {
+    Table {
+        def: &'static UnitDef,
+        prefix: Option<&'static Prefix>,
+    },
+    Currency(&'static str),
+}
+
+impl Unit {
+    fn symbol(&self) -> String {
+        match self {
+            Unit::Table { def, prefix } => {
+                format!("{}{}", prefix.map_or("", |p| p.symbol), def.symbol)
+            }
+            Unit::Currency(code) => code.to_string(),
+        }
+    }
+
+    fn dimension(&self) -> Dimension {
+        match self {
+            Unit::Table { def, .. } => def.dimension,
+            Unit::Currency(_) => MONEY,
+        }
+    }
+
+    // Size in SI base units, or in the base currency of the rate table
+    fn factor(&self, rates: Option<&RateTable>) -> Result<BigRational, EngineError> {
+        match self {
+            Unit::Table { def, prefix } => {
+                let factor = ratio(def.factor);
+                Ok(match prefix {
+                    Some(p) => {
+                        factor * BigRational::from_integer(BigInt::from(p.base)).pow(p.exponent)
+                    }
+                    None => factor,
+                })
+            }
+            Unit::Currency(code) => rates
+                .ok_or_else(|| EngineError::ExchangeRate("no exchange rates loaded".into()))?
+                .value_of(code),
+        }
+    }
+
+    fn same_base(&self, other: &Unit) -> bool {
+        match (self, other) {
+            (Unit::Table { def: a, .. }, Unit::Table { def: b, .. }) => std::ptr::eq(*a, *b),
+            (Unit::Currency(a), Unit::Currency(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+// Exact names win over prefixed ones, so "min" is minutes and "Pa" pascals
+fn lookup(name: &str) -> Option<Unit> {
+    if let Some(def) = UNITS
+        .iter()
+        .find(|u| u.symbol == name || u.names.contains(&name))
+    {
+        return Some(Unit::Table { def, prefix: None });
+    }
+    if let Some(code) = currency::code(name) {
+        return Some(Unit::Currency(code));
+    }
+    PREFIXES.iter().find_map(|prefix| {
+        let rest = name.strip_prefix(prefix.symbol)?;
+        let def = UNITS.iter().find(|u| u.symbol == rest)?;
+        let allowed = match def.prefixes {
+            Prefixes::None => false,
+            Prefixes::Si => !prefix.binary,
+            Prefixes::Binary => true,
+        };
+        allowed.then_some(Unit::Table {
+            def,
+            prefix: Some(prefix),
+        })
+    })
+}
+
+/// Whether `name` is a unit, with or without a prefix.
+pub fn is_unit(name: &str) -> bool {
+    lookup(name).is_some()
+}
+
+/// One of the unit `name`, or `None` if there is no such unit.
+pub fn unit_value(name: &str) -> Option<Number> {
+    lookup(name).map(|u| {
+        Number::Quantity(Quantity {
+            value: Box::new(Number::Integer(BigInt::one())),
+            units: vec![(u.symbol(), 1)],
+        })
+    })
+}
+
+// Units with their powers, after resolving the symbols. Units come from the
+// table when a quantity is built, so they resolve.
+fn parts(units: &[(String, i32)]) -> impl Iterator<Item = (Unit, i32)> + '_ {
+    units
+        .iter()
+        .filter_map(|(symbol, power)| lookup(symbol).map(|u| (u, *power)))
+}
+
+// How many of `to` make one of `from`. Units on both sides cancel first, so
+// amounts in a single currency convert without rates.
+fn conversion(
+    from: &[(String, i32)],
+    to: &[(String, i32)],
+    rates: Option<&RateTable>,
+) -> Result<BigRational, EngineError> {
+    let mut net: Vec<(&str, i32)> = Vec::new();
+    let negated = to.iter().map(|(s, p)| (s, -p));
+    for (symbol, power) in from.iter().map(|(s, p)| (s, *p)).chain(negated) {
+        match net.iter_mut().find(|(s, _)| s == symbol) {
+            Some(entry) => entry.1 += power,
+            None => net.push((symbol, power)),
+        }
+    }
+    let mut factor = BigRational::one();
+    for (symbol, power) in net.into_iter().filter(|n| n.1 != 0) {
+        if let Some(unit) = lookup(symbol) {
+            factor *= unit.factor(rates)?.pow(power);
+        }
+    }
+    Ok(factor)
+}
+
+/// A number with units, see the module documentation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Quantity {
+    value: Box<Number>,
+    /// Unit symbols with their powers, in the order they were written
+    units: Vec<(String, i32)>,
+}
+
+impl Quantity {
+    pub fn value(&self) -> &Number {
+        &self.value
+    }
+
+    /// The dimension of the units, as exponents of the base dimensions.
+    pub fn dimension(&self) -> Dimension {
+        let mut dimension = [0; 9];
+        for (unit, power) in parts(&self.units) {
+            for (d, u) in dimension.iter_mut().zip(unit.dimension()) {
+                *d += u * power;
+            }
+        }
+        dimension
+    }
+
+    /// The units as written after the number: "km/h", "kg m^2/s^2", "/s".
+    pub fn unit(&self) -> String {
+        let part = |(symbol, power): &(String, i32)| match power.abs() {
+            1 => symbol.clone(),
+            p => format!("{}^{}", symbol, p),
+        };
+        let numerator: Vec<String> = self.units.iter().filter(|u| u.1 > 0).map(part).collect();
+        let mut text = numerator.join(" ");
+        for denominator in self.units.iter().filter(|u| u.1 < 0) {
+            text.push('/');
+            text.push_str(&part(denominator));
+        }
+        text
+    }
+
+    fn with_value(self, value: Number) -> Number {
+        Number::Quantity(Quantity {
+            value: Box::new(value),
+            units: self.units,
+        })
+    }
+
+    pub(crate) fn negate(self) -> Quantity {
+        Quantity {
+            value: Box::new(-*self.value),
+            units: self.units,
+        }
+    }
+
+    // The value expressed in the units of `target`
+    fn value_in(
+        &self,
+        target: &Quantity,
+        rates: Option<&RateTable>,
+    ) -> Result<Number, EngineError> {
+        if self.dimension() != target.dimension() {
+            return Err(mismatch(self, target));
+        }
+        let factor = conversion(&self.units, &target.units, rates)?;
+        Ok(scale((*self.value).clone(), factor))
+    }
+
+    // A single temperature unit, which converts with its offset
+    fn temperature(&self) -> Option<(&'static UnitDef, BigRational)> {
+        match parts(&self.units).collect::<Vec<_>>()[..] {
+            [(unit @ Unit::Table { def, .. }, 1)] if def.dimension == TEMPERATURE => {
+                Some((def, unit.factor(None).ok()?))
+            }
+            _ => None,
+        }
+    }
+}
+
+// The right operand of `+`, `-`, `%` or a comparison in the units of the
+// left one. Temperatures in different units are refused when either has an
+// offset zero point, since 1 K + 1 °C could mean 2 K as well as 275.15 K.
+fn right_in_left(
+    l: &Quantity,
+    r: &Quantity,
+    rates: Option<&RateTable>,
+) -> Result<Number, EngineError> {
+    if l.dimension() != r.dimension() {
+        return Err(mismatch(l, r));
+    }
+    if let (Some((a, _)), Some((b, _))) = (l.temperature(), r.temperature())
+        && a.symbol != b.symbol
+        && (a.offset.0 != 0 || b.offset.0 != 0)
+    {
+        return Err(EngineError::DimensionMismatch(format!(
+            "temperatures in {} and {}; convert one with `to` first",
+            l.unit(),
+            r.unit()
+        )));
+    }
+    r.value_in(l, rates)
+}
+
+fn mismatch(lhs: &Quantity, rhs: &Quantity) -> EngineError {
+    EngineError::DimensionMismatch(format!("units {} and {}", lhs.unit(), rhs.unit()))
+}
+
+fn plain_mismatch(q: &Quantity) -> EngineError {
+    EngineError::DimensionMismatch(format!("units {} and a plain number", q.unit()))
+}
+
+fn scale(value: Number, factor: BigRational) -> Number {
+    if factor.is_one() {
+        return value;
+    }
+    match value * Number::Rational(factor) {
+        Number::Rational(r) => from_ratio(r),
+        other => other,
+    }
+}
+
+// Builds the result of `*`, `/` or `^`, which is a plain number once the
+// dimensions cancel
+fn quantity(
+    value: Number,
+    mut units: Vec<(String, i32)>,
+    rates: Option<&RateTable>,
+) -> Result<Number, EngineError> {
+    units.retain(|u| u.1 != 0);
+    let q = Quantity {
+        value: Box::new(value),
+        units,
+    };
+    if q.dimension() == [0; 9] {
+        let factor = conversion(&q.units, &[], rates)?;
+        Ok(scale(*q.value, factor))
+    } else {
+        Ok(Number::Quantity(q))
+    }
+}
+
+// Multiplies `lhs` by the units of `rhs` raised to `sign`. A unit that is
+// already present with another prefix is converted to the first one, so
+// `2 km * 300 m` is 0.6 km^2.
+fn combine(
+    lhs: Quantity,
+    rhs: Quantity,
+    value: Number,
+    sign: i32,
+    rates: Option<&RateTable>,
+) -> Result<Number, EngineError> {
+    let mut value = value;
+    let mut units = lhs.units;
+    for (symbol, power) in rhs.units {
+        let power = power * sign;
+        let unit = lookup(&symbol);
+        let existing = units.iter_mut().find(|(s, _)| {
+            *s == symbol || matches!((lookup(s), unit), (Some(a), Some(b)) if a.same_base(&b))
+        });
+        match (existing, unit) {
+            (Some(entry), Some(unit)) => {
+                if entry.0 != symbol
+                    && let Some(first) = lookup(&entry.0)
+                {
+                    let factor = unit.factor(rates)? / first.factor(rates)?;
+                    value = scale(value, factor.pow(power));
+                }
+                entry.1 += power;
+            }
+            _ => units.push((symbol, power)),
+        }
+    }
+    quantity(value, units, rates)
+}
+
+/// `lhs * rhs` keeping track of units.
+pub fn mul(lhs: Number, rhs: Number, rates: Option<&RateTable>) -> Result<Number, EngineError> {
+    match (lhs, rhs) {
+        (Number::Quantity(l), Number::Quantity(r)) => {
+            let value = (*l.value).clone() * (*r.value).clone();
+            combine(l, r, value, 1, rates)
+        }
+        (Number::Quantity(q), n) => {
+            let value = (*q.value).clone() * n;
+            Ok(q.with_value(value))
+        }
+        (n, Number::Quantity(q)) => {
+            let value = n * (*q.value).clone();
+            Ok(q.with_value(value))
+        }
+        (l, r) => Ok(l * r),
+    }
+}
+
+/// `lhs / rhs` keeping track of units.
+pub fn div(lhs: Number, rhs: Number, rates: Option<&RateTable>) -> Result<Number, EngineError> {
+    match (lhs, rhs) {
+        (Number::Quantity(l), Number::Quantity(r)) => {
+            let value = (*l.value).clone() / (*r.value).clone();
+            combine(l, r, value, -1, rates)
+        }
+        (Number::Quantity(q), n) => {
+            let value = (*q.value).clone() / n;
+            Ok(q.with_value(value))
+        }
+        (n, Number::Quantity(q)) => {
+            let value = n / *q.value;
+            let units = q.units.into_iter().map(|(s, p)| (s, -p)).collect();
+            quantity(value, units, rates)
+        }
+        (l, r) => Ok(l / r),
+    }
+}
+
+// `+`, `-` and `%` need the same dimension on both sides and answer in the
+// units of the left operand
+fn same_dimension(
+    lhs: Number,
+    rhs: Number,
+    rates: Option<&RateTable>,
+    op: fn(Number, Number) -> Number,
+) -> Result<Number, EngineError> {
+    match (lhs, rhs) {
+        (Number::Quantity(l), Number::Quantity(r)) => {
+            let r = right_in_left(&l, &r, rates)?;
+            let value = op((*l.value).clone(), r);
+            Ok(l.with_value(value))
+        }
+        (Number::Quantity(q), _) | (_, Number::Quantity(q)) => Err(plain_mismatch(&q)),
+        (l, r) => Ok(op(l, r)),
+    }
+}
+
+pub fn add(lhs: Number, rhs: Number, rates: Option<&RateTable>) -> Result<Number, EngineError> {
+    same_dimension(lhs, rhs, rates, |l, r| l + r)
+}
+
+pub fn sub(lhs: Number, rhs: Number, rates: Option<&RateTable>) -> Result<Number, EngineError> {
+    same_dimension(lhs, rhs, rates, |l, r| l - r)
+}
+
+pub fn rem(lhs: Number, rhs: Number, rates: Option<&RateTable>) -> Result<Number, EngineError> {
+    same_dimension(lhs, rhs, rates, |l, r| l % r)
+}
+
+/// `base ^ exp`. A quantity can be raised to a rational power as long as
+/// every unit ends up with a whole power, so `(9 m^2)^(1/2)` is 3 m.
+pub fn pow(base: Number, exp: Number, context: &Context) -> Result<Number, EngineError> {
+    match (base, exp) {
+        (Number::Quantity(q), exp) => {
+            let exponent = match &exp {
+                Number::Integer(i) => BigRational::from_integer(i.clone()),
+                Number::Rational(r) => r.clone(),
+                other => {
+                    return Err(EngineError::TypeMismatch(
+                        "rational exponent for a quantity".into(),
+                        other.type_name().into(),
+                    ));
+                }
+            };
+            let mut units = Vec::with_capacity(q.units.len());
+            for (symbol, power) in &q.units {
+                let scaled = &exponent * BigRational::from_integer(BigInt::from(*power));
+                match scaled
+                    .is_integer()
+                    .then(|| scaled.to_integer().to_i32())
+                    .flatten()
+                {
+                    Some(p) => units.push((symbol.clone(), p)),
+                    None => {
+                        return Err(EngineError::DomainError(format!(
+                            "{} cannot be raised to the power {}",
+                            q.unit(),
+                            exponent
+                        )));
+                    }
+                }
+            }
+            let value = power(*q.value, exp, context)?;
+            quantity(value, units, context.rates.as_ref())
+        }
+        (_, Number::Quantity(q)) => Err(EngineError::TypeMismatch(
+            "plain number as an exponent".into(),
+            format!("quantity in {}", q.unit()),
+        )),
+        (b, e) => power(b, e, context),
+    }
+}
+
+/// Orders two quantities of the same dimension.
+pub fn compare(
+    lhs: &Number,
+    rhs: &Number,
+    rates: Option<&RateTable>,
+) -> Result<Ordering, EngineError> {
+    match (lhs, rhs) {
+        (Number::Quantity(l), Number::Quantity(r)) => {
+            compare_values(&l.value, &right_in_left(l, r, rates)?)
+        }
+        (Number::Quantity(q), _) | (_, Number::Quantity(q)) => Err(plain_mismatch(q)),
+        (l, r) => compare_values(l, r),
+    }
+}
+
+/// Quantities are equal when they have the same dimension and size.
+pub fn equal(lhs: &Number, rhs: &Number, rates: Option<&RateTable>) -> bool {
+    match (lhs, rhs) {
+        (Number::Quantity(l), Number::Quantity(r)) => {
+            right_in_left(l, r, rates).is_ok_and(|r| numbers_equal(&l.value, &r))
+        }
+        (Number::Quantity(_), _) | (_, Number::Quantity(_)) => false,
+        (l, r) => numbers_equal(l, r),
+    }
+}
+
+/// `value to target`: `value` expressed in the units of `target`, whose own
+/// number is ignored. Converting between two temperature units applies their
+/// offsets.
+pub fn convert(
+    value: Number,
+    target: &Number,
+    rates: Option<&RateTable>,
+) -> Result<Number, EngineError> {
+    let Number::Quantity(target) = target else {
+        return Err(EngineError::TypeMismatch(
+            "unit to convert to".into(),
+            target.type_name().into(),
+        ));
+    };
+    let Number::Quantity(q) = value else {
+        return Err(EngineError::TypeMismatch(
+            "quantity to convert".into(),
+            value.type_name().into(),
+        ));
+    };
+    let value = match (q.temperature(), target.temperature()) {
+        (Some((from, from_factor)), Some((to, to_factor))) => {
+            let kelvin = scale(
+                (*q.value).clone() + Number::Rational(ratio(from.offset)),
+                from_factor,
+            );
+            let value = scale(kelvin, to_factor.recip()) - Number::Rational(ratio(to.offset));
+            match value {
+                Number::Rational(r) => from_ratio(r),
+                other => other,
+            }
+        }
+        _ => q.value_in(target, rates)?,
+    };
+    Ok(target.clone().with_value(value))
+}
@@ -0,0 +1,190 @@
+//! Fixed-width integers for programmer mode.
+//!
+//! Integers are unbounded by default. Setting `Context::word_size` to a
+//! `WordSize` (8 to 128 bits, signed or unsigned) makes every integer result
+//! wrap into that word the way machine arithmetic does, and the bitwise
+//! functions then work on the word: `bnot` flips exactly its bits and
+//! `rol`/`ror` rotate within it. Numbers as written wrap silently, so `0xFF`
+//! is -1 in signed 8-bit mode; anything computed that had to wrap sets the
+//! overflow flag read through `Context::overflowed`. Radix display of a
+//! negative value shows its two's complement bit pattern.
+
+use super::errors::EngineError;
+use super::types::Number;
+use num::{Integer, One, ToPrimitive, Zero};
+use num_bigint::{BigInt, BigUint};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// The widths programmer mode offers.
+pub const WIDTHS: [u32; 5] = [8, 16, 32, 64, 128];
+
+/// Saved by name (`"i32"`), which also keeps out widths `new` rejects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct WordSize {
+    bits: u32,
+    signed: bool,
+}
+
+impl WordSize {
+    /// The word `rol` and `ror` rotate within when no word size is set.
+    pub const DEFAULT: WordSize = WordSize {
+        bits: 64,
+        signed: true,
+    };
+
+    pub fn new(bits: u32, signed: bool) -> Result<Self, EngineError> {
+        if !WIDTHS.contains(&bits) {
+            return Err(EngineError::DomainError(format!(
+                "Word size must be 8, 16, 32, 64 or 128 bits, not {}",
+                bits
+            )));
+        }
+        Ok(Self { bits, signed })
+    }
+
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    pub fn signed(&self) -> bool {
+        self.signed
+    }
+
+    // 2^bits, the number of distinct values in the word
+    fn modulus(&self) -> BigInt {
+        BigInt::one() << self.bits
+    }
+
+    /// The smallest value of the word.
+    pub fn min(&self) -> BigInt {
+        if self.signed {
+            -(BigInt::one() << (self.bits - 1))
+        } else {
+            BigInt::zero()
+        }
+    }
+
+    /// The largest value of the word.
+    pub fn max(&self) -> BigInt {
+        if self.signed {
+            (BigInt::one() << (self.bits - 1)) - 1
+        } else {
+            self.modulus() - 1
+        }
+    }
+
+    pub fn contains(&self, i: &BigInt) -> bool {
+        *i >= self.min() && *i <= self.max()
+    }
+
+    /// The bits of `i` in the word, read as an unsigned number.
+    pub fn pattern(&self, i: &BigInt) -> BigInt {
+        i.mod_floor(&self.modulus())
+    }
+
+    /// `i` reduced modulo 2^bits into the word's range.
+    pub fn wrap(&self, i: &BigInt) -> BigInt {
+        let pattern = self.pattern(i);
+        if self.signed && pattern > self.max() {
+            pattern - self.modulus()
+        } else {
+            pattern
+        }
+    }
+
+    /// `base^exp` wrapped into the word without building the full power,
+    /// and whether the full power would not have fit.
+    pub fn pow(&self, base: &BigInt, exp: &BigInt) -> (BigInt, bool) {
+        let power = base.modpow(exp, &self.modulus());
+        // Past `bits` any base but 0 and ±1 has left the word; below it the
+        // exact power is small enough to check
+        let overflowed = base.magnitude() > &BigUint::one()
+            && (*exp > BigInt::from(self.bits)
+                || !self.contains(&base.pow(exp.to_u32().unwrap_or_default())));
+        (self.wrap(&power), overflowed)
+    }
+
+    /// Rotates the bits of `i` left by `count`; a negative count rotates right.
+    pub fn rotate_left(&self, i: &BigInt, count: &BigInt) -> BigInt {
+        let pattern = self.pattern(i);
+        let count = count.mod_floor(&BigInt::from(self.bits));
+        let count = usize::try_from(count).unwrap_or_default();
+        let rotated = (&pattern << count) | (pattern >> (self.bits as usize - count));
+        self.wrap(&rotated)
+    }
+
+    /// `i` in base 16, 8 or 2 as its two's complement bit pattern, so -1 in
+    /// an 8-bit word is `0xFF`.
+    pub fn format_radix(&self, i: &BigInt, radix: u32) -> Option<String> {
+        crate::utils::format_radix(&self.pattern(i), radix)
+    }
+
+    /// `value` with its integers wrapped into the word, or `None` when all of
+    /// them already fit.
+    pub(crate) fn fit(&self, value: &Number) -> Option<Number> {
+        match value {
+            Number::Integer(i) if !self.contains(i) => Some(Number::Integer(self.wrap(i))),
+            Number::List(items) if items.iter().any(|n| self.fit(n).is_some()) => {
+                Some(Number::List(
+                    items
+                        .iter()
+                        .map(|n| self.fit(n).unwrap_or_else(|| n.clone()))
+                        .collect(),
+                ))
+            }
+            Number::Matrix(m) if m.data().iter().any(|n| self.fit(n).is_some()) => {
+                Some(Number::Matrix(m.clone().map(|n| self.fit(&n).unwrap_or(n))))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Integer division in programmer mode truncates toward zero, as in C.
+pub(crate) fn divide(lhs: &BigInt, rhs: &BigInt) -> Result<Number, EngineError> {
+    if rhs.is_zero() {
+        return Err(EngineError::DivisionByZero);
+    }
+    Ok(Number::Integer(lhs / rhs))
+}
+
+impl fmt::Display for WordSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", if self.signed { "i" } else { "u" }, self.bits)
+    }
+}
+
+impl FromStr for WordSize {
+    type Err = String;
+
+    /// Accepts the display names, `i32` or `u8`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_ascii_lowercase();
+        let (signed, bits) = match lower.split_at_checked(1) {
+            Some(("i", bits)) => (true, bits),
+            Some(("u", bits)) => (false, bits),
+            _ => return Err(format!("Unknown word size '{}'", s)),
+        };
+        bits.parse()
+            .ok()
+            .and_then(|bits| WordSize::new(bits, signed).ok())
+            .ok_or_else(|| format!("Unknown word size '{}'", s))
+    }
+}
+
+impl From<WordSize> for String {
+    fn from(word: WordSize) -> String {
+        word.to_string()
+    }
+}
+
+impl TryFrom<String> for WordSize {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
@@ -1,4 +1,7 @@
+use crate::engine::angle::AngleUnit;
 use crate::engine::ast::Context;
+use crate::engine::errors::{EngineError, ParseError};
+use crate::engine::word::WordSize;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -194,7 +197,7 @@ impl AppSessionManager {
 
     pub fn backspace(&self) -> String {
         self.update_buffer(|buf| {
-            if buf.len() > 0 && buf != "0" {
+            if !buf.is_empty() && buf != "0" {
                 let mut new_buf = buf.clone();
                 new_buf.pop();
                 if new_buf.is_empty() {
@@ -277,18 +280,18 @@ impl AppSessionManager {
     }
 
     pub fn convert_to_hex(&self) -> String {
-        self.convert_base(16, "0x")
+        self.convert_base(16)
     }
 
     pub fn convert_to_bin(&self) -> String {
-        self.convert_base(2, "0b")
+        self.convert_base(2)
     }
 
     pub fn convert_to_oct(&self) -> String {
-        self.convert_base(8, "0o")
+        self.convert_base(8)
     }
 
-    fn convert_base(&self, radix: u32, prefix: &str) -> String {
+    fn convert_base(&self, radix: u32) -> String {
         let mut state = self.state.lock().unwrap();
         let id = state.current_session_id.clone();
 
@@ -296,13 +299,13 @@ impl AppSessionManager {
             let expr = session.buffer.clone();
             match crate::engine::evaluate(&expr, &mut session.context) {
                 Ok(crate::engine::types::Number::Integer(i)) => {
-                    let val = match radix {
-                        16 => format!("{:X}", i),
-                        8 => format!("{:o}", i),
-                        2 => format!("{:b}", i),
-                        _ => return "Error".to_string(),
+                    let formatted = match session.context.word_size {
+                        Some(word) => word.format_radix(&i, radix),
+                        None => crate::utils::format_radix(&i, radix),
+                    };
+                    let Some(result) = formatted else {
+                        return "Error".to_string();
                     };
-                    let result = format!("{}{}", prefix, val);
                     session.buffer = result.clone();
                     Self::save(&state);
                     result
@@ -323,6 +326,16 @@ impl AppSessionManager {
         }
     }
 
+    /// Parses the current buffer without evaluating it. Returns the syntax
+    /// error, if any, so the UI can highlight the offending input.
+    pub fn check_syntax(&self) -> Option<ParseError> {
+        let buffer = self.get_buffer();
+        match crate::engine::parser::parse(&buffer) {
+            Err(EngineError::ParserError(e)) => Some(e),
+            _ => None,
+        }
+    }
+
     pub fn get_last_result(&self) -> Option<String> {
         let state = self.state.lock().unwrap();
         state
@@ -346,6 +359,56 @@ impl AppSessionManager {
         Self::save(&state);
     }
 
+    /// Angle unit of the current session, used by the trigonometric functions.
+    pub fn get_angle_unit(&self) -> AngleUnit {
+        let state = self.state.lock().unwrap();
+        state
+            .sessions
+            .get(&state.current_session_id)
+            .map(|s| s.context.angle_unit)
+            .unwrap_or_default()
+    }
+
+    /// Changes the angle unit of the current session; it is saved with the
+    /// session's context.
+    pub fn set_angle_unit(&self, unit: AngleUnit) {
+        let mut state = self.state.lock().unwrap();
+        let id = state.current_session_id.clone();
+        if let Some(session) = state.sessions.get_mut(&id) {
+            session.context.angle_unit = unit;
+        }
+        Self::save(&state);
+    }
+
+    pub fn get_word_size(&self) -> Option<WordSize> {
+        let state = self.state.lock().unwrap();
+        state
+            .sessions
+            .get(&state.current_session_id)
+            .and_then(|s| s.context.word_size)
+    }
+
+    /// Switches the current session into programmer mode with the given
+    /// word, or back to unbounded integers with `None`.
+    pub fn set_word_size(&self, word_size: Option<WordSize>) {
+        let mut state = self.state.lock().unwrap();
+        let id = state.current_session_id.clone();
+        if let Some(session) = state.sessions.get_mut(&id) {
+            session.context.word_size = word_size;
+        }
+        Self::save(&state);
+    }
+
+    /// Whether the last evaluation in the current session wrapped a result
+    /// to fit its word size, for the UI's overflow indicator.
+    pub fn overflowed(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        state
+            .sessions
+            .get(&state.current_session_id)
+            .is_some_and(|s| s.context.overflowed())
+    }
+
     // Internal helper
     fn save(state: &AppState) {
         let sessions: Vec<Session> = state.sessions.values().cloned().collect();
@@ -13,6 +13,7 @@ pub fn format_float(val: f64) -> String {
 }
 
 use crate::engine::types::Number;
+use num_bigint::{BigInt, Sign};
 
 pub fn format_complex(c: Complex64) -> String {
     let re = c.re;
@@ -58,10 +59,58 @@ pub fn format_number(n: Number, use_decimals: bool) -> String {
             }
         }
         Number::Float(f) => format_float(f),
+        Number::BigFloat(b) => b.to_string(),
         Number::Complex(c) => format_complex(c),
+        Number::Boolean(b) => b.to_string(),
+        Number::List(items) => {
+            let items: Vec<String> = items
+                .into_iter()
+                .map(|item| format_number(item, use_decimals))
+                .collect();
+            format!("[{}]", items.join(", "))
+        }
+        Number::Text(s) => format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")),
+        Number::Quantity(q) => {
+            let unit = q.unit();
+            let value = format_number(q.value().clone(), use_decimals);
+            // Fractions and complex values are bracketed so that the text
+            // reads back as the same quantity: (1/2) km
+            if value.contains(['/', ' ']) {
+                format!("({}) {}", value, unit)
+            } else {
+                format!("{} {}", value, unit)
+            }
+        }
+        Number::Matrix(m) => {
+            let cols = m.cols();
+            let items: Vec<String> = m
+                .into_data()
+                .into_iter()
+                .map(|item| format_number(item, use_decimals))
+                .collect();
+            let rows: Vec<String> = items.chunks(cols).map(|row| row.join(", ")).collect();
+            // A trailing semicolon keeps a one-row matrix apart from a list
+            let end = if rows.len() == 1 { ";" } else { "" };
+            format!("[{}{}]", rows.join("; "), end)
+        }
     }
 }
 
+/// An integer in base 2, 8 or 16 with its literal prefix (`0b`, `0o`, `0x`).
+/// The sign goes before the prefix ("-0xFF"), which the lexer reads back as a
+/// negation.
+pub fn format_radix(i: &BigInt, radix: u32) -> Option<String> {
+    let magnitude = i.magnitude();
+    let (prefix, digits) = match radix {
+        16 => ("0x", format!("{:X}", magnitude)),
+        8 => ("0o", format!("{:o}", magnitude)),
+        2 => ("0b", format!("{:b}", magnitude)),
+        _ => return None,
+    };
+    let sign = if i.sign() == Sign::Minus { "-" } else { "" };
+    Some(format!("{}{}{}", sign, prefix, digits))
+}
+
 pub fn map_input_token(text: &str) -> &str {
     match text {
         "÷" => "/",
@@ -0,0 +1,134 @@
+use neocalc_core::engine::angle::AngleUnit;
+use neocalc_core::{Context, Number, evaluate};
+use num_bigint::BigInt;
+use num_rational::BigRational;
+
+fn in_unit(unit: AngleUnit) -> Context {
+    let mut context = Context::new();
+    context.angle_unit = unit;
+    context
+}
+
+fn int(n: i64) -> Number {
+    Number::Integer(BigInt::from(n))
+}
+
+fn ratio(n: i64, d: i64) -> Number {
+    Number::Rational(BigRational::new(BigInt::from(n), BigInt::from(d)))
+}
+
+fn approx(expr: &str, context: &mut Context, expected: f64) {
+    let value = evaluate(expr, context).unwrap().to_f64().unwrap();
+    assert!(
+        (value - expected).abs() < 1e-12,
+        "{} = {}, expected {}",
+        expr,
+        value,
+        expected
+    );
+}
+
+#[test]
+fn test_degree_mode() {
+    let mut context = in_unit(AngleUnit::Degrees);
+    assert_eq!(evaluate("sin(90)", &mut context), Ok(int(1)));
+    assert_eq!(evaluate("sin(30)", &mut context), Ok(ratio(1, 2)));
+    assert_eq!(evaluate("cos(60)", &mut context), Ok(ratio(1, 2)));
+    assert_eq!(evaluate("cos(180)", &mut context), Ok(int(-1)));
+    assert_eq!(evaluate("tan(45)", &mut context), Ok(int(1)));
+    assert!(evaluate("tan(90)", &mut context).is_err());
+    approx("sin(45)", &mut context, std::f64::consts::FRAC_1_SQRT_2);
+    approx("sin(1)", &mut context, 1f64.to_radians().sin());
+
+    // Inverse functions answer in degrees
+    assert_eq!(evaluate("asin(1/2)", &mut context), Ok(int(30)));
+    assert_eq!(evaluate("acos(0)", &mut context), Ok(int(90)));
+    assert_eq!(evaluate("atan(-1)", &mut context), Ok(int(-45)));
+    approx("asin(0.3)", &mut context, 0.3f64.asin().to_degrees());
+}
+
+#[test]
+fn test_gradian_mode() {
+    let mut context = in_unit(AngleUnit::Gradians);
+    assert_eq!(evaluate("sin(100)", &mut context), Ok(int(1)));
+    assert_eq!(evaluate("cos(200)", &mut context), Ok(int(-1)));
+    assert_eq!(evaluate("atan(1)", &mut context), Ok(int(50)));
+    assert_eq!(evaluate("asin(1/2)", &mut context), Ok(ratio(100, 3)));
+}
+
+#[test]
+fn test_radian_mode_is_the_default() {
+    let mut context = Context::new();
+    assert_eq!(context.angle_unit, AngleUnit::Radians);
+    assert_eq!(evaluate("sin(pi/6)", &mut context), Ok(ratio(1, 2)));
+    assert_eq!(evaluate("sin(0.0)", &mut context), Ok(int(0)));
+    // Tiny angles are not rounded to an exact zero
+    assert_eq!(
+        evaluate("sin(1e-16)", &mut context),
+        Ok(Number::Float(1e-16))
+    );
+    assert_eq!(
+        evaluate("tan(1e-17)", &mut context),
+        Ok(Number::Float(1e-17))
+    );
+    approx("asin(1/2)", &mut context, std::f64::consts::FRAC_PI_6);
+}
+
+#[test]
+fn test_angle_literals() {
+    // In radians, unit suffixes convert to radians
+    let mut context = Context::new();
+    assert_eq!(evaluate("sin(30°)", &mut context), Ok(ratio(1, 2)));
+    assert_eq!(evaluate("cos(180deg)", &mut context), Ok(int(-1)));
+    assert_eq!(evaluate("sin(100grad)", &mut context), Ok(int(1)));
+    approx("90°", &mut context, std::f64::consts::FRAC_PI_2);
+    approx("-45deg", &mut context, -std::f64::consts::FRAC_PI_4);
+
+    // In degrees, radian literals convert the other way
+    let mut context = in_unit(AngleUnit::Degrees);
+    approx("1rad", &mut context, 1f64.to_degrees());
+    assert_eq!(evaluate("200grad", &mut context), Ok(int(180)));
+    assert_eq!(evaluate("(45 + 45)°", &mut context), Ok(int(90)));
+
+    // The suffix must follow the number directly; otherwise it is a name
+    assert!(evaluate("30 deg", &mut context).is_err());
+}
+
+#[test]
+fn test_conversion_functions() {
+    let mut context = Context::new();
+    approx("deg(pi)", &mut context, 180.0);
+    approx("grad(pi/2)", &mut context, 100.0);
+    approx("rad(180)", &mut context, std::f64::consts::PI);
+    approx("deg(30°)", &mut context, 30.0);
+
+    // The functions take the same units in any angle mode
+    let mut context = in_unit(AngleUnit::Degrees);
+    approx("deg(pi)", &mut context, 180.0);
+    approx("grad(pi/2)", &mut context, 100.0);
+    approx("rad(180)", &mut context, std::f64::consts::PI);
+    assert_eq!(evaluate("sin(deg(pi/2))", &mut context), Ok(int(1)));
+
+    // Degrees, minutes and seconds
+    assert_eq!(evaluate("dms(30, 30)", &mut context), Ok(ratio(61, 2)));
+    assert_eq!(
+        evaluate("dms(10, 15, 36)", &mut context),
+        Ok(ratio(513, 50))
+    );
+    assert_eq!(evaluate("dms(30.5)", &mut context), Ok(ratio(303, 10)));
+    assert_eq!(
+        evaluate("dms(-10.26)", &mut context),
+        Ok(ratio(-101_536, 10_000))
+    );
+    let mut context = Context::new();
+    approx("dms(90, 0, 0)", &mut context, std::f64::consts::FRAC_PI_2);
+}
+
+#[test]
+fn test_angle_unit_names() {
+    assert_eq!("DEG".parse::<AngleUnit>(), Ok(AngleUnit::Degrees));
+    assert_eq!("rad".parse::<AngleUnit>(), Ok(AngleUnit::Radians));
+    assert_eq!("gon".parse::<AngleUnit>(), Ok(AngleUnit::Gradians));
+    assert!("turns".parse::<AngleUnit>().is_err());
+    assert_eq!(AngleUnit::Gradians.to_string(), "GRAD");
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use common::eval_str;
+use neocalc_core::{Context, EngineError, Number, evaluate};
+
+#[test]
+fn test_comparisons_and_logic_give_booleans() {
+    let mut context = Context::new();
+    assert_eq!(evaluate("2 > 1", &mut context), Ok(Number::Boolean(true)));
+    assert_eq!(evaluate("1 == 2", &mut context), Ok(Number::Boolean(false)));
+    for (expr, expected) in [
+        ("not(0)", "true"),
+        ("and(1, 2 > 3)", "false"),
+        ("or(0, 1)", "true"),
+        ("xor(1, 1, 1)", "true"),
+        ("TRUE()", "true"),
+        ("false", "false"),
+        ("TRUE", "true"),
+        ("and(True, FALSE)", "false"),
+        ("not(true)", "false"),
+        ("not([1, 0])", "[false, true]"),
+    ] {
+        assert_eq!(eval_str(expr, &mut context), expected, "{}", expr);
+    }
+}
+
+#[test]
+fn test_boolean_coercion() {
+    let mut context = Context::new();
+    // Booleans are not numbers: arithmetic and numeric functions, aggregates
+    // included, refuse them instead of reading them as 1 and 0
+    for expr in [
+        "true + true",
+        "(3 > 2) * 10",
+        "-true",
+        "true ^ 2",
+        "[1, 2] + true",
+        "true!",
+        "sqrt(true)",
+        "abs(false)",
+        "sum(true, true)",
+        "mean(true, false)",
+        "sum([1, 2 > 1])",
+        "norm.dist(true, 0, 1, true)",
+        "true < 2",
+    ] {
+        assert!(
+            matches!(
+                evaluate(expr, &mut context),
+                Err(EngineError::TypeMismatch(_, _))
+            ),
+            "{}",
+            expr
+        );
+    }
+    // They equal and order only against booleans, and the logic functions
+    // and flags such as `cumulative` take them
+    assert_eq!(eval_str("true == 1", &mut context), "false");
+    assert_eq!(eval_str("true == (1 < 2)", &mut context), "true");
+    assert_eq!(eval_str("false < true", &mut context), "true");
+    assert_eq!(eval_str("xor(true, false)", &mut context), "true");
+    assert_eq!(eval_str("norm.s.dist(0, true)", &mut context), "0.5");
+    assert_eq!(eval_str("[1, 2] == [1, 2]", &mut context), "true");
+    assert_eq!(
+        eval_str("if(1 > 0, \"yes\", \"no\")", &mut context),
+        "\"yes\""
+    );
+    assert_eq!(eval_str("\"is \" & (1 < 2)", &mut context), "\"is true\"");
+    for expr in ["true = 0", "FALSE = 1"] {
+        assert!(matches!(
+            evaluate(expr, &mut context),
+            Err(EngineError::ConstantAssignment(_))
+        ));
+    }
+}
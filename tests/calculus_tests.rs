@@ -0,0 +1,173 @@
+mod common;
+
+use common::eval_str;
+use neocalc_core::{Context, EngineError, Number, evaluate};
+
+// Checks each float result to a relative tolerance
+fn approx_all(context: &mut Context, cases: &[(&str, f64)]) {
+    for (expr, expected) in cases {
+        let value = match evaluate(expr, context) {
+            Ok(Number::Float(f)) => f,
+            other => panic!("{} gave {:?}", expr, other),
+        };
+        assert!(
+            (value - expected).abs() <= 1e-12 * expected.abs(),
+            "{} = {} instead of {}",
+            expr,
+            value,
+            expected
+        );
+    }
+}
+
+#[test]
+fn test_derivatives() {
+    let mut context = Context::new();
+    evaluate("f(x) = x^2 + 1", &mut context).unwrap();
+    approx_all(
+        &mut context,
+        &[
+            ("deriv(sin, 0)", 1.0),
+            ("deriv(f, 3)", 6.0),
+            ("DERIV(x^3, x, 2)", 12.0),
+            ("deriv(e^x, x, 1)", std::f64::consts::E),
+            ("deriv(atan, 1)", 0.5),
+            ("deriv(t * f(t), t, -2)", 13.0),
+        ],
+    );
+    // The variable is only bound inside the form
+    evaluate("x = 10", &mut context).unwrap();
+    approx_all(&mut context, &[("deriv(x^2, x, 1) + x", 12.0)]);
+}
+
+#[test]
+fn test_integrals() {
+    let mut context = Context::new();
+    approx_all(
+        &mut context,
+        &[
+            ("integrate(sin, 0, pi)", 2.0),
+            ("integrate(x^2, x, 1, 0)", -1.0 / 3.0),
+            ("integrate(1/sqrt(x), x, 0, 1)", 2.0),
+            ("integrate(sin(x)/x, x, 1, 1e4)", 0.6248084750187789),
+            ("integrate(e^(-(x^2)), x, -inf, inf)", 1.772453850905516),
+            (
+                "integrate(1/(1 + x^2), x, -1/0, 0)",
+                std::f64::consts::FRAC_PI_2,
+            ),
+            ("integrate(x^2 * e^(-x), x, 0, 1/0)", 2.0),
+        ],
+    );
+    assert_eq!(eval_str("integrate(sin, 1, 1)", &mut context), "0");
+    // The error estimate of a converged integral is small but reported
+    for expr in [
+        "integrate.err(sin, 0, pi)",
+        "integrate.err(x^2 * e^(-x), x, 0, inf)",
+    ] {
+        match evaluate(expr, &mut context) {
+            Ok(Number::Float(error)) => assert!((0.0..1e-10).contains(&error), "{}", expr),
+            other => panic!("{} gave {:?}", expr, other),
+        }
+    }
+    // A divergent integral is an error, not a large number
+    assert!(matches!(
+        evaluate("integrate(1/x, x, 0, 1)", &mut context),
+        Err(EngineError::DomainError(_))
+    ));
+    // Estimates that overflow are reported as divergence rather than NaN
+    for expr in ["integrate(x, x, 0, inf)", "integrate(sin(x), x, 0, inf)"] {
+        match evaluate(expr, &mut context) {
+            Err(EngineError::DomainError(message)) => {
+                assert!(!message.contains("NaN"), "{}: {}", expr, message)
+            }
+            other => panic!("{} gave {:?}", expr, other),
+        }
+    }
+    assert!(matches!(
+        evaluate("integrate(sqrt(x), x, -1, 1)", &mut context),
+        Err(EngineError::TypeMismatch(_, _))
+    ));
+}
+
+#[test]
+fn test_sums_and_products_are_exact() {
+    let mut context = Context::new();
+    evaluate("f(n) = 1/n", &mut context).unwrap();
+    evaluate("k = 7", &mut context).unwrap();
+    let cases = [
+        ("sum(k^2, k, 1, 10)", "385"),
+        ("sum(f, 1, 4)", "25/12"),
+        ("prod(k, k, 1, 20)", "2432902008176640000"),
+        ("PROD(1 - 1/j^2, j, 2, 10)", "11/20"),
+        ("sum(sum(j * k, j, 1, k), k, 1, 3)", "25"),
+        ("sum(k, k, 5, 1)", "0"),
+        ("prod(k, k, 5, 1)", "1"),
+        ("sum(k, k, 1, 1e3)", "500500"),
+        ("k", "7"),
+    ];
+    for (expr, expected) in cases {
+        assert_eq!(eval_str(expr, &mut context), expected, "{}", expr);
+    }
+    for expr in ["sum(k, k, 1, 2.5)", "sum(k, k, 1, 2i)"] {
+        assert!(
+            matches!(
+                evaluate(expr, &mut context),
+                Err(EngineError::DomainError(_))
+            ),
+            "{}",
+            expr
+        );
+    }
+    // The variable shadows a constant of the same name, as a parameter does
+    let cases = [
+        ("sum(i, i, 1, 10)", "55"),
+        ("prod(e, e, 1, 4)", "24"),
+        ("sum(pi^2, pi, 1, 3)", "14"),
+    ];
+    for (expr, expected) in cases {
+        assert_eq!(eval_str(expr, &mut context), expected, "{}", expr);
+    }
+    approx_all(&mut context, &[("deriv(e^2, e, 3)", 6.0)]);
+    assert_eq!(eval_str("i^2", &mut context), "-1");
+}
+
+#[test]
+fn test_sums_and_products_of_values() {
+    let mut context = Context::new();
+    evaluate("k = 7", &mut context).unwrap();
+    evaluate("xs = [1, 2, 3, 4]", &mut context).unwrap();
+    evaluate("x = 1", &mut context).unwrap();
+    evaluate("y = 2", &mut context).unwrap();
+    // Calls that are not a series total their arguments and list elements
+    let cases = [
+        ("sum(1, 2, 3)", "6"),
+        ("sum([1, 2, 3])", "6"),
+        ("sum(xs, 10)", "20"),
+        ("SUM(1/2, 1/3)", "5/6"),
+        ("prod(2, 3, 4)", "24"),
+        ("prod(xs)", "24"),
+        ("sum(2, 1, 3)", "6"),
+        ("sum(k, 2, 1, 3)", "13"),
+        ("sum(k, k, 1, 3, 4)", "22"),
+        ("sum(5)", "5"),
+        // A defined variable or a constant that the first argument does
+        // not use is a value, not the variable of a series
+        ("sum(x, y, 3, 4)", "10"),
+        ("prod(x, y, 3, 4)", "24"),
+        ("sum(x, pi, 3, 4) == 8 + pi", "true"),
+        // One the first argument uses is still bound, as is a new name
+        ("sum(y^2, y, 1, 3)", "14"),
+        ("sum(1, n, 1, 10)", "10"),
+    ];
+    for (expr, expected) in cases {
+        assert_eq!(eval_str(expr, &mut context), expected, "{}", expr);
+    }
+    assert!(matches!(
+        evaluate("sum(undefined_function, 1, 3)", &mut context),
+        Err(EngineError::UndefinedVariable(_))
+    ));
+    assert!(matches!(
+        evaluate("sum([])", &mut context),
+        Err(EngineError::ArgumentCount { .. })
+    ));
+}
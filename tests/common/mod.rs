@@ -0,0 +1,15 @@
+//! Helpers shared by the integration tests. Each test crate uses only some.
+#![allow(dead_code)]
+
+use neocalc_core::utils::format_number;
+use neocalc_core::{Context, evaluate};
+
+/// Evaluates `expr` and formats the result as the app shows it, exactly.
+pub fn eval_str(expr: &str, context: &mut Context) -> String {
+    format_number(evaluate(expr, context).unwrap(), false)
+}
+
+/// Like `eval_str`, with the result written as a decimal.
+pub fn eval_decimal(expr: &str, context: &mut Context) -> String {
+    format_number(evaluate(expr, context).unwrap(), true)
+}
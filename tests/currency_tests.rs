@@ -0,0 +1,142 @@
+mod common;
+
+use common::eval_str;
+use neocalc_core::engine::currency::RateTable;
+use neocalc_core::{Context, EngineError, Number, evaluate};
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const RATES: &str = r#"{
+    "base": "USD",
+    "timestamp": 1760572800,
+    "rates": {"EUR": 0.8, "BRL": "5.5", "JPY": 150}
+}"#;
+
+fn with_rates() -> Context {
+    let mut context = Context::new();
+    context.rates = Some(RateTable::from_json(RATES).unwrap());
+    context
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+#[test]
+fn test_conversions_are_exact() {
+    let mut context = with_rates();
+    let cases = [
+        ("100 USD in EUR", "80 EUR"),
+        ("100 EUR to USD", "125 USD"),
+        ("100 BRL to EUR", "(160/11) EUR"),
+        ("1500 JPY in BRL", "55 BRL"),
+        ("10 USD + 5 EUR", "(65/4) USD"),
+        ("[1, 2] * 1 EUR to USD", "[(5/4) USD, (5/2) USD]"),
+        ("20 EUR / 1 kg * 500 g to USD", "(25/2) USD"),
+    ];
+    for (expr, expected) in cases {
+        assert_eq!(eval_str(expr, &mut context), expected, "{}", expr);
+    }
+    assert_eq!(
+        evaluate("1 EUR > 1 USD", &mut context),
+        Ok(Number::from(true))
+    );
+    assert_eq!(eval_str("10 EUR / 1 USD", &mut context), "25/2");
+}
+
+#[test]
+fn test_single_currency_needs_no_rates() {
+    let mut context = Context::new();
+    assert_eq!(eval_str("5 USD + 3 USD", &mut context), "8 USD");
+    assert_eq!(eval_str("12 USD / 3 USD", &mut context), "4");
+    assert_eq!(eval_str("3 USD/kg * 2 kg", &mut context), "6 USD");
+    assert_eq!(
+        eval_str("60 USD / 2 h to USD/min", &mut context),
+        "(1/2) USD/min"
+    );
+    assert!(matches!(
+        evaluate("1 USD + 1 m", &mut context),
+        Err(EngineError::DimensionMismatch(_))
+    ));
+}
+
+#[test]
+fn test_missing_and_stale_rates() {
+    let mut context = Context::new();
+    assert!(matches!(
+        evaluate("1 USD in EUR", &mut context),
+        Err(EngineError::ExchangeRate(_))
+    ));
+    let mut context = with_rates();
+    assert!(matches!(
+        evaluate("1 USD in GBP", &mut context),
+        Err(EngineError::ExchangeRate(_))
+    ));
+
+    let mut table = RateTable::from_json(RATES).unwrap();
+    table.max_age = Some(Duration::from_secs(3600));
+    context.rates = Some(table);
+    assert!(matches!(
+        evaluate("1 USD in EUR", &mut context),
+        Err(EngineError::ExchangeRate(_))
+    ));
+    // Same-currency arithmetic does not look at the table
+    assert_eq!(eval_str("1 USD + 1 USD", &mut context), "2 USD");
+
+    let mut fresh = RateTable::new("EUR", now()).unwrap();
+    fresh.max_age = Some(Duration::from_secs(3600));
+    fresh
+        .set_rate("USD", BigRational::new(BigInt::from(11), BigInt::from(10)))
+        .unwrap();
+    context.rates = Some(fresh);
+    assert_eq!(eval_str("10 EUR in USD", &mut context), "11 USD");
+}
+
+#[test]
+fn test_rate_table_validation() {
+    for json in [
+        "not json",
+        r#"{"timestamp": 1, "rates": {}}"#,
+        r#"{"base": "USD", "rates": {}}"#,
+        r#"{"base": "XXX", "timestamp": 1, "rates": {}}"#,
+        r#"{"base": "USD", "timestamp": 1, "rates": {"EUR": -1}}"#,
+        r#"{"base": "USD", "timestamp": 1, "rates": {"EUR": "abc"}}"#,
+        r#"{"base": "USD", "timestamp": 1, "rates": {"EUR": true}}"#,
+    ] {
+        assert!(
+            matches!(
+                RateTable::from_json(json),
+                Err(EngineError::ExchangeRate(_))
+            ),
+            "{}",
+            json
+        );
+    }
+    let table = RateTable::from_json(RATES).unwrap();
+    assert_eq!(table.base(), "USD");
+    assert_eq!(table.timestamp(), 1760572800);
+    assert_eq!(
+        table.rate("EUR"),
+        Some(BigRational::new(BigInt::from(4), BigInt::from(5)))
+    );
+    assert_eq!(table.rate("GBP"), None);
+}
+
+#[test]
+fn test_rates_load_from_file_and_persist() {
+    let path = std::env::temp_dir().join(format!("neocalc-rates-{}.json", std::process::id()));
+    std::fs::write(&path, RATES).unwrap();
+    let table = RateTable::from_file(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert!(RateTable::from_file(&path).is_err());
+
+    let mut context = Context::new();
+    context.rates = Some(table.unwrap());
+    let json = serde_json::to_string(&context).unwrap();
+    let mut restored: Context = serde_json::from_str(&json).unwrap();
+    assert_eq!(eval_str("100 USD in EUR", &mut restored), "80 EUR");
+}
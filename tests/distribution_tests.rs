@@ -0,0 +1,166 @@
+mod common;
+
+use common::eval_str;
+use neocalc_core::{Context, EngineError, Number, evaluate};
+
+// Checks each float result to a relative tolerance
+fn approx_all(cases: &[(&str, f64)]) {
+    let mut context = Context::new();
+    for (expr, expected) in cases {
+        let value = match evaluate(expr, &mut context) {
+            Ok(Number::Float(f)) => f,
+            other => panic!("{} gave {:?}", expr, other),
+        };
+        assert!(
+            (value - expected).abs() <= 1e-12 * expected.abs(),
+            "{} = {} instead of {}",
+            expr,
+            value,
+            expected
+        );
+    }
+}
+
+#[test]
+fn test_continuous_distributions() {
+    approx_all(&[
+        ("norm.dist(1.5, 1, 2, true)", 0.5987063256829237),
+        ("NORM.DIST(1.5, 1, 2, false)", 0.1933340584014246),
+        ("norm.s.dist(-10, true)", 7.619853024160526e-24),
+        ("t.dist(2, 5, true)", 0.9490302605850708),
+        ("t.dist(2, 5, false)", 0.06509031032621647),
+        ("T.DIST.2T(2.5, 10)", 0.031446844236608804),
+        ("t.dist.rt(-2, 5)", 0.9490302605850708),
+        ("chisq.dist(3, 4, true)", 0.4421745996289254),
+        ("chisq.dist(3, 4, false)", 0.16734762011132237),
+        ("chisq.dist.rt(100, 10)", 5.449701982920529e-17),
+        ("f.dist(2, 3, 7, true)", 0.7973063575133491),
+        ("f.dist(2, 3, 7, false)", 0.14635695020189792),
+        ("f.dist.rt(2, 3, 7)", 0.20269364248665092),
+        ("expon.dist(0.5, 2, true)", 0.6321205588285577),
+        ("expon.dist(0.5, 2, false)", 0.7357588823428847),
+    ]);
+}
+
+#[test]
+fn test_inverses() {
+    approx_all(&[
+        ("norm.s.inv(0.975)", 1.959963984540054),
+        ("norm.s.inv(1e-20)", -9.262340089798408),
+        ("norm.inv(0.5, 3, 2)", 3.0),
+        ("t.inv(0.25, 3) + t.inv(0.75, 3) + 1", 1.0),
+        ("T.INV(0.05, 10)", -1.8124611228116763),
+        ("t.inv.2t(0.05, 10)", 2.228138851986275),
+        ("chisq.inv(0.95, 3)", 7.81472790325118),
+        ("chisq.inv.rt(1e-10, 2)", 46.051701859880914),
+        ("f.inv(0.95, 3, 7)", 4.346831399907818),
+        ("expon.inv(0.5, 2)", std::f64::consts::LN_2 / 2.0),
+        ("confidence.norm(0.05, 2.5, 50)", 0.692951912174839),
+        ("confidence.t(0.05, 2.5, 50)", 0.7104921387393248),
+    ]);
+    // Inverses round-trip through the distribution functions
+    approx_all(&[
+        ("f.dist.rt(f.inv.rt(0.01, 4, 9), 4, 9)", 0.01),
+        ("t.dist(t.inv(0.3, 2.5), 2.5, true)", 0.3),
+        ("norm.s.dist(norm.s.inv(0.9), true)", 0.9),
+    ]);
+}
+
+#[test]
+fn test_discrete_distributions() {
+    let mut context = Context::new();
+    // Exact probabilities stay exact
+    assert_eq!(
+        eval_str("binom.dist(3, 10, 1/2, false)", &mut context),
+        "15/128"
+    );
+    assert_eq!(
+        eval_str("binom.dist(3, 10, 1/2, true)", &mut context),
+        "11/64"
+    );
+    assert_eq!(eval_str("binom.dist(2, 2, 1, false)", &mut context), "1");
+    assert_eq!(
+        eval_str("binom.dist(3, 10, 0.5, false)", &mut context),
+        "15/128"
+    );
+    assert_eq!(
+        eval_str("binom.dist(2, 3, 0.25, true)", &mut context),
+        "63/64"
+    );
+    assert_eq!(eval_str("binom.inv(10, 0.5, 0.5)", &mut context), "5");
+    assert_eq!(eval_str("binom.inv(10, 0.3, 0.65)", &mut context), "4");
+    assert_eq!(eval_str("poisson.inv(0.75, 2.5)", &mut context), "3");
+    assert_eq!(eval_str("poisson.inv(0.76, 2.5)", &mut context), "4");
+    approx_all(&[
+        ("binom.dist(3, 10, 0.3, false)", 0.266827932),
+        ("binom.dist(3, 10, 0.3, true)", 0.6496107184),
+        ("poisson.dist(3, 2.5, false)", 0.21376301724973645),
+        ("poisson.dist(3, 2.5, true)", 0.757576133133066),
+    ]);
+}
+
+#[test]
+fn test_hypothesis_tests() {
+    let mut context = Context::new();
+    evaluate("a = [3, 4, 5, 8, 9, 1, 2, 4, 5]", &mut context).unwrap();
+    evaluate("b = [6, 19, 3, 2, 14, 4, 5, 17, 1]", &mut context).unwrap();
+    evaluate("z = [3, 6, 7, 8, 6, 5, 4, 2, 1, 9]", &mut context).unwrap();
+    let cases = [
+        ("t.test(a, b, 2, 1)", 0.1960157849252821),
+        ("TTEST(a, b, 2, 2)", 0.19199588676039622),
+        ("t.test(a, b, 1, 3)", 0.10114696168433895),
+        ("z.test(z, 4)", 0.09057419685136376),
+        ("ztest(z, 6)", 0.8630433891295299),
+        ("z.test(z, 4, 2)", 0.04099516050019149),
+    ];
+    for (expr, expected) in cases {
+        match evaluate(expr, &mut context) {
+            Ok(Number::Float(f)) => assert!(
+                (f - expected).abs() <= 1e-12 * expected,
+                "{} = {} instead of {}",
+                expr,
+                f,
+                expected
+            ),
+            other => panic!("{} gave {:?}", expr, other),
+        }
+    }
+    assert!(matches!(
+        evaluate("t.test(a, [1, 2], 2, 1)", &mut context),
+        Err(EngineError::DimensionMismatch(_))
+    ));
+    assert!(matches!(
+        evaluate("z.test([2, 2, 2], 1)", &mut context),
+        Err(EngineError::DivisionByZero)
+    ));
+}
+
+#[test]
+fn test_domain_errors_and_lists() {
+    let mut context = Context::new();
+    for expr in [
+        "norm.dist(1, 0, 0, true)",
+        "norm.s.inv(1)",
+        "t.inv(0.5, -1)",
+        "chisq.dist(-1, 2, true)",
+        "binom.dist(11, 10, 0.5, true)",
+        "binom.dist(1.5, 10, 0.5, true)",
+        "poisson.inv(1, 3)",
+        "t.test([1, 2], [3, 4], 3, 1)",
+    ] {
+        assert!(
+            matches!(
+                evaluate(expr, &mut context),
+                Err(EngineError::DomainError(_))
+            ),
+            "{}",
+            expr
+        );
+    }
+    // Scalar arguments map over lists like other functions
+    assert_eq!(
+        eval_str("binom.dist([0, 1, 2], 2, 1/2, false)", &mut context),
+        "[1/4, 1/2, 1/4]"
+    );
+    assert_eq!(eval_str("t.inv([0.5], 3)", &mut context), "[0]");
+}
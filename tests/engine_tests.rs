@@ -1,10 +1,7 @@
-use neocalc_core::{
-    evaluate,
-    Context,
-    Number,
-};
+use neocalc_core::{Context, Number, evaluate};
 use num_bigint::BigInt;
 use num_rational::BigRational;
+use std::time::Instant;
 
 #[test]
 fn test_sanity_arithmetic() {
@@ -139,14 +136,14 @@ fn test_variables_and_functions() {
 
     // Assignment
     evaluate("x = 10", &mut context).unwrap();
-    
+
     // Parens
     evaluate("(x)", &mut context).unwrap(); // Should equal 10
 
     // Function call (undefined)
     let res = evaluate("g(x)", &mut context);
     match res {
-        Err(neocalc_core::EngineError::UnknownFunction(_)) => (),
+        Err(neocalc_core::EngineError::UnknownFunction(..)) => (),
         _ => panic!("Expected UnknownFunction for g(x), got {:?}", res),
     }
 
@@ -156,17 +153,370 @@ fn test_variables_and_functions() {
     // Check if it exists
     let res = evaluate("h(5)", &mut context).unwrap();
     if let Number::Integer(i) = res {
-         assert_eq!(i, BigInt::from(10));
+        assert_eq!(i, BigInt::from(10));
     } else {
         panic!("Expected Integer 10 for h(5)");
     }
-    
+
     // Function definition with usage
     evaluate("f(x) = x^2", &mut context).unwrap();
     let res = evaluate("f(5)", &mut context).unwrap();
     if let Number::Integer(i) = res {
-         assert_eq!(i, BigInt::from(25));
+        assert_eq!(i, BigInt::from(25));
     } else {
-         panic!("Expected Integer 25 for f(5)");
+        panic!("Expected Integer 25 for f(5)");
+    }
+}
+
+fn eval_int(expression: &str, context: &mut Context) -> BigInt {
+    match evaluate(expression, context) {
+        Ok(Number::Integer(i)) => i,
+        other => panic!("Expected Integer for {:?}, got {:?}", expression, other),
+    }
+}
+
+fn eval_bool(expression: &str, context: &mut Context) -> bool {
+    match evaluate(expression, context) {
+        Ok(Number::Boolean(b)) => b,
+        other => panic!("Expected Boolean for {:?}, got {:?}", expression, other),
+    }
+}
+
+#[test]
+fn test_comparison_operators() {
+    let mut context = Context::new();
+    assert!(eval_bool("3 > 2", &mut context));
+    assert!(!eval_bool("3 < 2", &mut context));
+    assert!(eval_bool("2 <= 2", &mut context));
+    assert!(!eval_bool("2 >= 3", &mut context));
+    assert!(eval_bool("1/3 == 2/6", &mut context));
+    assert!(eval_bool("1/3 != 0.5", &mut context));
+
+    // Comparisons bind looser than arithmetic
+    assert!(eval_bool("1 + 2 == 3", &mut context));
+    assert!(eval_bool("2 * 3 > 5 + 0", &mut context));
+
+    // Exact comparison does not suffer from float rounding
+    assert!(eval_bool("10^20 + 1 > 10^20", &mut context));
+
+    // Usable as IF conditions
+    evaluate("x = 5", &mut context).unwrap();
+    assert_eq!(eval_int("if(x > 3, 1, 2)", &mut context), BigInt::from(1));
+}
+
+#[test]
+fn test_comparison_complex_operands() {
+    let mut context = Context::new();
+    let res = evaluate("sqrt(-1) < 1", &mut context);
+    assert!(matches!(
+        res,
+        Err(neocalc_core::EngineError::TypeMismatch(_, _))
+    ));
+
+    // Equality is still defined for complex numbers
+    assert!(eval_bool("sqrt(-1) == sqrt(-1)", &mut context));
+}
+
+#[test]
+fn test_lazy_if() {
+    let mut context = Context::new();
+
+    // The untaken branch is never evaluated
+    evaluate("x = 0", &mut context).unwrap();
+    assert_eq!(eval_int("if(x, 1/x, 7)", &mut context), BigInt::from(7));
+    assert_eq!(
+        eval_int("if(1, 3, undefined_var)", &mut context),
+        BigInt::from(3)
+    );
+
+    // Recursive user functions terminate at the base case
+    evaluate("f(n) = if(n, n * f(n - 1), 1)", &mut context).unwrap();
+    assert_eq!(eval_int("f(10)", &mut context), BigInt::from(3628800));
+}
+
+#[test]
+fn test_short_circuit_and_or() {
+    let mut context = Context::new();
+    assert!(!eval_bool("and(0, undefined_var)", &mut context));
+    assert!(eval_bool("OR(1, undefined_var)", &mut context));
+    assert!(eval_bool("and(1, 2 > 1)", &mut context));
+
+    // Arguments that are reached still report their errors
+    let res = evaluate("and(1, undefined_var)", &mut context);
+    assert!(matches!(
+        res,
+        Err(neocalc_core::EngineError::UndefinedVariable(_))
+    ));
+}
+
+#[test]
+fn test_iferror() {
+    let mut context = Context::new();
+    assert_eq!(
+        eval_int("iferror(undefined_var, 42)", &mut context),
+        BigInt::from(42)
+    );
+    assert_eq!(
+        eval_int("IFERROR(1 + 1, 42)", &mut context),
+        BigInt::from(2)
+    );
+    assert_eq!(
+        eval_int("iferror(fact(-1), -1)", &mut context),
+        BigInt::from(-1)
+    );
+    // Division by zero gives inf or NaN instead of an error, which counts too
+    assert_eq!(eval_int("iferror(1/0, 7)", &mut context), BigInt::from(7));
+    assert_eq!(eval_int("iferror(5 % 0, 7)", &mut context), BigInt::from(7));
+    assert_eq!(eval_int("iferror(0.0/0, 7)", &mut context), BigInt::from(7));
+}
+
+#[test]
+fn test_integer_literal_forms() {
+    let mut context = Context::new();
+    assert_eq!(eval_int("0o17", &mut context), BigInt::from(15));
+    assert_eq!(eval_int("0x1f", &mut context), BigInt::from(31));
+    assert_eq!(eval_int("1_000_000", &mut context), BigInt::from(1_000_000));
+    assert_eq!(eval_int("0xFF_FF", &mut context), BigInt::from(0xFFFF));
+    assert_eq!(
+        eval_int("0b1010_1010", &mut context),
+        BigInt::from(0b1010_1010)
+    );
+    assert_eq!(eval_int("0o7_7", &mut context), BigInt::from(63));
+    assert_eq!(eval_int("36#ZZ", &mut context), BigInt::from(1295));
+    assert_eq!(eval_int("3#1201", &mut context), BigInt::from(46));
+    assert_eq!(eval_int("16#ff_ff", &mut context), BigInt::from(0xFFFF));
+
+    match evaluate("1_000.5", &mut context) {
+        Ok(Number::Float(f)) => assert_eq!(f, 1000.5),
+        other => panic!("Expected Float, got {:?}", other),
+    }
+
+    // Digits outside the radix and radixes outside 2..=36 are rejected, and
+    // so are separators that do not sit between two digits
+    for bad in [
+        "0o8",
+        "0o78",
+        "0b102",
+        "2#102",
+        "37#1",
+        "1#0",
+        "1_",
+        "1__0",
+        "0xFF_",
+        "1_.5",
+        "1._5",
+        "16#ff__ff",
+    ] {
+        assert!(
+            matches!(
+                evaluate(bad, &mut context),
+                Err(neocalc_core::EngineError::ParserError(_))
+            ),
+            "{} should not parse",
+            bad
+        );
+    }
+}
+
+#[test]
+fn test_constants() {
+    let mut context = Context::new();
+    match evaluate("pi", &mut context) {
+        Ok(Number::Float(f)) => assert_eq!(f, std::f64::consts::PI),
+        other => panic!("Expected Float pi, got {:?}", other),
+    }
+    match evaluate("2tau - 4pi", &mut context) {
+        Ok(Number::Float(f)) => assert_eq!(f, 0.0),
+        other => panic!("Expected Float 0, got {:?}", other),
+    }
+    match evaluate("phi^2 - phi", &mut context) {
+        Ok(Number::Float(f)) => assert!((f - 1.0).abs() < 1e-12),
+        other => panic!("Expected Float 1, got {:?}", other),
+    }
+
+    // Multiples of π/2 are exact in trig functions
+    assert_eq!(eval_int("sin(pi)", &mut context), BigInt::from(0));
+    assert_eq!(eval_int("cos(pi)", &mut context), BigInt::from(-1));
+    assert_eq!(eval_int("sin(3pi/2)", &mut context), BigInt::from(-1));
+    assert_eq!(eval_int("tan(-pi)", &mut context), BigInt::from(0));
+    assert!(evaluate("tan(pi/2)", &mut context).is_err());
+}
+
+#[test]
+fn test_constants_are_read_only() {
+    let mut context = Context::new();
+    let res = evaluate("pi = 3", &mut context);
+    assert!(matches!(
+        res,
+        Err(neocalc_core::EngineError::ConstantAssignment(_))
+    ));
+
+    // Explicit shadowing through a function parameter is allowed
+    evaluate("f(e) = e * 2", &mut context).unwrap();
+    assert_eq!(eval_int("f(21)", &mut context), BigInt::from(42));
+    match evaluate("e", &mut context) {
+        Ok(Number::Float(f)) => assert_eq!(f, std::f64::consts::E),
+        other => panic!("Expected Float e, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_imaginary_literals() {
+    let mut context = Context::new();
+    match evaluate("2 + 4i", &mut context) {
+        Ok(Number::Complex(c)) => assert_eq!((c.re, c.im), (2.0, 4.0)),
+        other => panic!("Expected Complex, got {:?}", other),
+    }
+    match evaluate("1.5i * 2i", &mut context) {
+        Ok(Number::Complex(c)) => assert_eq!((c.re, c.im), (-3.0, 0.0)),
+        other => panic!("Expected Complex, got {:?}", other),
+    }
+    match evaluate("i^2", &mut context) {
+        Ok(Number::Complex(c)) => assert!((c.re + 1.0).abs() < 1e-12 && c.im.abs() < 1e-12),
+        other => panic!("Expected Complex, got {:?}", other),
+    }
+    // Complex results format as expressions that parse back
+    let shown = neocalc_core::utils::format_number(evaluate("3 - 2i", &mut context).unwrap(), true);
+    assert_eq!(shown, "3 - 2i");
+    assert_eq!(
+        evaluate(&shown, &mut context),
+        evaluate("3 - 2i", &mut context)
+    );
+    match evaluate("2.5e3i + 1_000i", &mut context) {
+        Ok(Number::Complex(c)) => assert_eq!((c.re, c.im), (0.0, 3500.0)),
+        other => panic!("Expected Complex, got {:?}", other),
+    }
+    // A name starting with 'i' after a number is a product, not an imaginary literal
+    evaluate("index = 3", &mut context).unwrap();
+    assert_eq!(
+        evaluate("2index", &mut context),
+        Ok(Number::Integer(BigInt::from(6)))
+    );
+    // Nor is `in` right after a number, which is two inches
+    assert!(matches!(
+        evaluate("2in", &mut context),
+        Ok(Number::Quantity(_))
+    ));
+}
+
+fn rational(n: i64, d: i64) -> Number {
+    Number::Rational(BigRational::new(BigInt::from(n), BigInt::from(d)))
+}
+
+#[test]
+fn test_exact_roots() {
+    let mut context = Context::new();
+    assert_eq!(eval_int("sqrt(16)", &mut context), BigInt::from(4));
+    assert_eq!(evaluate("sqrt(9/4)", &mut context), Ok(rational(3, 2)));
+    assert_eq!(
+        eval_int("sqrt(10^40)", &mut context),
+        BigInt::from(10).pow(20)
+    );
+    assert_eq!(eval_int("nthroot(27, 3)", &mut context), BigInt::from(3));
+    assert_eq!(eval_int("nthroot(-32, 5)", &mut context), BigInt::from(-2));
+    assert_eq!(
+        evaluate("nthroot(1/8, 3)", &mut context),
+        Ok(rational(1, 2))
+    );
+
+    // Inexact roots of non-negative reals are real floats
+    match evaluate("sqrt(2)", &mut context) {
+        Ok(Number::Float(f)) => assert_eq!(f, std::f64::consts::SQRT_2),
+        other => panic!("Expected Float, got {:?}", other),
+    }
+    match evaluate("nthroot(-2, 3)", &mut context) {
+        Ok(Number::Float(f)) => assert!((f + 2f64.cbrt()).abs() < 1e-15),
+        other => panic!("Expected Float, got {:?}", other),
+    }
+    // Even roots of negative numbers are complex
+    assert!(matches!(
+        evaluate("sqrt(-4)", &mut context),
+        Ok(Number::Complex(_))
+    ));
+    assert!(matches!(
+        evaluate("nthroot(-16, 4)", &mut context),
+        Ok(Number::Complex(_))
+    ));
+    assert!(evaluate("nthroot(8, 0)", &mut context).is_err());
+    assert!(evaluate("nthroot(8, 1.5)", &mut context).is_err());
+}
+
+#[test]
+fn test_rational_powers() {
+    let mut context = Context::new();
+    assert_eq!(eval_int("8^(1/3)", &mut context), BigInt::from(2));
+    assert_eq!(eval_int("(-8)^(1/3)", &mut context), BigInt::from(-2));
+    assert_eq!(eval_int("(-8)^(2/3)", &mut context), BigInt::from(4));
+    assert_eq!(evaluate("(1/4)^(1/2)", &mut context), Ok(rational(1, 2)));
+    assert_eq!(evaluate("(4/9)^(-3/2)", &mut context), Ok(rational(27, 8)));
+    assert_eq!(eval_int("16^0.75", &mut context), BigInt::from(8));
+    assert_eq!(evaluate("(2/3)^3", &mut context), Ok(rational(8, 27)));
+
+    // Only bases without an exact root fall back to floating point
+    let root = evaluate("2^(1/2)", &mut context).unwrap().to_f64().unwrap();
+    assert!((root - std::f64::consts::SQRT_2).abs() < 1e-15);
+}
+
+#[test]
+fn test_large_exact_powers_are_fast() {
+    let mut context = Context::new();
+    // Results of millions of bits skip the gcd a reduced fraction never needs
+    let start = Instant::now();
+    for expr in ["10^1000000", "(2/3)^300000", "(-3/10)^-200001"] {
+        assert!(evaluate(expr, &mut context).is_ok(), "{}", expr);
+    }
+    let duration = start.elapsed();
+    assert!(duration.as_secs() < 10, "large powers took {:?}", duration);
+    assert_eq!(evaluate("(-2/3)^-3", &mut context), Ok(rational(-27, 8)));
+}
+
+#[test]
+fn test_real_inputs_give_real_results() {
+    let mut context = Context::new();
+    for expr in [
+        "sin(1)",
+        "cos(2)",
+        "atan(3)",
+        "asin(0.5)",
+        "acos(-1/3)",
+        "tanh(1)",
+        "ln(2)",
+        "log(5)",
+        "2^0.5",
+        "pmt(0.05, 10, 1000)",
+        "std(1, 2, 4)",
+    ] {
+        match evaluate(expr, &mut context) {
+            Ok(Number::Float(_)) => {}
+            other => panic!("Expected Float for {}, got {:?}", expr, other),
+        }
+    }
+    // Reals order and round like reals
+    assert!(eval_bool("sin(1) < 1", &mut context));
+    match evaluate("round(sin(1), 2)", &mut context) {
+        Ok(Number::Float(f)) => assert_eq!(f, 0.84),
+        other => panic!("Expected Float, got {:?}", other),
+    }
+    assert_eq!(
+        eval_int("band(floor(7.5), 3)", &mut context),
+        BigInt::from(3)
+    );
+
+    // Complex only where the real function is undefined
+    match evaluate("ln(-1)", &mut context) {
+        Ok(Number::Complex(c)) => assert_eq!(c.im, std::f64::consts::PI),
+        other => panic!("Expected Complex, got {:?}", other),
     }
+    assert!(matches!(
+        evaluate("asin(2)", &mut context),
+        Ok(Number::Complex(_))
+    ));
+    assert!(matches!(
+        evaluate("(-2)^0.5", &mut context),
+        Ok(Number::Complex(_))
+    ));
+    assert!(matches!(
+        evaluate("sinh(1 + 2i)", &mut context),
+        Ok(Number::Complex(_))
+    ));
 }
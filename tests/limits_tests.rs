@@ -0,0 +1,184 @@
+use neocalc_core::engine::evaluate_on_thread;
+use neocalc_core::engine::limits::{CancellationToken, EvalLimits};
+use neocalc_core::engine::native::NativeFunction;
+use neocalc_core::{Context, EngineError, Number, evaluate};
+use num_bigint::BigInt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+fn is_limit(res: &Result<Number, EngineError>) -> bool {
+    matches!(res, Err(EngineError::LimitExceeded(_)))
+}
+
+#[test]
+fn test_unbounded_recursion_is_stopped() {
+    let mut context = Context::new();
+    evaluate("f(n) = f(n + 1)", &mut context).unwrap();
+    assert!(is_limit(&evaluate("f(0)", &mut context)));
+
+    // The context is usable again afterwards
+    evaluate("g(n) = if(n, 1 + g(n - 1), 0)", &mut context).unwrap();
+    assert_eq!(
+        evaluate("g(20)", &mut context),
+        Ok(Number::Integer(BigInt::from(20)))
+    );
+}
+
+#[test]
+fn test_deep_recursion_uses_a_clean_error() {
+    let mut context = Context::new();
+    evaluate("g(n) = if(n, 1 + g(n - 1), 0)", &mut context).unwrap();
+    assert!(is_limit(&evaluate("g(100000)", &mut context)));
+}
+
+#[test]
+fn test_default_depth_allows_moderate_user_recursion() {
+    let mut context = Context::new();
+    evaluate("f(n) = if(n, n * f(n - 1), 1)", &mut context).unwrap();
+    assert_eq!(
+        evaluate("f(150) == fact(150)", &mut context),
+        Ok(Number::Boolean(true))
+    );
+    assert!(is_limit(&evaluate("f(1000)", &mut context)));
+}
+
+#[test]
+fn test_evaluate_runs_on_the_calling_thread() {
+    let threads = Arc::new(Mutex::new(Vec::new()));
+    let mut context = Context::new();
+    let seen = Arc::clone(&threads);
+    context.register_native(NativeFunction::new("here", move |_args, _ctx| {
+        seen.lock().unwrap().push(std::thread::current().id());
+        Ok(Number::Integer(BigInt::from(0)))
+    }));
+    evaluate("here()", &mut context).unwrap();
+    evaluate_on_thread("here()", &mut context).unwrap();
+    let threads = threads.lock().unwrap();
+    assert_eq!(threads[0], std::thread::current().id());
+    assert_ne!(threads[1], std::thread::current().id());
+}
+
+#[test]
+fn test_own_thread_allows_deep_user_recursion() {
+    let mut context = Context::new();
+    context.limits.max_depth = 100_000;
+    evaluate("f(n) = if(n, n * f(n - 1), 1)", &mut context).unwrap();
+    assert_eq!(
+        evaluate_on_thread("f(5000) == fact(5000)", &mut context),
+        Ok(Number::Boolean(true))
+    );
+    evaluate("g(n) = if(n, 1 + g(n - 1), 0)", &mut context).unwrap();
+    assert!(is_limit(&evaluate_on_thread("g(100000)", &mut context)));
+    // The context comes back with what the evaluation stored in it
+    evaluate_on_thread("x = 5", &mut context).unwrap();
+    assert_eq!(
+        evaluate("x", &mut context),
+        Ok(Number::Integer(BigInt::from(5)))
+    );
+}
+
+#[test]
+fn test_step_limit() {
+    let mut context = Context::new();
+    context.limits.max_steps = 1_000;
+    evaluate(
+        "fib(n) = if(n < 2, n, fib(n - 1) + fib(n - 2))",
+        &mut context,
+    )
+    .unwrap();
+    assert!(is_limit(&evaluate("fib(20)", &mut context)));
+    assert!(evaluate("fib(5)", &mut context).is_ok());
+}
+
+#[test]
+fn test_integer_size_limit() {
+    let mut context = Context::new();
+    assert!(is_limit(&evaluate("2^4000000000", &mut context)));
+    assert!(is_limit(&evaluate("(2^4000000)^1000", &mut context)));
+    assert!(is_limit(&evaluate("1000000!", &mut context)));
+    assert!(is_limit(&evaluate("fact(1000000)", &mut context)));
+    assert!(is_limit(&evaluate("lsh(1, 10^8)", &mut context)));
+    assert!(evaluate("lsh(0, 10^8)", &mut context).is_ok());
+
+    // Trivial bases never grow
+    assert_eq!(
+        evaluate("1^(10^30)", &mut context),
+        Ok(Number::Integer(BigInt::from(1)))
+    );
+    assert_eq!(
+        evaluate("(-1)^(10^30 + 1)", &mut context),
+        Ok(Number::Integer(BigInt::from(-1)))
+    );
+
+    context.limits.max_bits = 64;
+    assert!(is_limit(&evaluate("2^64", &mut context)));
+    assert!(is_limit(&evaluate("2^40 * 2^40", &mut context)));
+    assert!(is_limit(&evaluate("21!", &mut context)));
+    // Builtin results are checked too
+    assert!(is_limit(&evaluate(
+        "det([[2^40, 0], [0, 2^40]])",
+        &mut context
+    )));
+    assert!(evaluate("2^62", &mut context).is_ok());
+    assert!(evaluate("20!", &mut context).is_ok());
+}
+
+#[test]
+fn test_time_limit() {
+    let mut context = Context::new();
+    context.limits = EvalLimits {
+        timeout: Some(Duration::from_millis(50)),
+        ..EvalLimits::unlimited()
+    };
+    context.limits.max_depth = 192;
+    evaluate(
+        "spin(n) = if(n, spin(n - 1) + spin(n - 1), 0)",
+        &mut context,
+    )
+    .unwrap();
+    assert!(is_limit(&evaluate("spin(60)", &mut context)));
+}
+
+#[test]
+fn test_cancellation_from_another_thread() {
+    let mut context = Context::new();
+    context.limits = EvalLimits {
+        max_depth: 192,
+        ..EvalLimits::unlimited()
+    };
+    let token: CancellationToken = context.cancel.clone();
+    evaluate(
+        "spin(n) = if(n, spin(n - 1) + spin(n - 1), 0)",
+        &mut context,
+    )
+    .unwrap();
+
+    let handle = std::thread::spawn(move || evaluate("spin(60)", &mut context));
+    std::thread::sleep(Duration::from_millis(50));
+    token.cancel();
+    assert_eq!(handle.join().unwrap(), Err(EngineError::Cancelled));
+}
+
+#[test]
+fn test_iferror_does_not_swallow_aborts() {
+    let mut context = Context::new();
+    assert!(is_limit(&evaluate(
+        "iferror(2^4000000000, 0)",
+        &mut context
+    )));
+}
+
+#[test]
+fn test_precise_functions_are_bounded() {
+    let mut context = Context::new();
+    context.set_precision(Some(50)).unwrap();
+    // Reducing the argument would take 3000 extra digits of pi
+    assert!(is_limit(&evaluate("sin(10^3000)", &mut context)));
+    assert!(is_limit(&evaluate("tan(-10^3000)", &mut context)));
+    // Tiny arguments need no extra digits at all
+    assert!(evaluate("sinh(1e-3000)", &mut context).is_ok());
+    assert!(evaluate("atan(1e-3000)", &mut context).is_ok());
+    // Within the cap the series still stop at the deadline
+    context.limits.timeout = Some(Duration::from_millis(1));
+    assert!(is_limit(&evaluate("sin(10^1999)", &mut context)));
+}
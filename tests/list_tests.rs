@@ -0,0 +1,125 @@
+mod common;
+
+use common::eval_str;
+use neocalc_core::engine::limits::EvalLimits;
+use neocalc_core::utils::format_number;
+use neocalc_core::{Context, EngineError, Number, evaluate};
+
+#[test]
+fn test_list_literals_and_indexing() {
+    let mut context = Context::new();
+    assert_eq!(eval_str("[1, 2 + 3, 1/2]", &mut context), "[1, 5, 1/2]");
+    assert_eq!(eval_str("[]", &mut context), "[]");
+    evaluate("xs = [10, 20, 30]", &mut context).unwrap();
+    assert_eq!(eval_str("xs[1] + xs[3]", &mut context), "40");
+    assert_eq!(eval_str("[[1, 2], [3, 4]][2][1]", &mut context), "3");
+    assert_eq!(eval_str("len(xs)", &mut context), "3");
+    assert!(matches!(
+        evaluate("xs[4]", &mut context),
+        Err(EngineError::DomainError(_))
+    ));
+    assert!(matches!(
+        evaluate("xs[0]", &mut context),
+        Err(EngineError::DomainError(_))
+    ));
+    assert!(matches!(
+        evaluate("len(5)", &mut context),
+        Err(EngineError::TypeMismatch(..))
+    ));
+}
+
+#[test]
+fn test_elementwise_arithmetic() {
+    let mut context = Context::new();
+    assert_eq!(
+        eval_str("[1, 2, 3] + [10, 20, 30]", &mut context),
+        "[11, 22, 33]"
+    );
+    assert_eq!(eval_str("2 * [1, 2, 3]", &mut context), "[2, 4, 6]");
+    assert_eq!(eval_str("[1, 2] / 4", &mut context), "[1/4, 1/2]");
+    assert_eq!(eval_str("[1, 2, 3]^2", &mut context), "[1, 4, 9]");
+    assert_eq!(eval_str("-[1, -2]", &mut context), "[-1, 2]");
+    assert_eq!(eval_str("[3, 4]!", &mut context), "[6, 24]");
+    assert!(matches!(
+        evaluate("[1, 2] + [1, 2, 3]", &mut context),
+        Err(EngineError::DimensionMismatch(_))
+    ));
+    assert_eq!(
+        evaluate("[1, 2] == [1, 2]", &mut context),
+        Ok(Number::from(true))
+    );
+    assert!(matches!(
+        evaluate("[1, 2] < 3", &mut context),
+        Err(EngineError::TypeMismatch(..))
+    ));
+}
+
+#[test]
+fn test_scalar_functions_map_over_lists() {
+    let mut context = Context::new();
+    assert_eq!(eval_str("sqrt([4, 9, 16])", &mut context), "[2, 3, 4]");
+    assert_eq!(eval_str("abs([-1, 2, -3])", &mut context), "[1, 2, 3]");
+    assert_eq!(
+        format_number(
+            evaluate("round([1.25, 2.5], 1)", &mut context).unwrap(),
+            true
+        ),
+        "[1.3, 2.5]"
+    );
+    assert!(matches!(
+        evaluate("nthroot([8, 27], [3, 3, 3])", &mut context),
+        Err(EngineError::DimensionMismatch(_))
+    ));
+}
+
+#[test]
+fn test_range() {
+    let mut context = Context::new();
+    assert_eq!(eval_str("range(1, 10, 2)", &mut context), "[1, 3, 5, 7, 9]");
+    assert_eq!(eval_str("range(1, 4)", &mut context), "[1, 2, 3, 4]");
+    assert_eq!(eval_str("range(5, 1, -2)", &mut context), "[5, 3, 1]");
+    assert_eq!(
+        eval_str("range(0, 1, 1/4)", &mut context),
+        "[0, 1/4, 1/2, 3/4, 1]"
+    );
+    assert_eq!(eval_str("len(range(0, 1, 0.1))", &mut context), "11");
+    assert_eq!(eval_str("range(3, 1)", &mut context), "[]");
+    assert!(matches!(
+        evaluate("range(1, 2, 0)", &mut context),
+        Err(EngineError::DomainError(_))
+    ));
+
+    context.limits = EvalLimits {
+        max_elements: 100,
+        ..EvalLimits::default()
+    };
+    assert!(matches!(
+        evaluate("range(1, 1000)", &mut context),
+        Err(EngineError::LimitExceeded(_))
+    ));
+}
+
+#[test]
+fn test_aggregates_accept_lists() {
+    let mut context = Context::new();
+    evaluate("data = [2, 4, 4, 4, 5, 5, 7, 9]", &mut context).unwrap();
+    assert_eq!(eval_str("mean(data)", &mut context), "5");
+    assert_eq!(eval_str("median(data)", &mut context), "9/2");
+    assert_eq!(eval_str("mean(data, 14)", &mut context), "6");
+    assert_eq!(eval_str("var(range(1, 5))", &mut context), "5/2");
+    assert_eq!(
+        format_number(
+            evaluate("npv(0.1, [100, 100])", &mut context).unwrap(),
+            true
+        ),
+        format_number(evaluate("npv(0.1, 100, 100)", &mut context).unwrap(), true)
+    );
+    assert_eq!(
+        evaluate("and([1, 1, 0])", &mut context),
+        Ok(Number::from(false))
+    );
+    assert_eq!(
+        evaluate("or([0, 0, 1])", &mut context),
+        Ok(Number::from(true))
+    );
+}
@@ -0,0 +1,164 @@
+mod common;
+
+use common::eval_str;
+use neocalc_core::{Context, EngineError, evaluate};
+
+#[test]
+fn test_matrix_literals() {
+    let mut context = Context::new();
+    assert_eq!(eval_str("[1, 2; 3, 4]", &mut context), "[1, 2; 3, 4]");
+    assert_eq!(eval_str("[1, 2;]", &mut context), "[1, 2;]");
+    assert_eq!(eval_str("[1; 2]", &mut context), "[1; 2]");
+    assert_eq!(
+        eval_str("matrix([[1, 2], [3, 4]]) == [1, 2; 3, 4]", &mut context),
+        "true"
+    );
+    evaluate("A = [1, 2; 3, 4]", &mut context).unwrap();
+    assert_eq!(eval_str("A[2]", &mut context), "[3, 4]");
+    assert_eq!(eval_str("A[2][1]", &mut context), "3");
+    assert!(matches!(
+        evaluate("[1, 2; 3]", &mut context),
+        Err(EngineError::DimensionMismatch(_))
+    ));
+    assert!(matches!(
+        evaluate("[[1], 2; 3, 4]", &mut context),
+        Err(EngineError::TypeMismatch(..))
+    ));
+}
+
+#[test]
+fn test_matrix_arithmetic() {
+    let mut context = Context::new();
+    evaluate("A = [1, 2; 3, 4]", &mut context).unwrap();
+    assert_eq!(eval_str("A + A", &mut context), "[2, 4; 6, 8]");
+    assert_eq!(eval_str("A - 1", &mut context), "[0, 1; 2, 3]");
+    assert_eq!(eval_str("2 * A", &mut context), "[2, 4; 6, 8]");
+    assert_eq!(eval_str("A / 2", &mut context), "[1/2, 1; 3/2, 2]");
+    // Elements that divide evenly come out as integers, not n/1 fractions
+    assert_eq!(
+        evaluate("[2, 4; 6, 8] / 2", &mut context).unwrap(),
+        evaluate("A", &mut context).unwrap()
+    );
+    assert_eq!(
+        evaluate("[2, 4] / 2", &mut context).unwrap(),
+        evaluate("[1, 2]", &mut context).unwrap()
+    );
+    assert_eq!(eval_str("A * A", &mut context), "[7, 10; 15, 22]");
+    assert_eq!(eval_str("A * [1, 1]", &mut context), "[3, 7]");
+    assert_eq!(eval_str("[1, 1] * A", &mut context), "[4, 6]");
+    assert_eq!(eval_str("A ^ 3", &mut context), "[37, 54; 81, 118]");
+    assert_eq!(eval_str("A ^ 0", &mut context), "[1, 0; 0, 1]");
+    assert_eq!(eval_str("A ^ -1 * A", &mut context), "[1, 0; 0, 1]");
+    assert_eq!(eval_str("-A", &mut context), "[-1, -2; -3, -4]");
+    assert_eq!(eval_str("abs(-A)", &mut context), "[1, 2; 3, 4]");
+    assert!(matches!(
+        evaluate("A + [1, 2, 3; 4, 5, 6]", &mut context),
+        Err(EngineError::DimensionMismatch(_))
+    ));
+    assert!(matches!(
+        evaluate("A * [1, 2, 3]", &mut context),
+        Err(EngineError::DimensionMismatch(_))
+    ));
+    assert!(matches!(
+        evaluate("1 / A", &mut context),
+        Err(EngineError::TypeMismatch(..))
+    ));
+}
+
+#[test]
+fn test_exact_linear_algebra() {
+    let mut context = Context::new();
+    evaluate("A = [2, 1; 1, 1]", &mut context).unwrap();
+    assert_eq!(
+        eval_str("transpose([1, 2, 3; 4, 5, 6])", &mut context),
+        "[1, 4; 2, 5; 3, 6]"
+    );
+    assert_eq!(eval_str("det(A)", &mut context), "1");
+    assert_eq!(eval_str("det([1, 2; 3, 4])", &mut context), "-2");
+    assert_eq!(eval_str("det([1, 2; 2, 4])", &mut context), "0");
+    assert_eq!(eval_str("inv(A)", &mut context), "[1, -1; -1, 2]");
+    assert_eq!(
+        eval_str("inverse([2, 0; 0, 4])", &mut context),
+        "[1/2, 0; 0, 1/4]"
+    );
+    assert_eq!(eval_str("rank([1, 2; 2, 4])", &mut context), "1");
+    assert_eq!(eval_str("rank(identity(3))", &mut context), "3");
+    assert_eq!(
+        eval_str("rref([1, 2, 3; 4, 5, 6])", &mut context),
+        "[1, 0, -1; 0, 1, 2]"
+    );
+    assert_eq!(eval_str("solve(A, [3, 2])", &mut context), "[1, 1]");
+    assert_eq!(
+        eval_str("solve([1, 1; 1, -1], [1; 0])", &mut context),
+        "[1/2; 1/2]"
+    );
+    assert_eq!(eval_str("eye(2)", &mut context), "[1, 0; 0, 1]");
+    assert!(matches!(
+        evaluate("inv([1, 2; 2, 4])", &mut context),
+        Err(EngineError::DomainError(_))
+    ));
+    assert!(matches!(
+        evaluate("det([1, 2, 3])", &mut context),
+        Err(EngineError::TypeMismatch(..))
+    ));
+    assert!(matches!(
+        evaluate("det([1, 2, 3; 4, 5, 6])", &mut context),
+        Err(EngineError::DimensionMismatch(_))
+    ));
+}
+
+#[test]
+fn test_float_matrices() {
+    let mut context = Context::new();
+    let det = evaluate("det([0.5, 1.5; 2.5, 3.5])", &mut context)
+        .unwrap()
+        .to_f64()
+        .unwrap();
+    assert!((det + 2.0).abs() < 1e-12);
+    assert_eq!(eval_str("rank([1.0, 2.0; 2.0, 4.0])", &mut context), "1");
+}
+
+#[test]
+fn test_eigenvalues() {
+    let mut context = Context::new();
+    assert_eq!(
+        eval_str("eigenvalues([2, 1; 1, 2])", &mut context),
+        "[1, 3]"
+    );
+    assert_eq!(
+        eval_str("eig([2, 0, 0; 0, 3, 4; 0, 4, 9])", &mut context),
+        "[1, 2, 11]"
+    );
+    assert_eq!(eval_str("eig([1, 1; 0, 1])", &mut context), "[1, 1]");
+    assert_eq!(eval_str("eig([0, -1; 1, 0])", &mut context), "[-1i, 1i]");
+
+    let values = evaluate("eig([1, 2; 3, 4])", &mut context).unwrap();
+    let expected = [(5.0 - 33f64.sqrt()) / 2.0, (5.0 + 33f64.sqrt()) / 2.0];
+    match values {
+        neocalc_core::Number::List(items) => {
+            for (item, expected) in items.iter().zip(expected) {
+                assert!((item.to_f64().unwrap() - expected).abs() < 1e-12);
+            }
+        }
+        other => panic!("expected a list, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_irrational_eigenvalues() {
+    let mut context = Context::new();
+    // Companion matrix of λ³ - 2: one real root and a complex pair
+    let values = evaluate("eig([0, 0, 2; 1, 0, 0; 0, 1, 0])", &mut context).unwrap();
+    let neocalc_core::Number::List(items) = values else {
+        panic!("expected a list");
+    };
+    assert_eq!(items.len(), 3);
+    assert!((items[2].to_f64().unwrap() - 2f64.cbrt()).abs() < 1e-12);
+    let pair = items[0].to_complex();
+    assert!((pair.re + 2f64.cbrt() / 2.0).abs() < 1e-12);
+    assert!(pair.im < 0.0);
+    assert!(matches!(
+        evaluate("eig(identity(11))", &mut context),
+        Err(EngineError::DomainError(_))
+    ));
+}
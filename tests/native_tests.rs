@@ -0,0 +1,138 @@
+use neocalc_core::engine::native::NativeFunction;
+use neocalc_core::{Context, EngineError, Number, evaluate};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+fn int(n: i64) -> Number {
+    Number::Integer(n.into())
+}
+
+#[test]
+fn test_native_reads_host_data() {
+    let cells: Arc<Mutex<HashMap<i64, Number>>> = Arc::new(Mutex::new(HashMap::new()));
+    cells.lock().unwrap().insert(1, int(40));
+
+    let mut context = Context::new();
+    let model = Arc::clone(&cells);
+    context.register_native(
+        NativeFunction::new("cell", move |args, _ctx| {
+            let index = args[0]
+                .to_f64()
+                .ok_or_else(|| EngineError::TypeMismatch("index".into(), "complex".into()))?;
+            model
+                .lock()
+                .unwrap()
+                .get(&(index as i64))
+                .cloned()
+                .ok_or_else(|| EngineError::DomainError("empty cell".into()))
+        })
+        .params(&["index"]),
+    );
+
+    assert_eq!(evaluate("cell(1) + 2", &mut context), Ok(int(42)));
+    // The closure sees later changes to the host's data
+    cells.lock().unwrap().insert(1, int(1));
+    assert_eq!(evaluate("cell(1) + 2", &mut context), Ok(int(3)));
+    assert!(matches!(
+        evaluate("cell(2)", &mut context),
+        Err(EngineError::DomainError(_))
+    ));
+}
+
+#[test]
+fn test_native_arity_is_checked() {
+    let mut context = Context::new();
+    let native = NativeFunction::new("total", |args, _ctx| {
+        Ok(args.iter().cloned().fold(int(0), |a, b| a + b))
+    })
+    .params(&["values"])
+    .variadic(1);
+    assert_eq!(native.signature(), "total(values...)");
+    context.register_native(native);
+
+    assert_eq!(evaluate("total(1, 2, 3)", &mut context), Ok(int(6)));
+    assert_eq!(
+        evaluate("total()", &mut context),
+        Err(EngineError::ArgumentCount {
+            name: "total".into(),
+            min: 1,
+            max: None,
+            got: 0,
+        })
+    );
+}
+
+#[test]
+fn test_natives_take_precedence_over_builtins() {
+    let mut context = Context::new();
+    context.register_native(NativeFunction::new("sqrt", |_args, _ctx| Ok(int(-1))).params(&["x"]));
+    context.register_native(
+        NativeFunction::new("if", |_args, _ctx| Ok(int(7))).params(&["a", "b", "c"]),
+    );
+    assert_eq!(evaluate("sqrt(4)", &mut context), Ok(int(-1)));
+    assert_eq!(evaluate("if(1, 2, 3)", &mut context), Ok(int(7)));
+
+    assert!(context.unregister_native("sqrt").is_some());
+    assert_eq!(evaluate("sqrt(4)", &mut context), Ok(int(2)));
+}
+
+#[test]
+fn test_native_names_are_case_insensitive() {
+    let mut context = Context::new();
+    context.register_native(
+        NativeFunction::new("cell", |args, _ctx| Ok(args[0].clone())).params(&["i"]),
+    );
+    assert_eq!(evaluate("CELL(1)", &mut context), Ok(int(1)));
+    assert_eq!(evaluate("Cell(2)", &mut context), Ok(int(2)));
+    assert_eq!(
+        evaluate("CELL(x) = x", &mut context),
+        Err(EngineError::NativeRedefinition("CELL".into()))
+    );
+    // A native registered in capitals still takes precedence over `sqrt`
+    context.register_native(NativeFunction::new("SQRT", |_args, _ctx| Ok(int(-1))).params(&["x"]));
+    assert_eq!(evaluate("sqrt(4)", &mut context), Ok(int(-1)));
+    assert!(context.unregister_native("Sqrt").is_some());
+    assert_eq!(evaluate("SQRT(4)", &mut context), Ok(int(2)));
+}
+
+#[test]
+fn test_user_definitions_cannot_shadow_natives_by_default() {
+    let mut context = Context::new();
+    context.register_native(NativeFunction::new("rate_now", |_args, _ctx| Ok(int(5))));
+    assert_eq!(
+        evaluate("rate_now() = 1", &mut context),
+        Err(EngineError::NativeRedefinition("rate_now".into()))
+    );
+    assert_eq!(evaluate("rate_now()", &mut context), Ok(int(5)));
+
+    context.register_native(
+        NativeFunction::new("twice", |args, _ctx| Ok(args[0].clone() * int(2)))
+            .params(&["x"])
+            .allow_shadowing(),
+    );
+    assert_eq!(evaluate("twice(4)", &mut context), Ok(int(8)));
+    evaluate("twice(x) = x + x + 1", &mut context).unwrap();
+    assert_eq!(evaluate("twice(4)", &mut context), Ok(int(9)));
+}
+
+#[test]
+fn test_natives_are_per_context() {
+    let mut context = Context::new();
+    context.register_native(NativeFunction::new("answer", |_args, _ctx| Ok(int(42))));
+    assert_eq!(
+        evaluate("answr()", &mut context),
+        Err(EngineError::UnknownFunction(
+            "answr".into(),
+            Some("answer".into())
+        ))
+    );
+
+    let mut other = Context::new();
+    assert!(evaluate("answer()", &mut other).is_err());
+
+    // Closures are not part of a saved session
+    let json = serde_json::to_string(&context).unwrap();
+    let mut restored: Context = serde_json::from_str(&json).unwrap();
+    assert!(restored.native("answer").is_none());
+    assert!(evaluate("answer()", &mut restored).is_err());
+}
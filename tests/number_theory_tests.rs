@@ -0,0 +1,188 @@
+mod common;
+
+use common::eval_str;
+use neocalc_core::engine::limits::EvalLimits;
+use neocalc_core::{Context, EngineError, Number, evaluate};
+
+#[test]
+fn test_divisibility_and_counting() {
+    let mut context = Context::new();
+    let cases = [
+        ("gcd(12, 18)", "6"),
+        ("gcd(-12, 18, 27)", "3"),
+        ("gcd([0, 0])", "0"),
+        ("lcm(4, 6, 10)", "60"),
+        ("lcm(3, 0)", "0"),
+        ("nCr(5, 2)", "10"),
+        ("ncr(100, 50)", "100891344545564193334812497256"),
+        ("nCr(3, 5)", "0"),
+        ("nPr(5, 2)", "20"),
+        ("perm(10, 0)", "1"),
+        ("binomial(6, 3)", "20"),
+        ("binomial(-1, 3)", "-1"),
+        ("binomial(1/2, 2)", "-1/8"),
+        ("binomial(5, -1)", "0"),
+        ("nCr([4, 5, 6], 2)", "[6, 10, 15]"),
+    ];
+    for (expr, expected) in cases {
+        assert_eq!(eval_str(expr, &mut context), expected, "{}", expr);
+    }
+    for expr in ["gcd(2.5, 5)", "nCr(-1, 2)"] {
+        assert!(
+            matches!(
+                evaluate(expr, &mut context),
+                Err(EngineError::DomainError(_))
+            ),
+            "{}",
+            expr
+        );
+    }
+}
+
+#[test]
+fn test_modular_arithmetic() {
+    let mut context = Context::new();
+    let cases = [
+        ("mod_pow(2, 10, 1000)", "24"),
+        ("mod_pow(3, 10^18, 1000000007)", "246336683"),
+        ("powmod(3, -1, 7)", "5"),
+        ("mod_pow(-2, 3, 5)", "2"),
+        ("mod_inv(3, 7)", "5"),
+        ("mod_inv(-3, 7)", "2"),
+        ("modinv(10, 17)", "12"),
+    ];
+    for (expr, expected) in cases {
+        assert_eq!(eval_str(expr, &mut context), expected, "{}", expr);
+    }
+    for expr in ["mod_inv(2, 4)", "mod_pow(2, 3, 0)", "mod_pow(2, -1, 4)"] {
+        assert!(
+            matches!(
+                evaluate(expr, &mut context),
+                Err(EngineError::DomainError(_))
+            ),
+            "{}",
+            expr
+        );
+    }
+}
+
+#[test]
+fn test_primes() {
+    let mut context = Context::new();
+    for (n, prime) in [
+        ("2", true),
+        ("1", false),
+        ("-7", false),
+        ("561", false),
+        ("7919", true),
+        ("3215031751", false),
+        ("2^61 - 1", true),
+        ("2^89 - 1", true),
+        ("2^89 + 1", false),
+    ] {
+        assert_eq!(
+            evaluate(&format!("isprime({})", n), &mut context),
+            Ok(Number::from(prime)),
+            "{}",
+            n
+        );
+    }
+    assert_eq!(eval_str("nextprime(1)", &mut context), "2");
+    assert_eq!(eval_str("nextprime(13)", &mut context), "17");
+    assert_eq!(
+        eval_str("nextprime(10^20)", &mut context),
+        "100000000000000000039"
+    );
+}
+
+#[test]
+fn test_factoring() {
+    let mut context = Context::new();
+    let cases = [
+        ("factor(360)", "[2, 2, 2, 3, 3, 5]"),
+        ("factor(-12)", "[-1, 2, 2, 3]"),
+        ("factor(1)", "[]"),
+        ("factor(97)", "[97]"),
+        ("factor(360, \"product\")", "\"2^3 * 3^2 * 5\""),
+        ("factor(-1, \"product\")", "\"-1\""),
+        // Two 10-digit primes need Pollard's rho
+        ("factor(1000000007 * 998244353)", "[998244353, 1000000007]"),
+        ("factor((2^31 - 1)^2)", "[2147483647, 2147483647]"),
+        ("totient(36)", "12"),
+        ("totient(1)", "1"),
+        ("totient(1000000007)", "1000000006"),
+        ("divisors(12)", "[1, 2, 3, 4, 6, 12]"),
+        ("divisors(-9)", "[1, 3, 9]"),
+        ("divisors(1)", "[1]"),
+    ];
+    for (expr, expected) in cases {
+        assert_eq!(eval_str(expr, &mut context), expected, "{}", expr);
+    }
+    // The product form reads back as the number
+    let product = evaluate("factor(2^10 * 3^5 * 7, \"product\")", &mut context).unwrap();
+    let Number::Text(product) = product else {
+        panic!("{:?}", product)
+    };
+    assert_eq!(eval_str(&product, &mut context), "1741824");
+    for expr in [
+        "factor(0)",
+        "totient(0)",
+        "divisors(0)",
+        "factor(6, \"sum\")",
+    ] {
+        assert!(
+            matches!(
+                evaluate(expr, &mut context),
+                Err(EngineError::DomainError(_))
+            ),
+            "{}",
+            expr
+        );
+    }
+}
+
+#[test]
+fn test_fibonacci() {
+    let mut context = Context::new();
+    let cases = [
+        ("fib(0)", "0"),
+        ("fib(1)", "1"),
+        ("fib(10)", "55"),
+        ("fib(100)", "354224848179261915075"),
+        ("fib(-1)", "1"),
+        ("fib(-8)", "-21"),
+        ("fib([1, 2, 3, 4])", "[1, 1, 2, 3]"),
+    ];
+    for (expr, expected) in cases {
+        assert_eq!(eval_str(expr, &mut context), expected, "{}", expr);
+    }
+}
+
+#[test]
+fn test_large_results_respect_limits() {
+    let mut context = Context::new();
+    context.limits = EvalLimits {
+        max_bits: 10_000,
+        ..EvalLimits::default()
+    };
+    for expr in ["fib(10^6)", "nCr(10^6, 5 * 10^5)", "nPr(10^6, 10^5)"] {
+        assert!(
+            matches!(
+                evaluate(expr, &mut context),
+                Err(EngineError::LimitExceeded(_))
+            ),
+            "{}",
+            expr
+        );
+    }
+    assert!(evaluate("fib(10^4)", &mut context).is_ok());
+}
+
+#[test]
+fn test_large_binomials() {
+    let mut context = Context::new();
+    // Pascal's rule on coefficients of about 20000 bits
+    let pascal = "nCr(2 * 10^4, 10^4) == nCr(2 * 10^4 - 1, 10^4 - 1) + nCr(2 * 10^4 - 1, 10^4)";
+    assert_eq!(eval_str(pascal, &mut context), "true");
+    assert_eq!(eval_str("nPr(10^4, 10^4) == (10^4)!", &mut context), "true");
+}
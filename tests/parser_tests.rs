@@ -0,0 +1,79 @@
+use neocalc_core::engine::errors::ParseError;
+use neocalc_core::engine::parser::parse;
+use neocalc_core::{Context, EngineError, evaluate};
+
+fn parse_error(expression: &str) -> ParseError {
+    match parse(expression) {
+        Err(EngineError::ParserError(e)) => e,
+        other => panic!("Expected ParserError for {:?}, got {:?}", expression, other),
+    }
+}
+
+#[test]
+fn test_missing_paren_points_at_end() {
+    let e = parse_error("2 * (3 + 4");
+    assert_eq!(e.span, 10..10);
+    assert_eq!(e.expected, vec!["')'".to_string()]);
+    assert_eq!(e.found, None);
+    assert_eq!(e.to_string(), "expected ')', found end of input");
+}
+
+#[test]
+fn test_unexpected_operator_span() {
+    let e = parse_error("1 + * 2");
+    assert_eq!(e.span, 4..5);
+    assert_eq!(e.found.as_deref(), Some("*"));
+    assert_eq!(e.expected, vec!["expression".to_string()]);
+}
+
+#[test]
+fn test_bad_argument_separator() {
+    let e = parse_error("f(1 2");
+    assert_eq!(e.span, 4..5);
+    assert_eq!(e.expected.len(), 2);
+    assert_eq!(e.to_string(), "expected ',' or ')', found '2'");
+}
+
+#[test]
+fn test_invalid_parameter_name() {
+    let e = parse_error("f(x, 2 + y) = x");
+    assert_eq!(e.span, 5..10);
+    assert_eq!(e.found.as_deref(), Some("2 + y"));
+}
+
+#[test]
+fn test_unknown_character() {
+    let e = parse_error("3 $ 4");
+    assert_eq!(e.span, 2..3);
+    assert_eq!(e.found.as_deref(), Some("$"));
+}
+
+#[test]
+fn test_render_caret() {
+    let source = "1 + * 2";
+    let rendered = parse_error(source).render(source);
+    assert_eq!(rendered, "1 + * 2\n    ^\nexpected expression, found '*'");
+
+    let source = "f(x, 2 + y) = x";
+    let rendered = parse_error(source).render(source);
+    let lines: Vec<&str> = rendered.lines().collect();
+    assert_eq!(lines[1], "     ^^^^^");
+}
+
+#[test]
+fn test_render_counts_characters_not_bytes() {
+    let source = "x·2 + )";
+    let e = parse_error(source);
+    // '·' is two bytes in UTF-8 but a single column
+    assert_eq!(e.span, 1..3);
+    assert_eq!(e.render(source).lines().nth(1), Some(" ^"));
+}
+
+#[test]
+fn test_evaluate_surfaces_parse_error() {
+    let mut context = Context::new();
+    match evaluate("(1 + 2", &mut context) {
+        Err(EngineError::ParserError(e)) => assert_eq!(e.span, 6..6),
+        other => panic!("Expected ParserError, got {:?}", other),
+    }
+}
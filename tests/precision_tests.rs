@@ -0,0 +1,224 @@
+mod common;
+
+use common::eval_decimal;
+use neocalc_core::engine::bigfloat::BigFloat;
+use neocalc_core::{Context, Number, evaluate};
+
+fn precise(digits: u32) -> Context {
+    let mut context = Context::new();
+    context.set_precision(Some(digits)).unwrap();
+    context
+}
+
+#[test]
+fn test_default_mode_uses_machine_floats() {
+    let mut context = Context::new();
+    assert_eq!(context.precision(), None);
+    assert!(matches!(
+        evaluate("0.1 + 0.2", &mut context),
+        Ok(Number::Float(_))
+    ));
+}
+
+#[test]
+fn test_decimal_literals_are_exact() {
+    let mut context = precise(30);
+    assert_eq!(eval_decimal("0.1 + 0.2", &mut context), "0.3");
+    assert_eq!(
+        evaluate("0.1 + 0.2 == 0.3", &mut context),
+        Ok(Number::from(true))
+    );
+    assert_eq!(eval_decimal("1.5 * 4", &mut context), "6");
+}
+
+#[test]
+fn test_constants_at_precision() {
+    let mut context = precise(50);
+    assert_eq!(
+        eval_decimal("pi", &mut context),
+        "3.1415926535897932384626433832795028841971693993751"
+    );
+    assert_eq!(
+        eval_decimal("e", &mut context),
+        "2.7182818284590452353602874713526624977572470937"
+    );
+    assert_eq!(
+        eval_decimal("phi", &mut context),
+        "1.6180339887498948482045868343656381177203091798058"
+    );
+}
+
+#[test]
+fn test_roots_and_logarithms() {
+    let mut context = precise(50);
+    assert_eq!(
+        eval_decimal("sqrt(2)", &mut context),
+        "1.4142135623730950488016887242096980785696718753769"
+    );
+    assert_eq!(
+        eval_decimal("ln(2)", &mut context),
+        "0.69314718055994530941723212145817656807550013436026"
+    );
+    assert_eq!(eval_decimal("log(1000)", &mut context), "3");
+    assert_eq!(
+        eval_decimal("2^0.5", &mut context),
+        "1.4142135623730950488016887242096980785696718753769"
+    );
+    // Negative arguments keep their complex results
+    assert!(matches!(
+        evaluate("sqrt(-4)", &mut context),
+        Ok(Number::Complex(_))
+    ));
+}
+
+#[test]
+fn test_trigonometry_at_precision() {
+    let mut context = precise(40);
+    assert_eq!(
+        eval_decimal("sin(1)", &mut context),
+        "0.8414709848078965066525023216302989996226"
+    );
+    assert_eq!(
+        eval_decimal("atan(1) * 4", &mut context),
+        "3.141592653589793238462643383279502884197"
+    );
+    assert_eq!(
+        eval_decimal("asin(0.5) * 6", &mut context),
+        "3.141592653589793238462643383279502884197"
+    );
+    assert_eq!(eval_decimal("sin(pi)", &mut context), "0");
+    assert_eq!(eval_decimal("cos(pi)", &mut context), "-1");
+    // Angles far below the working precision are not rounded to zero
+    assert_eq!(eval_decimal("sin(10^-20)", &mut context), "1e-20");
+    assert_eq!(
+        eval_decimal("sin(pi * 10^-40)", &mut context),
+        "3.141592653589793238462643383279502884197e-40"
+    );
+    assert_eq!(
+        eval_decimal("tanh(0.5)", &mut context),
+        "0.4621171572600097585023184836436725487303"
+    );
+}
+
+#[test]
+fn test_statistics_at_precision() {
+    let mut context = precise(30);
+    assert_eq!(
+        eval_decimal("std(1, 2, 3, 4)", &mut context),
+        "1.29099444873580562839308846659"
+    );
+}
+
+#[test]
+fn test_precision_is_validated() {
+    let mut context = Context::new();
+    assert!(context.set_precision(Some(0)).is_err());
+    assert!(context.set_precision(Some(1_000_000)).is_err());
+    assert!(context.set_precision(None).is_ok());
+}
+
+#[test]
+fn test_bigfloat_formatting() {
+    let big = BigFloat::parse("1.25e30", 20).unwrap();
+    assert_eq!(big.to_string(), "1.25e30");
+    let small = BigFloat::parse("-0.000012", 20).unwrap();
+    assert_eq!(small.to_string(), "-0.000012");
+    assert_eq!(BigFloat::parse("2.500", 20).unwrap().to_string(), "2.5");
+}
+
+#[test]
+fn test_long_literals_keep_their_digits() {
+    let mut context = precise(30);
+    assert_eq!(
+        eval_decimal("1.000000000000000000000000001 - 1", &mut context),
+        "1e-27"
+    );
+    assert_eq!(
+        eval_decimal("ln(1.00000000000000000001)", &mut context),
+        "9.99999999999999999995e-21"
+    );
+
+    // Without a working precision they are ordinary floats
+    let mut context = Context::new();
+    assert_eq!(
+        evaluate("1.000000000000000000000000001", &mut context),
+        Ok(Number::Float(1.0))
+    );
+}
+
+// Leading 490 significant digits of the reference values
+const LN_2: &str = concat!(
+    "0.693147180559945309417232121458176568075500134360255254120680009493393621969694",
+    "71560586332699641868754200148102057068573368552023575813055703267075163507596193",
+    "07275708283714351903070386238916734711233501153644979552391204751726815749320651",
+    "55524734139525882950453007095326366642654104239157814952043740430385500801944170",
+    "64167151864471283996817178454695702627163106454615025720740248163777338963855069",
+    "52606683411372738737229289564935470257626520988596932019650585547647033067936544",
+    "325476327449",
+);
+
+const LOG_2: &str = concat!(
+    "0.301029995663981195213738894724493026768189881462108541310427461127108189274424",
+    "50948692725211818617204068447719143099537909476788113352350599969233370469557506",
+    "45029642541934026618197343116029435011839028981785826171544395318619290463538846",
+    "99520239310849612462540400263312594621478845847318282672683982326196542793507631",
+    "31754835092713896494691778576891805079000759954808781545971458503196487762612249",
+    "22908291181909514989971716198604776765000678205179125573286286683420004029205098",
+    "370845722248",
+);
+
+const SQRT_2: &str = concat!(
+    "1.414213562373095048801688724209698078569671875376948073176679737990732478462107",
+    "03885038753432764157273501384623091229702492483605585073721264412149709993583141",
+    "32226659275055927557999505011527820605714701095599716059702745345968620147285174",
+    "18640889198609552329230484308714321450839762603627995251407989687253396546331808",
+    "82964062061525835239505474575028775996172983557522033753185701135437460340849884",
+    "71603868999706990048150305440277903164542478230684929369186215805784631115966687",
+    "130130156185",
+);
+
+const SINH_1: &str = concat!(
+    "1.175201193643801456882381850595600815155717981334095870229565413013307567304323",
+    "89560711745208962339184041953332757953235678521890191945728213684035288324842382",
+    "29689806253026878572974193778037894530156457975748559863812033933000211943571349",
+    "39276747928783808639778091594382288709437918371232250230643268348982186865900736",
+    "85971387655364877379154362084919505984009856969575046017073476460455599148776422",
+    "54885845736315892502135438245978143162874775249565935186798861968577094170390099",
+    "113872716177",
+);
+
+const COSH_1: &str = concat!(
+    "1.543080634815243778477905620757061682601529112365863704737402214710769063049223",
+    "69896426472643554303558704685860442352756503219469470958629076349394237734720691",
+    "51633480026408029059364105029494057980033657762593319443209506958499136898103743",
+    "05484712739298456160390385817471453636004518736306827514348801202720574972705524",
+    "47167070644710327114228293944841167727310213963295866727301228262614098572154591",
+    "62042522453939258584439199475134380734969475319971032521055637731102374474158960",
+    "765443652715",
+);
+
+fn assert_digits(expr: &str, expected: &str, context: &mut Context) {
+    let result = eval_decimal(expr, context);
+    assert!(
+        result.starts_with(expected),
+        "{} = {}, expected {}...",
+        expr,
+        result,
+        expected
+    );
+}
+
+#[test]
+fn test_logarithms_and_powers_past_f64_range() {
+    // 10^precision no longer fits an f64 here
+    let mut context = precise(500);
+    assert_digits("ln(2)", LN_2, &mut context);
+    assert_digits("log(2)", LOG_2, &mut context);
+    assert_digits("2^0.5", SQRT_2, &mut context);
+    assert_digits("sinh(1)", SINH_1, &mut context);
+    assert_digits("cosh(1)", COSH_1, &mut context);
+
+    let mut context = precise(1000);
+    assert_digits("ln(2)", LN_2, &mut context);
+    assert_eq!(eval_decimal("sinh(1) + cosh(1) - e", &mut context), "0");
+}
@@ -0,0 +1,153 @@
+use neocalc_core::engine::functions::{Category, apply, list_functions, lookup};
+use neocalc_core::i18n::LocalizationService;
+use neocalc_core::{Context, EngineError, Number, evaluate};
+
+#[test]
+fn test_signatures() {
+    assert_eq!(lookup("sin").unwrap().signature(), "sin(angle)");
+    assert_eq!(lookup("round").unwrap().signature(), "round(x, [digits])");
+    assert_eq!(lookup("mean").unwrap().signature(), "mean(values...)");
+    assert_eq!(lookup("npv").unwrap().signature(), "npv(rate, values...)");
+    assert_eq!(
+        lookup("dms").unwrap().signature(),
+        "dms(degrees, [minutes], [seconds])"
+    );
+    assert_eq!(lookup("true").unwrap().signature(), "true()");
+}
+
+#[test]
+fn test_arity_is_checked_centrally() {
+    let mut context = Context::new();
+    assert_eq!(
+        evaluate("sin(1, 2)", &mut context),
+        Err(EngineError::ArgumentCount {
+            name: "sin".into(),
+            min: 1,
+            max: Some(1),
+            got: 2,
+        })
+    );
+    let err = evaluate("round()", &mut context).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Function 'round' expects 1 to 2 argument(s), got 0"
+    );
+    let err = evaluate("var(1)", &mut context).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Function 'var' expects at least 2 argument(s), got 1"
+    );
+    assert!(evaluate("dms(30, 15)", &mut context).is_ok());
+
+    // Direct callers of `apply` get the same validation
+    assert!(matches!(
+        apply("band", vec![Number::Integer(1.into())], &context),
+        Err(EngineError::ArgumentCount { .. })
+    ));
+}
+
+#[test]
+fn test_special_forms_use_registry_arity() {
+    let mut context = Context::new();
+    assert!(matches!(
+        evaluate("if(1, 2)", &mut context),
+        Err(EngineError::ArgumentCount { got: 2, .. })
+    ));
+    assert!(matches!(
+        evaluate("iferror(1)", &mut context),
+        Err(EngineError::ArgumentCount { got: 1, .. })
+    ));
+}
+
+#[test]
+fn test_list_functions() {
+    let functions = list_functions();
+    assert!(functions.iter().any(|f| f.name == "sqrt"));
+    // Grouped by category, then sorted by name
+    let keys: Vec<_> = functions.iter().map(|f| (f.category, f.name)).collect();
+    let mut sorted = keys.clone();
+    sorted.sort();
+    assert_eq!(keys, sorted);
+    assert_eq!(lookup("fv").unwrap().category, Category::Financial);
+}
+
+#[test]
+fn test_every_function_is_documented() {
+    let english = LocalizationService::new("en-US");
+    for function in list_functions() {
+        assert_ne!(
+            english.get(function.description),
+            function.description,
+            "{} has no description",
+            function.name
+        );
+        assert_ne!(
+            english.get(function.category.key()),
+            function.category.key()
+        );
+    }
+    let portuguese = LocalizationService::new("pt-BR");
+    assert_eq!(portuguese.get("fn-sqrt"), "Raiz quadrada");
+}
+
+#[test]
+fn test_lookup_ignores_case_and_follows_aliases() {
+    let mut context = Context::new();
+    for (expr, expected) in [
+        ("ABS(-3)", "3"),
+        ("Floor(2.5)", "2"),
+        ("CEILING(2.5)", "3"),
+        ("IF(0, 1, 2)", "2"),
+        ("lm(3 + 4i)", "4"),
+    ] {
+        let result = evaluate(expr, &mut context).unwrap();
+        assert_eq!(neocalc_core::utils::format_number(result, true), expected);
+    }
+    assert_eq!(lookup("COSIN").unwrap().name, "acos");
+    assert_eq!(lookup("ceiling").unwrap().aliases, &["ceiling"]);
+}
+
+#[test]
+fn test_functions_are_listed_once() {
+    let functions = list_functions();
+    let mut names: Vec<_> = functions.iter().map(|f| f.name.to_lowercase()).collect();
+    let count = names.len();
+    names.sort();
+    names.dedup();
+    assert_eq!(names.len(), count);
+    assert!(
+        !functions
+            .iter()
+            .any(|f| f.name == "ABS" || f.name == "cosin")
+    );
+}
+
+#[test]
+fn test_unknown_function_suggestions() {
+    let mut context = Context::new();
+    assert_eq!(
+        evaluate("sqr(4)", &mut context),
+        Err(EngineError::UnknownFunction(
+            "sqr".into(),
+            Some("sqrt".into())
+        ))
+    );
+    let err = evaluate("mena(1, 2)", &mut context).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Function 'mena' is not known; did you mean 'mean'?"
+    );
+    // User definitions are suggested too
+    evaluate("area(r) = pi * r^2", &mut context).unwrap();
+    assert_eq!(
+        evaluate("arae(2)", &mut context),
+        Err(EngineError::UnknownFunction(
+            "arae".into(),
+            Some("area".into())
+        ))
+    );
+    assert_eq!(
+        evaluate("frobnicate(1)", &mut context),
+        Err(EngineError::UnknownFunction("frobnicate".into(), None))
+    );
+}
@@ -0,0 +1,121 @@
+use neocalc_core::engine::angle::AngleUnit;
+use neocalc_core::engine::word::WordSize;
+use neocalc_core::session_manager::AppSessionManager;
+
+// A session file in the temp directory, removed when the test ends, whether
+// it passed or not
+struct TempFile(String);
+
+impl TempFile {
+    fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("neocalc-test-{}.json", uuid::Uuid::new_v4()));
+        TempFile(path.to_string_lossy().into_owned())
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+fn temp_manager() -> (TempFile, AppSessionManager) {
+    let file = TempFile::new();
+    let manager = AppSessionManager::new(file.0.clone());
+    (file, manager)
+}
+
+fn enter(manager: &AppSessionManager, expression: &str) {
+    manager.clear();
+    manager.input(expression.to_string());
+}
+
+#[test]
+fn test_base_conversions_round_trip() {
+    let (_file, manager) = temp_manager();
+
+    for (expression, hex, oct, bin) in [
+        ("255", "0xFF", "0o377", "0b11111111"),
+        ("0 - 255", "-0xFF", "-0o377", "-0b11111111"),
+    ] {
+        enter(&manager, expression);
+        assert_eq!(manager.convert_to_hex(), hex);
+        assert_eq!(manager.evaluate(), expression.replace("0 - ", "-"));
+
+        enter(&manager, expression);
+        assert_eq!(manager.convert_to_oct(), oct);
+        assert_eq!(manager.evaluate(), expression.replace("0 - ", "-"));
+
+        enter(&manager, expression);
+        assert_eq!(manager.convert_to_bin(), bin);
+        assert_eq!(manager.evaluate(), expression.replace("0 - ", "-"));
+    }
+}
+
+#[test]
+fn test_word_size_shows_twos_complement_and_overflow() {
+    let (_file, manager) = temp_manager();
+    assert_eq!(manager.get_word_size(), None);
+    manager.set_word_size(Some(WordSize::new(8, true).unwrap()));
+    assert_eq!(manager.get_word_size(), "i8".parse().ok());
+
+    enter(&manager, "0 - 1");
+    assert_eq!(manager.convert_to_hex(), "0xFF");
+    assert_eq!(manager.evaluate(), "-1");
+    assert!(!manager.overflowed());
+
+    enter(&manager, "100 + 100");
+    assert_eq!(manager.evaluate(), "-56");
+    assert!(manager.overflowed());
+    enter(&manager, "100 + 27");
+    assert_eq!(manager.convert_to_bin(), "0b1111111");
+    assert!(!manager.overflowed());
+
+    manager.set_word_size(None);
+    enter(&manager, "100 + 100");
+    assert_eq!(manager.evaluate(), "200");
+}
+
+#[test]
+fn test_angle_unit_is_saved_per_session() {
+    let file = TempFile::new();
+    let path = file.0.clone();
+
+    let manager = AppSessionManager::new(path.clone());
+    assert_eq!(manager.get_angle_unit(), AngleUnit::Radians);
+    manager.set_angle_unit(AngleUnit::Degrees);
+    enter(&manager, "sin(90)");
+    assert_eq!(manager.evaluate(), "1");
+
+    // A new session starts in radians
+    let first = manager
+        .get_sessions_overview()
+        .into_iter()
+        .find(|s| s.is_active)
+        .unwrap()
+        .id;
+    let second = manager.create_session();
+    manager.switch_session(second);
+    assert_eq!(manager.get_angle_unit(), AngleUnit::Radians);
+
+    // The setting survives a reload
+    let reloaded = AppSessionManager::new(path.clone());
+    reloaded.switch_session(first);
+    assert_eq!(reloaded.get_angle_unit(), AngleUnit::Degrees);
+}
+
+#[test]
+fn test_boolean_variables_survive_a_reload() {
+    let file = TempFile::new();
+    let path = file.0.clone();
+
+    let manager = AppSessionManager::new(path.clone());
+    enter(&manager, "flag = 3 > 2");
+    assert_eq!(manager.evaluate(), "true");
+
+    let reloaded = AppSessionManager::new(path.clone());
+    enter(&reloaded, "flag");
+    assert_eq!(reloaded.evaluate(), "true");
+    enter(&reloaded, "not(flag)");
+    assert_eq!(reloaded.evaluate(), "false");
+}
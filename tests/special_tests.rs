@@ -0,0 +1,174 @@
+mod common;
+
+use common::eval_str;
+use neocalc_core::{Context, EngineError, Number, evaluate};
+use num::complex::Complex64;
+
+// Checks a real or complex result to a relative tolerance
+fn approx(expr: &str, context: &mut Context, re: f64, im: f64) {
+    let value = match evaluate(expr, context) {
+        Ok(Number::Complex(c)) => c,
+        Ok(Number::Float(f)) if im == 0.0 => Complex64::new(f, 0.0),
+        other => panic!("{} gave {:?}", expr, other),
+    };
+    let expected = Complex64::new(re, im);
+    assert!(
+        (value - expected).norm() <= 1e-12 * expected.norm(),
+        "{} = {} instead of {}",
+        expr,
+        value,
+        expected
+    );
+}
+
+#[test]
+fn test_gamma_family() {
+    let mut context = Context::new();
+    let cases = [
+        ("gamma(0.5)", 1.772453850905516, 0.0),
+        ("gamma(-1.5)", 2.363271801207355, 0.0),
+        ("gamma(170.5)", 5.56209241456e+305, 0.0),
+        ("gamma(1 + i)", 0.498015668118356, -0.1549498283018107),
+        ("lgamma(100)", 359.1342053695754, 0.0),
+        ("lgamma(-2.5)", -0.056243716497674051, 0.0),
+        ("lgamma(3 + 4i)", -1.7566267846037841, 4.742664438034658),
+        ("beta(2.5, 1.5)", 0.19634954084936208, 0.0),
+        ("beta(-0.5, 2)", -4.0, 0.0),
+        ("beta(1 + i, 2)", 0.1, -0.3),
+        ("digamma(1)", -0.5772156649015329, 0.0),
+        ("digamma(0.5)", -1.9635100260214235, 0.0),
+        ("digamma(-0.5)", 0.03648997397857652, 0.0),
+        ("digamma(10)", 2.251752589066721, 0.0),
+        ("digamma(1 + i)", 0.09465032062247698, 1.0766740474685812),
+    ];
+    for (expr, re, im) in cases {
+        approx(expr, &mut context, re, im);
+    }
+    // Exact where the value is rational
+    assert_eq!(eval_str("gamma(5)", &mut context), "24");
+    assert_eq!(eval_str("beta(2, 3)", &mut context), "1/12");
+    assert_eq!(eval_str("beta(0.5, -0.5)", &mut context), "0");
+    for expr in [
+        "gamma(0)",
+        "gamma(-3)",
+        "lgamma(-1)",
+        "digamma(0)",
+        "beta(-1, 3)",
+    ] {
+        assert!(
+            matches!(
+                evaluate(expr, &mut context),
+                Err(EngineError::DomainError(_))
+            ),
+            "{}",
+            expr
+        );
+    }
+}
+
+#[test]
+fn test_factorial_of_non_integers() {
+    let mut context = Context::new();
+    approx("2.5!", &mut context, 3.3233509704478426, 0.0);
+    approx("(1/2)!", &mut context, 0.886226925452758, 0.0);
+    approx("(-0.5)!", &mut context, 1.772453850905516, 0.0);
+    approx(
+        "fact(1 + i)",
+        &mut context,
+        0.6529654964201668,
+        0.34306583981654536,
+    );
+    assert_eq!(eval_str("5!", &mut context), "120");
+    assert!(matches!(
+        evaluate("(-1.0)!", &mut context),
+        Err(EngineError::DomainError(_))
+    ));
+    assert!(matches!(
+        evaluate("(-2)!", &mut context),
+        Err(EngineError::DomainError(_))
+    ));
+}
+
+#[test]
+fn test_error_function() {
+    let mut context = Context::new();
+    let cases = [
+        ("erf(0.5)", 0.5204998778130465, 0.0),
+        ("erf(-2)", -0.9953222650189527, 0.0),
+        ("erf(4)", 0.9999999845827421, 0.0),
+        ("erfc(5)", 1.537459794428035e-12, 0.0),
+        ("erfc(-1)", 1.842700792949715, 0.0),
+        ("erfc(0.3)", 0.6713732405408726, 0.0),
+        ("erf(1 + i)", 1.3161512816979476, 0.19045346923783469),
+        ("erf(4 + i)", 1.0000000150962953, 3.794032969089071e-8),
+        ("erfc(4 + i)", -1.509629525002696e-8, -3.794032969089071e-8),
+        ("erf(2i)", 0.0, 18.564802414575553),
+    ];
+    for (expr, re, im) in cases {
+        approx(expr, &mut context, re, im);
+    }
+    assert_eq!(evaluate("erf(0)", &mut context), Ok(Number::Float(0.0)));
+}
+
+#[test]
+fn test_zeta() {
+    let mut context = Context::new();
+    let cases = [
+        ("zeta(2)", 1.6449340668482264, 0.0),
+        ("zeta(3)", 1.2020569031595943, 0.0),
+        ("zeta(0.5)", -1.4603545088095868, 0.0),
+        ("zeta(-1)", -0.08333333333333333, 0.0),
+        ("zeta(-2.5)", 0.008516928777850331, 0.0),
+        ("zeta(2 + 3i)", 0.7980219851462758, -0.1137443080529385),
+    ];
+    for (expr, re, im) in cases {
+        approx(expr, &mut context, re, im);
+    }
+    assert_eq!(eval_str("zeta(0)", &mut context), "-1/2");
+    assert_eq!(eval_str("zeta(-4)", &mut context), "0");
+    assert!(matches!(
+        evaluate("zeta(1)", &mut context),
+        Err(EngineError::DomainError(_))
+    ));
+}
+
+#[test]
+fn test_bessel_functions() {
+    let mut context = Context::new();
+    let cases = [
+        ("besselj(0, 1)", 0.7651976865579666),
+        ("besselj(1, 10)", 0.04347274616886144),
+        ("besselj(5, 10)", -0.23406152818679364),
+        ("besselj(-3, 2)", -0.12894324947440205),
+        ("besselj(2, -3)", 0.4860912605858911),
+        ("besselj(0, 100)", 0.019985850304223122),
+        ("besselj(40, 5)", 8.702241617388818e-33),
+        ("bessely(0, 1)", 0.08825696421567696),
+        ("bessely(1, 10)", 0.24901542420695388),
+        ("bessely(2, 1)", -1.6506826068162544),
+        ("bessely(-1, 30)", -0.08442557066174723),
+        ("bessely(3, 50)", 0.06445912206022249),
+    ];
+    for (expr, expected) in cases {
+        approx(expr, &mut context, expected, 0.0);
+    }
+    assert_eq!(
+        evaluate("besselj(3, 0)", &mut context),
+        Ok(Number::Float(0.0))
+    );
+    for expr in [
+        "besselj(1.5, 2)",
+        "besselj(1, 2i)",
+        "bessely(0, 0)",
+        "bessely(1, -2)",
+    ] {
+        assert!(
+            matches!(
+                evaluate(expr, &mut context),
+                Err(EngineError::DomainError(_))
+            ),
+            "{}",
+            expr
+        );
+    }
+}
@@ -0,0 +1,145 @@
+mod common;
+
+use common::eval_str;
+use neocalc_core::utils::format_number;
+use neocalc_core::{Context, EngineError, Number, evaluate};
+
+fn check(context: &mut Context, cases: &[(&str, &str)]) {
+    for (expr, expected) in cases {
+        assert_eq!(eval_str(expr, context), *expected, "{}", expr);
+    }
+}
+
+#[test]
+fn test_population_and_sample_variants() {
+    let mut context = Context::new();
+    evaluate("d = [3, 4, 5, 2, 3, 4, 5, 6, 4, 7]", &mut context).unwrap();
+    check(
+        &mut context,
+        &[
+            ("var.p(d)", "201/100"),
+            ("var.s(d)", "67/30"),
+            ("VAR.S(d) == var(d)", "true"),
+            ("stdev.p(1, 3)", "1"),
+            (
+                "stdev.s(2, 4, 4, 4, 5, 5, 7, 9) == std(2, 4, 4, 4, 5, 5, 7, 9)",
+                "true",
+            ),
+            ("mode(1, 3, 3, 2, 2)", "2"),
+            ("mode(d)", "4"),
+            ("kurt(d)", "-4770/31423"),
+            ("skew(1, 2, 3)", "0"),
+            ("geomean(2, 8)", "4"),
+            ("geomean(1/2, 2, 4)", "1.5874010519681994"),
+            // Exact inputs are rooted exactly, or rounded once
+            ("geomean(1, 2)", "1.4142135623730951"),
+            ("geomean(1/2, 1/4, 1)", "1/2"),
+            ("geomean(10^400, 10^402) == 10^401", "true"),
+            ("abs(geomean(1.5, 6) - 3) < 1e-12", "true"),
+            ("geomean(10^400, 1.5) > 0", "true"),
+            ("harmean(1, 2, 4)", "12/7"),
+        ],
+    );
+    let skew = evaluate("skew(d)", &mut context).unwrap();
+    assert_eq!(format_number(skew, false), "0.3595430714067971");
+    assert!(matches!(
+        evaluate("mode(1, 2, 3)", &mut context),
+        Err(EngineError::DomainError(_))
+    ));
+    assert!(matches!(
+        evaluate("geomean(1, -2)", &mut context),
+        Err(EngineError::DomainError(_))
+    ));
+    assert!(matches!(
+        evaluate("skew(2, 2, 2)", &mut context),
+        Err(EngineError::DivisionByZero)
+    ));
+}
+
+#[test]
+fn test_percentiles_and_quartiles() {
+    let mut context = Context::new();
+    evaluate("d = [4, 2, 3, 1]", &mut context).unwrap();
+    check(
+        &mut context,
+        &[
+            ("percentile(d, 3/10)", "19/10"),
+            ("percentile(d, 0.3)", "1.9"),
+            ("percentile(d, 0.3, \"exclusive\")", "1.5"),
+            ("percentile(d, 1)", "4"),
+            ("quartile(d, 1)", "7/4"),
+            ("quartile(d, 1, \"exclusive\")", "5/4"),
+            ("quartile(d, 1, \"lower\")", "1"),
+            ("quartile(d, 1, \"higher\")", "2"),
+            ("quartile(d, 1, \"nearest\")", "2"),
+            ("quartile(d, 1, \"midpoint\")", "3/2"),
+            ("quartile(d, 2) == median(d)", "true"),
+            ("percentile([1, 2, 3, 4, 5, 6], 1/2, \"nearest\")", "3"),
+            (
+                "percentile([1, 2, 3, 4, 5, 6, 7, 8], 1/2, \"nearest\")",
+                "5",
+            ),
+        ],
+    );
+    for expr in [
+        "percentile(d, 1.5)",
+        "percentile(d, 0.1, \"exclusive\")",
+        "percentile(d, 0.5, \"cubic\")",
+        "quartile(d, 5)",
+    ] {
+        assert!(
+            matches!(
+                evaluate(expr, &mut context),
+                Err(EngineError::DomainError(_))
+            ),
+            "{}",
+            expr
+        );
+    }
+}
+
+#[test]
+fn test_regression_is_exact() {
+    let mut context = Context::new();
+    evaluate("x = [1, 2, 3, 4, 5]", &mut context).unwrap();
+    evaluate("y = [2, 4, 5, 4, 5]", &mut context).unwrap();
+    check(
+        &mut context,
+        &[
+            ("slope(y, x)", "3/5"),
+            ("intercept(y, x)", "11/5"),
+            ("rsq(y, x)", "3/5"),
+            ("forecast(6, y, x)", "29/5"),
+            ("forecast.linear([0, 10], y, x)", "[11/5, 41/5]"),
+            ("covar(x, y)", "6/5"),
+            ("covariance.s(x, y)", "3/2"),
+            ("correl(x, 2 * x + 1)", "1"),
+            ("correl(x, -x)", "-1"),
+            ("correl(x, y)", "0.7745966692414834"),
+        ],
+    );
+    // Results that come out whole are integers rather than n/1 fractions
+    for (expr, expected) in [
+        ("slope(2 * x, x)", 2),
+        ("intercept(2 * x + 1, x)", 1),
+        ("covar(x, x)", 2),
+        ("covariance.s(x, 2 * x)", 5),
+    ] {
+        assert_eq!(
+            evaluate(expr, &mut context),
+            Ok(Number::Integer(expected.into())),
+            "{}",
+            expr
+        );
+    }
+    assert_eq!(
+        evaluate("slope([1, 2], y)", &mut context),
+        Err(EngineError::DimensionMismatch(
+            "lists of length 2 and 5 passed to 'slope'".into()
+        ))
+    );
+    assert!(matches!(
+        evaluate("slope(y, [1, 1, 1, 1, 1])", &mut context),
+        Err(EngineError::DivisionByZero)
+    ));
+}
@@ -1,10 +1,6 @@
-use neocalc_core::{
-    evaluate,
-    Context,
-    Number,
-};
-use std::time::Instant;
+use neocalc_core::{Context, Number, evaluate};
 use num_bigint::BigInt;
+use std::time::Instant;
 
 #[test]
 fn stress_test_parser_depth() {
@@ -14,16 +10,19 @@ fn stress_test_parser_depth() {
     // Depth of AST ~ 1000.
     // Parser recursion depth ~ 1000.
     let n = 2000;
-    let expr: String = std::iter::repeat("1").take(n).collect::<Vec<_>>().join(" + ");
-    
+    let expr: String = std::iter::repeat_n("1", n).collect::<Vec<_>>().join(" + ");
+
     let start = Instant::now();
     let res = evaluate(&expr, &mut context);
     let duration = start.elapsed();
-    
+
     match res {
         Ok(Number::Integer(i)) => {
             assert_eq!(i, BigInt::from(n), "Sum should be equal to count");
-            println!("Parser Depth Test (N={}): {:?} - Parsed and Evaluated", n, duration);
+            println!(
+                "Parser Depth Test (N={}): {:?} - Parsed and Evaluated",
+                n, duration
+            );
         }
         Err(e) => {
             panic!("Parser failed at depth {}: {:?}", n, e);
@@ -35,42 +34,56 @@ fn stress_test_parser_depth() {
 #[test]
 fn stress_test_context_cloning_overhead() {
     let mut context = Context::new();
-    
+
     // 1. Populate context with MANY variables
     let var_count = 10_000;
     println!("Populating {} variables...", var_count);
     for i in 0..var_count {
         evaluate(&format!("v{} = {}", i, i), &mut context).unwrap();
     }
-    
+
     // 2. Define a simple function
     evaluate("f(x) = x + 1", &mut context).unwrap();
-    
+
     // 3. Call it repeatedly
     let call_count = 1000;
     let start = Instant::now();
-    for i in 0..call_count {
+    for _ in 0..call_count {
         evaluate("f(1)", &mut context).unwrap();
     }
     let duration = start.elapsed();
-    
-    println!("Context Overhead Test (Vars={}, Calls={}): {:?}", var_count, call_count, duration);
+
+    println!(
+        "Context Overhead Test (Vars={}, Calls={}): {:?}",
+        var_count, call_count, duration
+    );
     println!("Average time per call: {:?}", duration / call_count as u32);
-    
-    // If it takes > 1ms per call, it's slow. 
+
+    // If it takes > 1ms per call, it's slow.
     // cloning 10k items 1000 times = 10M copies.
 }
 
 #[test]
 fn stress_test_recursion_fib() {
     let mut context = Context::new();
-    
+
     // Define naive fibonacci: fib(n) = fib(n-1) + fib(n-2)
-    // Base cases handled by if? We don't have 'if' in Expr yet!
-    // Ah, we can't do recursion without conditionals.
-    // We only have math operators.
-    // So we can't test recursion logic per se without standard library 'if' function.
-    // Assuming we have 'if(cond, true_val, false_val)'.
-    // We don't have an 'if' function in `core_funcs`?
-    // Let's check.
+    // IF is a lazy special form, so only the taken branch recurses.
+    evaluate(
+        "fib(n) = if(n < 2, n, fib(n - 1) + fib(n - 2))",
+        &mut context,
+    )
+    .unwrap();
+
+    let start = Instant::now();
+    let res = evaluate("fib(15)", &mut context);
+    let duration = start.elapsed();
+
+    match res {
+        Ok(Number::Integer(i)) => {
+            assert_eq!(i, BigInt::from(610));
+            println!("Recursive fib(15): {:?}", duration);
+        }
+        other => panic!("Expected Integer 610, got {:?}", other),
+    }
 }
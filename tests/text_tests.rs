@@ -0,0 +1,166 @@
+mod common;
+
+use common::eval_str;
+use neocalc_core::engine::angle::AngleUnit;
+use neocalc_core::{Context, EngineError, Number, evaluate};
+
+fn text(value: &str) -> Number {
+    Number::Text(value.to_string())
+}
+
+#[test]
+fn test_string_literals() {
+    let mut context = Context::new();
+    assert_eq!(evaluate(r#""hello""#, &mut context), Ok(text("hello")));
+    assert_eq!(evaluate(r#""""#, &mut context), Ok(text("")));
+    assert_eq!(
+        evaluate(r#""say \"hi\"\n""#, &mut context),
+        Ok(text("say \"hi\"\n"))
+    );
+    assert_eq!(eval_str(r#""a\\b""#, &mut context), r#""a\\b""#);
+    evaluate(r#"name = "Ada""#, &mut context).unwrap();
+    assert_eq!(eval_str("name", &mut context), r#""Ada""#);
+    assert_eq!(eval_str("name[1]", &mut context), r#""A""#);
+    assert_eq!(eval_str(r#"["a", "b"]"#, &mut context), r#"["a", "b"]"#);
+    assert!(evaluate(r#""unterminated"#, &mut context).is_err());
+}
+
+#[test]
+fn test_concatenation_and_comparison() {
+    let mut context = Context::new();
+    assert_eq!(
+        evaluate(r#""n = " & 1 + 2"#, &mut context),
+        Ok(text("n = 3"))
+    );
+    assert_eq!(
+        evaluate(r#""ratio " & 1/4"#, &mut context),
+        Ok(text("ratio 0.25"))
+    );
+    assert_eq!(
+        evaluate(r#"1 & 2 == "12""#, &mut context),
+        Ok(Number::from(true))
+    );
+    assert_eq!(eval_str(r#""abc" < "abd""#, &mut context), "true");
+    assert_eq!(eval_str(r#""abc" == "abc""#, &mut context), "true");
+    assert_eq!(eval_str(r#""1" == 1"#, &mut context), "false");
+    assert_eq!(
+        evaluate(r#"if(2 > 1, "yes", "no")"#, &mut context),
+        Ok(text("yes"))
+    );
+    for expr in [r#""a" + 1"#, r#"-"a""#, r#"sqrt("4")"#, r#""a" < 1"#] {
+        assert!(
+            matches!(
+                evaluate(expr, &mut context),
+                Err(EngineError::TypeMismatch(..))
+            ),
+            "{}",
+            expr
+        );
+    }
+}
+
+#[test]
+fn test_len_char_and_code() {
+    let mut context = Context::new();
+    assert_eq!(eval_str(r#"len("héllo")"#, &mut context), "5");
+    assert_eq!(evaluate("char(65)", &mut context), Ok(text("A")));
+    assert_eq!(eval_str(r#"code("π")"#, &mut context), "960");
+    assert_eq!(eval_str("char([104, 105])", &mut context), r#"["h", "i"]"#);
+    assert_eq!(eval_str(r#"code(char(8364))"#, &mut context), "8364");
+    assert!(matches!(
+        evaluate("char(-1)", &mut context),
+        Err(EngineError::DomainError(_))
+    ));
+    assert!(matches!(
+        evaluate(r#"code("")"#, &mut context),
+        Err(EngineError::DomainError(_))
+    ));
+}
+
+#[test]
+fn test_text_formats() {
+    let mut context = Context::new();
+    let cases = [
+        ("text(1/4)", "0.25"),
+        (r#"text(3.14159, "0.00")"#, "3.14"),
+        (r#"text(2.675, "0.00")"#, "2.68"),
+        (r##"text(1234567.891, "#,##0.00")"##, "1,234,567.89"),
+        (r#"text(-0.001, "0.00")"#, "0.00"),
+        (r#"text(-12.5, "0")"#, "-13"),
+        (r#"text(7, "000")"#, "007"),
+        (r#"text(0.5, "0.0#")"#, "0.5"),
+        (r#"text(0.125, "0.0#")"#, "0.13"),
+        (r#"text(0.256, "0.0%")"#, "25.6%"),
+        (r##"text(1234.5, "$#,##0")"##, "$1,235"),
+        (r#"text(12345, "0.00E+00")"#, "1.23E+04"),
+        (r#"text(0.000123, "0.0E+0")"#, "1.2E-4"),
+        (r#"text(9.99, "0.0E+00")"#, "1.0E+01"),
+        (r#"text(255, "hex")"#, "0xFF"),
+        (r#"text(-5, "bin")"#, "-0b101"),
+        (r#"text(8, "OCT")"#, "0o10"),
+        (r#"text("as is", "0.00")"#, "as is"),
+    ];
+    for (expr, expected) in cases {
+        assert_eq!(evaluate(expr, &mut context), Ok(text(expected)), "{}", expr);
+    }
+    assert!(matches!(
+        evaluate(r#"text(1.5, "hex")"#, &mut context),
+        Err(EngineError::DomainError(_))
+    ));
+    assert!(matches!(
+        evaluate(r#"text(1, "abc")"#, &mut context),
+        Err(EngineError::DomainError(_))
+    ));
+}
+
+#[test]
+fn test_text_dms() {
+    let mut context = Context::new();
+    context.angle_unit = AngleUnit::Degrees;
+    assert_eq!(
+        evaluate(r#"text(30.5, "dms")"#, &mut context),
+        Ok(text("30°30'0\""))
+    );
+    assert_eq!(
+        evaluate(r#"text(-10.2525, "dms")"#, &mut context),
+        Ok(text("-10°15'9\""))
+    );
+}
+
+#[test]
+fn test_value() {
+    let mut context = Context::new();
+    let cases = [
+        (r#"value("0x1F")"#, "31"),
+        (r#"value(" 42 ")"#, "42"),
+        (r#"value("-1.5e3")"#, "-1500"),
+        (r#"value("1_000")"#, "1000"),
+        (r#"value("36#ZZ")"#, "1295"),
+        (r#"value("12.5%")"#, "0.125"),
+        (r#"value("50%")"#, "1/2"),
+        ("value(7)", "7"),
+        (r#"value(text(255, "hex"))"#, "255"),
+    ];
+    for (expr, expected) in cases {
+        assert_eq!(eval_str(expr, &mut context), expected, "{}", expr);
+    }
+    for expr in [r#"value("abc")"#, r#"value("1 + 2")"#, r#"value("")"#] {
+        assert!(
+            matches!(
+                evaluate(expr, &mut context),
+                Err(EngineError::DomainError(_))
+            ),
+            "{}",
+            expr
+        );
+    }
+}
+
+#[test]
+fn test_text_variables_persist() {
+    let mut context = Context::new();
+    evaluate(r#"label = "total""#, &mut context).unwrap();
+    let json = serde_json::to_string(&context).unwrap();
+    let mut restored: Context = serde_json::from_str(&json).unwrap();
+    assert_eq!(evaluate("label", &mut restored), Ok(text("total")));
+}
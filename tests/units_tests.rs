@@ -0,0 +1,200 @@
+mod common;
+
+use common::{eval_decimal, eval_str};
+use neocalc_core::utils::format_number;
+use neocalc_core::{Context, EngineError, Number, evaluate};
+
+#[test]
+fn test_unit_literals_and_prefixes() {
+    let mut context = Context::new();
+    let cases = [
+        ("5 km", "5 km"),
+        ("5km", "5 km"),
+        ("2 m^2", "2 m^2"),
+        ("(3 m)^2", "9 m^2"),
+        ("-4 s", "-4 s"),
+        ("3 meters", "3 m"),
+        ("1 GiB to MiB", "1024 MiB"),
+        ("1 kB to bit", "8000 bit"),
+        ("1 kWh to J", "3600000 J"),
+        ("250 mL to L", "(1/4) L"),
+        ("1 dam to m", "10 m"),
+        ("9.81 m/s^2 * 2 s", "19.62 m/s"),
+        ("10 / 2 s to Hz", "5 Hz"),
+        ("1 / 4 s", "(1/4) /s"),
+    ];
+    for (expr, expected) in cases {
+        assert_eq!(eval_str(expr, &mut context), expected, "{}", expr);
+    }
+}
+
+#[test]
+fn test_dimensions_combine_and_cancel() {
+    let mut context = Context::new();
+    assert_eq!(eval_decimal("5 km / 20 min", &mut context), "0.25 km/min");
+    assert_eq!(eval_str("5 km / 20 min to km/h", &mut context), "15 km/h");
+    assert_eq!(eval_str("2 km * 300 m", &mut context), "(3/5) km^2");
+    assert_eq!(eval_decimal("1 m + 50 cm", &mut context), "1.5 m");
+    assert_eq!(eval_decimal("1 ft to cm", &mut context), "30.48 cm");
+    assert_eq!(eval_str("3 ft in inch", &mut context), "36 inch");
+    // `in` is also the inch after a number and as a target
+    assert_eq!(eval_str("3 ft to in", &mut context), "36 inch");
+    assert_eq!(eval_str("3 ft in in", &mut context), "36 inch");
+    assert_eq!(eval_str("12 in to ft", &mut context), "1 ft");
+    assert_eq!(eval_str("2 in + 1 inch", &mut context), "3 inch");
+    assert_eq!(eval_str("(9 m^2)^(1/2)", &mut context), "3 m");
+    // Cancelled dimensions leave a plain number
+    assert_eq!(
+        evaluate("1 km / 1 m", &mut context),
+        Ok(Number::Integer(1000.into()))
+    );
+    assert_eq!(eval_str("1 h / 1 min", &mut context), "60");
+    assert_eq!(eval_str("2 m / 4 m", &mut context), "1/2");
+}
+
+#[test]
+fn test_dimension_mismatches() {
+    let mut context = Context::new();
+    for expr in [
+        "1 m + 1 s",
+        "1 m - 1",
+        "1 + 1 m",
+        "5 km to s",
+        "1 m < 1 s",
+        "1 kg % 1 m",
+    ] {
+        assert!(
+            matches!(
+                evaluate(expr, &mut context),
+                Err(EngineError::DimensionMismatch(_))
+            ),
+            "{}",
+            expr
+        );
+    }
+    for expr in ["5 to m", "5 m to 2", "sqrt(4 m)", "2^(1 m)", "[1 m; 2 m]"] {
+        assert!(
+            matches!(
+                evaluate(expr, &mut context),
+                Err(EngineError::TypeMismatch(..))
+            ),
+            "{}",
+            expr
+        );
+    }
+    assert!(matches!(
+        evaluate("(2 m)^(1/2)", &mut context),
+        Err(EngineError::DomainError(_))
+    ));
+    // The left operand is named first
+    assert_eq!(
+        evaluate("1 m + 1 s", &mut context),
+        Err(EngineError::DimensionMismatch("units m and s".into()))
+    );
+}
+
+#[test]
+fn test_temperatures() {
+    let mut context = Context::new();
+    let cases = [
+        ("20 °C to °F", "68 °F"),
+        ("20°C to K", "293.15 K"),
+        ("-40 degF to degC", "-40 °C"),
+        ("0 K to °F", "-459.67 °F"),
+        ("212 fahrenheit to celsius", "100 °C"),
+        // Arithmetic treats temperatures as differences
+        ("100 °C - 90 °C", "10 °C"),
+    ];
+    for (expr, expected) in cases {
+        assert_eq!(eval_decimal(expr, &mut context), expected, "{}", expr);
+    }
+    // Mixing an offset temperature with another unit is ambiguous
+    for expr in ["1 K + 1 °C", "20 °C - 5 °F", "20 °C < 300 K"] {
+        assert!(
+            matches!(
+                evaluate(expr, &mut context),
+                Err(EngineError::DimensionMismatch(_))
+            ),
+            "{}",
+            expr
+        );
+    }
+    assert_eq!(eval_decimal("1 K + 1 mK", &mut context), "1.001 K");
+}
+
+#[test]
+fn test_comparisons() {
+    let mut context = Context::new();
+    assert_eq!(
+        evaluate("1 km > 999 m", &mut context),
+        Ok(Number::from(true))
+    );
+    assert_eq!(
+        evaluate("1 mi == 1609.344 m", &mut context),
+        Ok(Number::from(true))
+    );
+    assert_eq!(
+        evaluate("60 min == 1 h", &mut context),
+        Ok(Number::from(true))
+    );
+    assert_eq!(
+        evaluate("1 m == 1 s", &mut context),
+        Ok(Number::from(false))
+    );
+    assert_eq!(eval_str("if(2 lb > 1 kg, 1, 0)", &mut context), "0");
+}
+
+#[test]
+fn test_names_and_variables() {
+    let mut context = Context::new();
+    // Unit names are units only after a number or as a conversion target
+    evaluate("x = 5", &mut context).unwrap();
+    for expr in ["m", "m * 2", "km / 2", "x / s"] {
+        assert!(
+            matches!(
+                evaluate(expr, &mut context),
+                Err(EngineError::UndefinedVariable(_))
+            ),
+            "{}",
+            expr
+        );
+    }
+    assert_eq!(eval_str("-1/2 /s to Hz", &mut context), "(-1/2) Hz");
+    assert_eq!(eval_str("2 kg m^2/s^2 to J", &mut context), "2 J");
+    // So are the units in parentheses after a number
+    assert_eq!(eval_str("3 (km/h)", &mut context), "3 km/h");
+    assert_eq!(eval_str("36 (km/h) to m/s", &mut context), "10 m/s");
+    assert_eq!(eval_str("6 / (m s)", &mut context), "6 /m/s");
+    // A unit name before '(' is still a call
+    evaluate("h(x) = x + 1", &mut context).unwrap();
+    assert_eq!(eval_str("2 h(3)", &mut context), "8");
+    // Defined variables win over units
+    evaluate("m = 3", &mut context).unwrap();
+    assert_eq!(eval_str("2 m", &mut context), "6");
+    assert_eq!(eval_str("2 km", &mut context), "2 km");
+    // Lists of quantities
+    assert_eq!(eval_str("[1, 2] * 1 s", &mut context), "[1 s, 2 s]");
+    assert_eq!(
+        eval_str("[1 h, 30 min] to min", &mut context),
+        "[60 min, 30 min]"
+    );
+}
+
+#[test]
+fn test_quantities_read_back_and_persist() {
+    let mut context = Context::new();
+    for expr in [
+        "5 km / 20 min",
+        "1 / 4 s",
+        "3 kg * 2 m^2 / 1 s^2",
+        "(1 + 2i) m",
+    ] {
+        let value = evaluate(expr, &mut context).unwrap();
+        let text = format_number(value.clone(), false);
+        assert_eq!(evaluate(&text, &mut context), Ok(value), "{}", text);
+    }
+    evaluate("trip = 42 km", &mut context).unwrap();
+    let json = serde_json::to_string(&context).unwrap();
+    let mut restored: Context = serde_json::from_str(&json).unwrap();
+    assert_eq!(eval_str("trip to m", &mut restored), "42000 m");
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use common::eval_str;
+use neocalc_core::engine::word::WordSize;
+use neocalc_core::{Context, EngineError, Number, evaluate};
+
+fn in_word(word: &str) -> Context {
+    let mut context = Context::new();
+    context.word_size = Some(word.parse().unwrap());
+    context
+}
+
+#[test]
+fn test_arithmetic_wraps() {
+    let cases = [
+        ("i8", "127 + 1", "-128", true),
+        ("i8", "-128 - 1", "127", true),
+        ("i8", "-128", "-128", false),
+        ("i8", "0xFF", "-1", false),
+        ("u8", "0 - 1", "255", true),
+        ("u8", "200 + 55", "255", false),
+        ("u8", "16 * 16", "0", true),
+        ("i16", "7 / 2", "3", false),
+        ("i16", "-7 / 2", "-3", false),
+        ("u32", "3^100", "3476558801", true),
+        ("i64", "2^63", "-9223372036854775808", true),
+        (
+            "u128",
+            "2^128 - 1",
+            "340282366920938463463374607431768211455",
+            true,
+        ),
+        ("i32", "[2147483647, 1] + 1", "[-2147483648, 2]", true),
+    ];
+    for (word, expr, expected, overflow) in cases {
+        let mut context = in_word(word);
+        assert_eq!(
+            eval_str(expr, &mut context),
+            expected,
+            "{} in {}",
+            expr,
+            word
+        );
+        assert_eq!(context.overflowed(), overflow, "{} in {}", expr, word);
+    }
+    // The flag is cleared by the next evaluation
+    let mut context = in_word("u8");
+    evaluate("255 + 1", &mut context).unwrap();
+    assert!(context.overflowed());
+    evaluate("1 + 1", &mut context).unwrap();
+    assert!(!context.overflowed());
+
+    // Non-integers are left alone
+    assert_eq!(eval_str("7 / 2.0", &mut in_word("i8")), "3.5");
+    assert_eq!(
+        evaluate("1 / 0", &mut in_word("i8")),
+        Err(EngineError::DivisionByZero)
+    );
+}
+
+#[test]
+fn test_bitwise_functions_use_the_word() {
+    let cases = [
+        ("u8", "bnot(0)", "255"),
+        ("i8", "bnot(0)", "-1"),
+        ("u16", "bnot(0x00FF)", "65280"),
+        ("u8", "lsh(0x81, 1)", "2"),
+        ("u8", "lsh(1, 100)", "0"),
+        ("i8", "rsh(-128, 7)", "-1"),
+        ("u8", "rol(0x81, 1)", "3"),
+        ("u8", "ror(1, 1)", "128"),
+        ("i8", "rol(1, 7)", "-128"),
+        ("u8", "rol(0x81, -1)", "192"),
+        ("u32", "rol(1, 33)", "2"),
+    ];
+    for (word, expr, expected) in cases {
+        let mut context = in_word(word);
+        assert_eq!(
+            eval_str(expr, &mut context),
+            expected,
+            "{} in {}",
+            expr,
+            word
+        );
+        assert!(!context.overflowed(), "{} in {}", expr, word);
+    }
+
+    // Without a word size rotations work on 64-bit signed integers
+    let mut context = Context::new();
+    assert_eq!(eval_str("rol(1, 63)", &mut context), "-9223372036854775808");
+    assert_eq!(eval_str("ror(-2, 1)", &mut context), "9223372036854775807");
+    assert!(matches!(
+        evaluate("rol(2^64, 1)", &mut context),
+        Err(EngineError::DomainError(_))
+    ));
+    assert_eq!(eval_str("bnot(0)", &mut context), "-1");
+}
+
+#[test]
+fn test_twos_complement_display() {
+    let i8 = WordSize::new(8, true).unwrap();
+    let u16 = WordSize::new(16, false).unwrap();
+    assert_eq!(i8.format_radix(&(-1).into(), 16).unwrap(), "0xFF");
+    assert_eq!(i8.format_radix(&(-128).into(), 2).unwrap(), "0b10000000");
+    assert_eq!(u16.format_radix(&255.into(), 8).unwrap(), "0o377");
+
+    let mut context = in_word("i16");
+    assert_eq!(
+        evaluate("text(-2, \"hex\")", &mut context),
+        Ok(Number::Text("0xFFFE".into()))
+    );
+}
+
+#[test]
+fn test_word_size_names_and_persistence() {
+    assert_eq!("i32".parse(), Ok(WordSize::new(32, true).unwrap()));
+    assert_eq!("U128".parse(), Ok(WordSize::new(128, false).unwrap()));
+    assert_eq!(WordSize::new(64, false).unwrap().to_string(), "u64");
+    for name in ["i12", "x8", "u", ""] {
+        assert!(name.parse::<WordSize>().is_err(), "{}", name);
+    }
+    assert!(WordSize::new(0, true).is_err());
+    let word = WordSize::new(16, true).unwrap();
+    assert_eq!(word.min(), (-32768).into());
+    assert_eq!(word.max(), 32767.into());
+
+    let context = in_word("u8");
+    let json = serde_json::to_string(&context).unwrap();
+    let mut restored: Context = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored.word_size, context.word_size);
+    assert_eq!(eval_str("250 + 10", &mut restored), "4");
+    assert!(serde_json::from_str::<WordSize>("\"i7\"").is_err());
+}